@@ -60,7 +60,7 @@ fn main() {
         // We apply the application layout
         application
             // We use the default layout engine, but you could make your own layout engine
-            .layout(&mapping, &mut DefaultLayoutEngine)
+            .layout(&mapping, &mut DefaultLayoutEngine::default())
             .unwrap();
 
         // Since interactions engines require constructed layout to process interactions we
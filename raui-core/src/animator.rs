@@ -7,8 +7,19 @@
 //! See [`Animator`] and [`AnimatorStates`] for code samples.
 //!
 //! [`WidgetContext`]: crate::widget::context::WidgetContext
-use crate::{messenger::MessageSender, widget::WidgetId, MessageData, Scalar};
+use crate::{
+    messenger::MessageSender,
+    widget::{
+        utils::{lerp, Color, Transform, Vec2},
+        WidgetId,
+    },
+    MessageData, Prefab, PrefabError, PrefabValue, PropsData, Scalar,
+};
 use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "scalar64"))]
+use std::f32::consts::PI;
+#[cfg(feature = "scalar64")]
+use std::f64::consts::PI;
 use std::{collections::HashMap, sync::mpsc::Sender};
 
 /// An error that may occur when animating a value
@@ -19,18 +30,61 @@ pub enum AnimationError {
     CouldNotWriteData,
 }
 
+/// Playback control command that can be applied to a running animation
+///
+/// Controls can be sent from widget lifecycle hooks with [`Animator::control`] or from the host
+/// with [`Application::animation_control`].
+///
+/// [`Application::animation_control`]: crate::application::Application::animation_control
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnimationControl {
+    /// Stop advancing animation time
+    Pause,
+    /// Continue advancing animation time
+    Resume,
+    /// Pause if running, resume if paused
+    TogglePause,
+    /// Jump to given time (in seconds)
+    Seek(Scalar),
+    /// Jump to given progress factor of the whole animation, in range `<0;1>`
+    SeekProgress(Scalar),
+    /// Flip current playback direction
+    Reverse,
+    /// Set playback direction, `true` means playing backwards
+    SetReversed(bool),
+    /// Set playback rate, `1` means normal speed
+    SetSpeed(Scalar),
+    /// Set whether looped animation should bounce back and forth instead of restarting
+    SetPingPong(bool),
+}
+
+/// Request sent through [`AnimationUpdate`] channel
+#[derive(Debug, Clone)]
+pub(crate) enum AnimationRequest {
+    Change(Option<Animation>),
+    Control(AnimationControl),
+}
+
 /// Handle to an animation sending channel used internally to update widget animations values in
 /// lifecycle hooks
 #[derive(Clone)]
-pub(crate) struct AnimationUpdate(Sender<(String, Option<Animation>)>);
+pub(crate) struct AnimationUpdate(Sender<(String, AnimationRequest)>);
 
 impl AnimationUpdate {
-    pub fn new(sender: Sender<(String, Option<Animation>)>) -> Self {
+    pub fn new(sender: Sender<(String, AnimationRequest)>) -> Self {
         Self(sender)
     }
 
     pub fn change(&self, name: &str, data: Option<Animation>) -> Result<(), AnimationError> {
-        if self.0.send((name.to_owned(), data)).is_err() {
+        self.send(name, AnimationRequest::Change(data))
+    }
+
+    pub fn control(&self, name: &str, control: AnimationControl) -> Result<(), AnimationError> {
+        self.send(name, AnimationRequest::Control(control))
+    }
+
+    fn send(&self, name: &str, request: AnimationRequest) -> Result<(), AnimationError> {
+        if self.0.send((name.to_owned(), request)).is_err() {
             Err(AnimationError::CouldNotWriteData)
         } else {
             Ok(())
@@ -38,6 +92,26 @@ impl AnimationUpdate {
     }
 }
 
+/// Scales animations time of widgets subtree
+///
+/// Put it in shared props of a widget to make all animations of that widget and its descendants
+/// run slower (values below `1`), faster (values above `1`) or freeze them (`0`). Nested scale
+/// replaces the one set by ancestors, so for example pause menu can freeze gameplay HUD animations
+/// while still animating itself. It is further multiplied by
+/// [`Application::animations_time_scale`].
+///
+/// [`Application::animations_time_scale`]: crate::application::Application::animations_time_scale
+#[derive(PropsData, Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct AnimationTimeScale(pub Scalar);
+
+impl Default for AnimationTimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
 /// Allows manipulating widget animations
 ///
 /// An [`Animator`] can be used inside of the [`WidgetMountOrChangeContext`] that is provided when
@@ -96,6 +170,66 @@ impl<'a> Animator<'a> {
         self.update.change(anim_id, animation)
     }
 
+    /// Apply playback control to the animation associated to a given `anim_id`
+    #[inline]
+    pub fn control(&self, anim_id: &str, control: AnimationControl) -> Result<(), AnimationError> {
+        self.update.control(anim_id, control)
+    }
+
+    /// Pause the animation associated to a given `anim_id`
+    #[inline]
+    pub fn pause(&self, anim_id: &str) -> Result<(), AnimationError> {
+        self.control(anim_id, AnimationControl::Pause)
+    }
+
+    /// Resume the animation associated to a given `anim_id`
+    #[inline]
+    pub fn resume(&self, anim_id: &str) -> Result<(), AnimationError> {
+        self.control(anim_id, AnimationControl::Resume)
+    }
+
+    /// Jump to given time (in seconds) of the animation associated to a given `anim_id`
+    #[inline]
+    pub fn seek(&self, anim_id: &str, time: Scalar) -> Result<(), AnimationError> {
+        self.control(anim_id, AnimationControl::Seek(time))
+    }
+
+    /// Jump to given progress factor of the animation associated to a given `anim_id`
+    #[inline]
+    pub fn seek_progress(&self, anim_id: &str, factor: Scalar) -> Result<(), AnimationError> {
+        self.control(anim_id, AnimationControl::SeekProgress(factor))
+    }
+
+    /// Flip playback direction of the animation associated to a given `anim_id`
+    #[inline]
+    pub fn reverse(&self, anim_id: &str) -> Result<(), AnimationError> {
+        self.control(anim_id, AnimationControl::Reverse)
+    }
+
+    /// Set playback rate of the animation associated to a given `anim_id`
+    #[inline]
+    pub fn set_speed(&self, anim_id: &str, speed: Scalar) -> Result<(), AnimationError> {
+        self.control(anim_id, AnimationControl::SetSpeed(speed))
+    }
+
+    /// Set ping-pong looping of the animation associated to a given `anim_id`
+    #[inline]
+    pub fn set_ping_pong(&self, anim_id: &str, ping_pong: bool) -> Result<(), AnimationError> {
+        self.control(anim_id, AnimationControl::SetPingPong(ping_pong))
+    }
+
+    /// Check whether or not the animation associated to a given `anim_id` is paused
+    #[inline]
+    pub fn is_paused(&self, anim_id: &str) -> bool {
+        self.states.is_paused(anim_id)
+    }
+
+    /// Check whether or not the animation associated to a given `anim_id` plays backwards
+    #[inline]
+    pub fn is_reversed(&self, anim_id: &str) -> bool {
+        self.states.is_reversed(anim_id)
+    }
+
     /// Get the current progress of the animation of a given value
     ///
     /// This will return [`None`] if the value is not currently being animated.
@@ -135,6 +269,12 @@ impl<'a> Animator<'a> {
         self.value_progress_factor(anim_id, value_name)
             .unwrap_or(0.)
     }
+
+    /// Get current value of the [`AnimationClip`] track with the given name
+    #[inline]
+    pub fn track_value(&self, anim_id: &str, track_name: &str) -> Option<AnimationTrackValue> {
+        self.states.track_value(anim_id, track_name)
+    }
 }
 
 /// The amount of progress made for a value in an animation
@@ -213,12 +353,42 @@ impl AnimatorStates {
         !self.in_progress()
     }
 
+    /// Returns whether or not _any_ of the animations for this component are either in-progress
+    /// or paused and waiting to be resumed
+    pub fn in_progress_or_paused(&self) -> bool {
+        self.0.values().any(|s| s.in_progress() || s.is_paused())
+    }
+
     /// Returns true if the widget has an animation with the given `anim_id`
     #[inline]
     pub fn has(&self, anim_id: &str) -> bool {
         self.0.contains_key(anim_id)
     }
 
+    /// Returns true if the animation with the given `anim_id` is paused
+    #[inline]
+    pub fn is_paused(&self, anim_id: &str) -> bool {
+        self.0
+            .get(anim_id)
+            .map(|s| s.is_paused())
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the animation with the given `anim_id` plays backwards
+    #[inline]
+    pub fn is_reversed(&self, anim_id: &str) -> bool {
+        self.0
+            .get(anim_id)
+            .map(|s| s.is_reversed())
+            .unwrap_or_default()
+    }
+
+    /// Get the state of the animation with the given `anim_id`
+    #[inline]
+    pub fn state(&self, anim_id: &str) -> Option<&AnimatorState> {
+        self.0.get(anim_id)
+    }
+
     /// Get the current progress of the animation of a given value
     ///
     /// This will return [`None`] if the value is not currently being animated.
@@ -265,6 +435,57 @@ impl AnimatorStates {
             .unwrap_or(0.)
     }
 
+    /// Get current value of the [`AnimationClip`] track with the given name
+    ///
+    /// This will return [`None`] if the animation does not play a clip with such track.
+    #[inline]
+    pub fn track_value(&self, anim_id: &str, track_name: &str) -> Option<AnimationTrackValue> {
+        self.0
+            .get(anim_id)
+            .and_then(|state| state.track_value(track_name))
+    }
+
+    /// Same as [`track_value`][Self::track_value] but returning `default` if there is no scalar
+    /// track with such name
+    pub fn track_scalar_or(&self, anim_id: &str, track_name: &str, default: Scalar) -> Scalar {
+        match self.track_value(anim_id, track_name) {
+            Some(AnimationTrackValue::Scalar(value)) => value,
+            _ => default,
+        }
+    }
+
+    /// Same as [`track_value`][Self::track_value] but returning `default` if there is no vector
+    /// track with such name
+    pub fn track_vec2_or(&self, anim_id: &str, track_name: &str, default: Vec2) -> Vec2 {
+        match self.track_value(anim_id, track_name) {
+            Some(AnimationTrackValue::Vec2(value)) => value,
+            _ => default,
+        }
+    }
+
+    /// Same as [`track_value`][Self::track_value] but returning `default` if there is no color
+    /// track with such name
+    pub fn track_color_or(&self, anim_id: &str, track_name: &str, default: Color) -> Color {
+        match self.track_value(anim_id, track_name) {
+            Some(AnimationTrackValue::Color(value)) => value,
+            _ => default,
+        }
+    }
+
+    /// Same as [`track_value`][Self::track_value] but returning `default` if there is no transform
+    /// track with such name
+    pub fn track_transform_or(
+        &self,
+        anim_id: &str,
+        track_name: &str,
+        default: Transform,
+    ) -> Transform {
+        match self.track_value(anim_id, track_name) {
+            Some(AnimationTrackValue::Transform(value)) => value,
+            _ => default,
+        }
+    }
+
     /// Update the animation with the given `anim_id`
     ///
     /// If `animation` is [`None`] the animation will be removed.
@@ -276,6 +497,18 @@ impl AnimatorStates {
         }
     }
 
+    /// Apply playback control to the animation with the given `anim_id`
+    ///
+    /// Returns `false` if there is no such animation.
+    pub fn control(&mut self, anim_id: &str, control: AnimationControl) -> bool {
+        if let Some(state) = self.0.get_mut(anim_id) {
+            state.control(control);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Processes the animations, updating the values of each animation baed on the progressed time
     pub(crate) fn process(
         &mut self,
@@ -294,7 +527,7 @@ impl AnimatorStates {
 /// This is most often accessed though [`AnimatorStates`] in the [`WidgetContext`].
 ///
 /// [`WidgetContext`]: crate::widget::context::WidgetContext
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnimatorState {
     #[serde(default)]
     #[serde(skip_serializing_if = "HashMap::is_empty")]
@@ -303,32 +536,145 @@ pub struct AnimatorState {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     messages: Vec<(Scalar, String)>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    events: Vec<(Scalar, AnimationEvent)>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    clips: Vec<(Scalar, AnimationClip)>,
+    #[serde(default)]
     time: Scalar,
     #[serde(default)]
     duration: Scalar,
     #[serde(default)]
     looped: bool,
+    #[serde(default)]
+    ping_pong: bool,
+    #[serde(default)]
+    paused: bool,
+    #[serde(default)]
+    reversed: bool,
+    #[serde(default = "AnimatorState::default_speed")]
+    speed: Scalar,
+}
+
+impl Default for AnimatorState {
+    fn default() -> Self {
+        Self {
+            sheet: Default::default(),
+            messages: Default::default(),
+            events: Default::default(),
+            clips: Default::default(),
+            time: 0.0,
+            duration: 0.0,
+            looped: false,
+            ping_pong: false,
+            paused: false,
+            reversed: false,
+            speed: Self::default_speed(),
+        }
+    }
 }
 
 impl AnimatorState {
+    fn default_speed() -> Scalar {
+        1.0
+    }
+
     /// Initialize a new [`AnimatorState`] given an animation
     pub(crate) fn new(animation: Animation) -> Self {
-        let mut sheet = HashMap::new();
-        let mut messages = vec![];
-        let (time, looped) = Self::include_animation(animation, &mut sheet, &mut messages, 0.0);
+        let mut result = Self::default();
+        let (time, looped, ping_pong) = result.include_animation(animation, 0.0);
         Self {
-            sheet,
-            messages,
-            time: 0.0,
             duration: time,
             looped,
+            ping_pong,
+            ..result
         }
     }
 
     /// Returns whether or not the animations is in-progress
+    ///
+    /// Paused animations and animations frozen with zero speed are not considered in-progress.
     #[inline]
     pub fn in_progress(&self) -> bool {
-        self.looped || (self.time <= self.duration && !self.sheet.is_empty())
+        !self.is_frozen() && !self.is_finished()
+    }
+
+    /// Returns whether or not the animation is paused (or frozen with zero speed) and waiting to
+    /// be resumed
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.is_frozen() && !self.is_finished()
+    }
+
+    /// Returns whether or not the animation plays backwards
+    #[inline]
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+
+    /// Returns whether or not the looped animation bounces back and forth
+    #[inline]
+    pub fn is_ping_pong(&self) -> bool {
+        self.ping_pong
+    }
+
+    /// Current playback rate
+    #[inline]
+    pub fn speed(&self) -> Scalar {
+        self.speed
+    }
+
+    /// Current animation time
+    #[inline]
+    pub fn time(&self) -> Scalar {
+        self.time
+    }
+
+    /// Total animation duration
+    #[inline]
+    pub fn duration(&self) -> Scalar {
+        self.duration
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.paused || self.speed <= 0.0
+    }
+
+    fn is_finished(&self) -> bool {
+        if self.looped {
+            return false;
+        }
+        // clips keep animation alive for their whole duration even without tracks, so their
+        // events get fired.
+        if self.sheet.is_empty() && self.clips.is_empty() {
+            return true;
+        }
+        if self.reversed {
+            self.time < 0.0
+        } else {
+            self.time > self.duration
+        }
+    }
+
+    /// Apply playback control to this animation
+    pub fn control(&mut self, control: AnimationControl) {
+        match control {
+            AnimationControl::Pause => self.paused = true,
+            AnimationControl::Resume => self.paused = false,
+            AnimationControl::TogglePause => self.paused = !self.paused,
+            AnimationControl::Seek(time) => self.seek(time),
+            AnimationControl::SeekProgress(factor) => self.seek(factor * self.duration),
+            AnimationControl::Reverse => self.reversed = !self.reversed,
+            AnimationControl::SetReversed(reversed) => self.reversed = reversed,
+            AnimationControl::SetSpeed(speed) => self.speed = speed.max(0.0),
+            AnimationControl::SetPingPong(ping_pong) => self.ping_pong = ping_pong,
+        }
+    }
+
+    fn seek(&mut self, time: Scalar) {
+        self.time = time.max(0.0).min(self.duration);
+        self.update_phases();
     }
 
     /// Returns `true` if this animation is not in-progress
@@ -372,6 +718,16 @@ impl AnimatorState {
         self.value_progress_factor(name).unwrap_or(0.)
     }
 
+    /// Get current value of the [`AnimationClip`] track with the given name
+    ///
+    /// If more than one played clip has such track, the one included last wins.
+    pub fn track_value(&self, name: &str) -> Option<AnimationTrackValue> {
+        self.clips.iter().rev().find_map(|(start, clip)| {
+            let time = (self.time - start).max(0.0).min(clip.duration());
+            clip.tracks.get(name).and_then(|track| track.sample(time))
+        })
+    }
+
     /// Processes the animations, updating the values of each animation baed on the progressed time
     pub(crate) fn process(
         &mut self,
@@ -379,35 +735,73 @@ impl AnimatorState {
         owner: &WidgetId,
         message_sender: &MessageSender,
     ) {
-        if delta_time > 0.0 {
-            if self.looped && self.time > self.duration {
-                self.time = 0.0;
+        let delta_time = delta_time * self.speed;
+        if self.paused || delta_time <= 0.0 {
+            return;
+        }
+        if self.looped {
+            if self.reversed && self.time < 0.0 {
+                if self.ping_pong {
+                    self.time = 0.0;
+                    self.reversed = false;
+                } else {
+                    self.time = self.duration;
+                }
+            } else if !self.reversed && self.time > self.duration {
+                if self.ping_pong {
+                    self.time = self.duration;
+                    self.reversed = true;
+                } else {
+                    self.time = 0.0;
+                }
             }
-            let old_time = self.time;
+        }
+        let old_time = self.time;
+        if self.reversed {
+            self.time -= delta_time;
+        } else {
             self.time += delta_time;
-            for phase in self.sheet.values_mut() {
-                phase.cached_time = (self.time - phase.start).min(phase.duration).max(0.0);
-                phase.cached_progress = if phase.duration > 0.0 {
-                    phase.cached_time / phase.duration
-                } else {
-                    0.0
-                };
+        }
+        self.update_phases();
+        for (time, message) in &self.messages {
+            let passed = if self.reversed {
+                *time < old_time && *time >= self.time
+            } else {
+                *time >= old_time && *time < self.time
+            };
+            if passed {
+                message_sender.write(owner.to_owned(), AnimationMessage(message.to_owned()));
             }
-            for (time, message) in &self.messages {
-                if *time >= old_time && *time < self.time {
-                    message_sender.write(owner.to_owned(), AnimationMessage(message.to_owned()));
-                }
+        }
+        for (time, event) in &self.events {
+            let passed = if self.reversed {
+                *time < old_time && *time >= self.time
+            } else {
+                *time >= old_time && *time < self.time
+            };
+            if passed {
+                message_sender.write(owner.to_owned(), event.to_owned());
             }
         }
     }
 
+    fn update_phases(&mut self) {
+        for phase in self.sheet.values_mut() {
+            phase.cached_time = (self.time - phase.start).min(phase.duration).max(0.0);
+            phase.cached_progress = if phase.duration > 0.0 {
+                phase.cached_time / phase.duration
+            } else {
+                0.0
+            };
+        }
+    }
+
     // Add an animation to this [`AnimatorState`] recursively
     fn include_animation(
+        &mut self,
         animation: Animation,
-        sheet: &mut HashMap<String, AnimationPhase>,
-        messages: &mut Vec<(Scalar, String)>,
         mut time: Scalar,
-    ) -> (Scalar, bool) {
+    ) -> (Scalar, bool, bool) {
         match animation {
             Animation::Value(value) => {
                 let duration = value.duration.max(0.0);
@@ -417,33 +811,57 @@ impl AnimatorState {
                     cached_time: 0.0,
                     cached_progress: 0.0,
                 };
-                sheet.insert(value.name, phase);
-                (time + duration, false)
+                self.sheet.insert(value.name, phase);
+                (time + duration, false, false)
             }
             Animation::Sequence(anims) => {
                 for anim in anims {
-                    time = Self::include_animation(anim, sheet, messages, time).0;
+                    time = self.include_animation(anim, time).0;
                 }
-                (time, false)
+                (time, false, false)
             }
             Animation::Parallel(anims) => {
                 let mut result = time;
                 for anim in anims {
-                    result = Self::include_animation(anim, sheet, messages, time)
-                        .0
-                        .max(result);
+                    result = self.include_animation(anim, time).0.max(result);
                 }
-                (result, false)
+                (result, false, false)
             }
             Animation::Looped(anim) => {
-                let looped = sheet.is_empty();
-                time = Self::include_animation(*anim, sheet, messages, time).0;
-                (time, looped)
+                let looped = self.sheet.is_empty();
+                time = self.include_animation(*anim, time).0;
+                (time, looped, false)
+            }
+            Animation::PingPong(anim) => {
+                let looped = self.sheet.is_empty();
+                time = self.include_animation(*anim, time).0;
+                (time, looped, looped)
             }
-            Animation::TimeShift(v) => ((time - v).max(0.0), false),
+            Animation::TimeShift(v) => ((time - v).max(0.0), false, false),
             Animation::Message(message) => {
-                messages.push((time, message));
-                (time, false)
+                self.messages.push((time, message));
+                (time, false, false)
+            }
+            Animation::Clip(mut clip) => {
+                let duration = clip.duration();
+                let phase = AnimationPhase {
+                    start: time,
+                    duration,
+                    cached_time: 0.0,
+                    cached_progress: 0.0,
+                };
+                for name in clip.tracks.keys() {
+                    self.sheet.insert(name.to_owned(), phase.clone());
+                }
+                for keyframe in std::mem::take(&mut clip.events) {
+                    self.events
+                        .push((time + keyframe.time.max(0.0), keyframe.event));
+                }
+                for track in clip.tracks.values_mut() {
+                    track.sort();
+                }
+                self.clips.push((time, clip));
+                (time + duration, false, false)
             }
         }
     }
@@ -479,10 +897,14 @@ pub enum Animation {
     Parallel(Vec<Animation>),
     /// An animation that will play in a loop
     Looped(Box<Animation>),
+    /// An animation that will play in a loop, bouncing back and forth
+    PingPong(Box<Animation>),
     /// TODO: Document `TimeShift`
     TimeShift(Scalar),
     /// Send an [`AnimationMessage`]
     Message(String),
+    /// Play keyframed [`AnimationClip`]
+    Clip(AnimationClip),
 }
 
 impl Default for Animation {
@@ -505,6 +927,359 @@ pub struct AnimatedValue {
     pub duration: Scalar,
 }
 
+/// Keyframed animation authored as data
+///
+/// Clip consists of named tracks of keyframes and of event keyframes. Clips are regular props so
+/// they can be loaded from prefabs, and are played through [`Animator`] with [`Animation::Clip`].
+/// Current track values can be read with [`AnimatorStates::track_value`] (each track is also
+/// exposed as animated value of the same name), events are sent to the widget as
+/// [`AnimationEvent`] messages.
+///
+/// # Example
+///
+/// ```
+/// # use raui_core::prelude::*;
+/// let clip = AnimationClip::from_prefab(serde_yaml::from_str(r#"
+/// tracks:
+///   opacity:
+///     Scalar:
+///       - time: 0
+///         value: 0
+///         easing: OutQuad
+///       - time: 0.5
+///         value: 1
+/// events:
+///   - time: 0.5
+///     name: shown
+/// "#).unwrap()).unwrap();
+/// assert_eq!(clip.duration(), 0.5);
+/// ```
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct AnimationClip {
+    /// Minimal duration of the clip, actual duration covers all keyframes
+    #[serde(default)]
+    pub duration: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub tracks: HashMap<String, AnimationTrack>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<AnimationEventKeyframe>,
+}
+
+impl AnimationClip {
+    /// Duration of the clip including all its keyframes
+    pub fn duration(&self) -> Scalar {
+        let tracks = self
+            .tracks
+            .values()
+            .map(|track| track.duration())
+            .fold(0.0, Scalar::max);
+        let events = self
+            .events
+            .iter()
+            .map(|keyframe| keyframe.time)
+            .fold(0.0, Scalar::max);
+        self.duration.max(tracks).max(events)
+    }
+}
+
+/// Single keyframe of an [`AnimationTrack`]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AnimationKeyframe<T> {
+    /// Time relative to clip start
+    #[serde(default)]
+    pub time: Scalar,
+    pub value: T,
+    /// Easing of transition from this keyframe to the next one
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+/// Track of keyframes of specific value type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AnimationTrack {
+    Scalar(Vec<AnimationKeyframe<Scalar>>),
+    Vec2(Vec<AnimationKeyframe<Vec2>>),
+    Color(Vec<AnimationKeyframe<Color>>),
+    Transform(Vec<AnimationKeyframe<Transform>>),
+}
+
+impl AnimationTrack {
+    /// Time of the last keyframe
+    pub fn duration(&self) -> Scalar {
+        fn last<T>(keyframes: &[AnimationKeyframe<T>]) -> Scalar {
+            keyframes.iter().map(|k| k.time).fold(0.0, Scalar::max)
+        }
+
+        match self {
+            Self::Scalar(keyframes) => last(keyframes),
+            Self::Vec2(keyframes) => last(keyframes),
+            Self::Color(keyframes) => last(keyframes),
+            Self::Transform(keyframes) => last(keyframes),
+        }
+    }
+
+    /// Get track value at given time relative to clip start
+    ///
+    /// Keyframes are expected to be sorted by time. Returns [`None`] for empty tracks.
+    pub fn sample(&self, time: Scalar) -> Option<AnimationTrackValue> {
+        match self {
+            Self::Scalar(keyframes) => {
+                Self::sample_keyframes(keyframes, time, lerp).map(AnimationTrackValue::Scalar)
+            }
+            Self::Vec2(keyframes) => {
+                Self::sample_keyframes(keyframes, time, lerp_vec2).map(AnimationTrackValue::Vec2)
+            }
+            Self::Color(keyframes) => Self::sample_keyframes(keyframes, time, |a, b, f| Color {
+                r: lerp(a.r, b.r, f),
+                g: lerp(a.g, b.g, f),
+                b: lerp(a.b, b.b, f),
+                a: lerp(a.a, b.a, f),
+            })
+            .map(AnimationTrackValue::Color),
+            Self::Transform(keyframes) => {
+                Self::sample_keyframes(keyframes, time, |a, b, f| Transform {
+                    pivot: lerp_vec2(a.pivot, b.pivot, f),
+                    align: lerp_vec2(a.align, b.align, f),
+                    translation: lerp_vec2(a.translation, b.translation, f),
+                    rotation: lerp(a.rotation, b.rotation, f),
+                    scale: lerp_vec2(a.scale, b.scale, f),
+                    skew: lerp_vec2(a.skew, b.skew, f),
+                })
+                .map(AnimationTrackValue::Transform)
+            }
+        }
+    }
+
+    fn sort(&mut self) {
+        fn sort<T>(keyframes: &mut [AnimationKeyframe<T>]) {
+            keyframes.sort_by(|a, b| {
+                a.time
+                    .partial_cmp(&b.time)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+
+        match self {
+            Self::Scalar(keyframes) => sort(keyframes),
+            Self::Vec2(keyframes) => sort(keyframes),
+            Self::Color(keyframes) => sort(keyframes),
+            Self::Transform(keyframes) => sort(keyframes),
+        }
+    }
+
+    fn sample_keyframes<T, F>(keyframes: &[AnimationKeyframe<T>], time: Scalar, f: F) -> Option<T>
+    where
+        T: Copy,
+        F: Fn(T, T, Scalar) -> T,
+    {
+        let index = keyframes.iter().position(|k| k.time > time);
+        match index {
+            Some(0) => keyframes.first().map(|k| k.value),
+            Some(index) => {
+                let from = &keyframes[index - 1];
+                let to = &keyframes[index];
+                let duration = to.time - from.time;
+                let factor = if duration > 0.0 {
+                    (time - from.time) / duration
+                } else {
+                    1.0
+                };
+                Some(f(from.value, to.value, from.easing.apply(factor)))
+            }
+            None => keyframes.last().map(|k| k.value),
+        }
+    }
+}
+
+fn lerp_vec2(from: Vec2, to: Vec2, factor: Scalar) -> Vec2 {
+    Vec2 {
+        x: lerp(from.x, to.x, factor),
+        y: lerp(from.y, to.y, factor),
+    }
+}
+
+/// Current value of an [`AnimationTrack`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnimationTrackValue {
+    Scalar(Scalar),
+    Vec2(Vec2),
+    Color(Color),
+    Transform(Transform),
+}
+
+/// Event keyframe of an [`AnimationClip`]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AnimationEventKeyframe {
+    /// Time relative to clip start
+    #[serde(default)]
+    pub time: Scalar,
+    #[serde(flatten)]
+    pub event: AnimationEvent,
+}
+
+/// A [`MessageData`][crate::messenger::MessageData] implementation sent when playing an
+/// [`AnimationClip`] passes its event keyframe
+#[derive(MessageData, Debug, Default, Clone, Serialize, Deserialize)]
+#[message_data(crate::messenger::MessageData)]
+pub struct AnimationEvent {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "PrefabValue::is_null")]
+    pub payload: PrefabValue,
+}
+
+impl AnimationEvent {
+    /// Create event with given payload
+    pub fn new<T>(name: &str, payload: &T) -> Result<Self, PrefabError>
+    where
+        T: Prefab,
+    {
+        Ok(Self {
+            name: name.to_owned(),
+            payload: payload.to_prefab()?,
+        })
+    }
+
+    /// Read event payload as given type
+    pub fn payload<T>(&self) -> Result<T, PrefabError>
+    where
+        T: Prefab,
+    {
+        T::from_prefab(self.payload.clone())
+    }
+}
+
+/// Easing curve that remaps linear progress factor into eased one
+///
+/// # Example
+///
+/// ```
+/// # use raui_core::prelude::*;
+/// assert_eq!(Easing::Linear.apply(0.25), 0.25);
+/// assert_eq!(Easing::InQuad.apply(0.5), 0.25);
+/// assert_eq!(Easing::OutQuad.apply(1.0), 1.0);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InSine,
+    OutSine,
+    InOutSine,
+    InBack,
+    OutBack,
+    InOutBack,
+    OutBounce,
+    /// Jumps between given number of discrete steps
+    Steps(u32),
+    /// CSS-like cubic bezier curve with control points `(x1, y1)` and `(x2, y2)`
+    CubicBezier(Scalar, Scalar, Scalar, Scalar),
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl Easing {
+    /// Remap progress factor in range `<0;1>`
+    pub fn apply(&self, factor: Scalar) -> Scalar {
+        const BACK: Scalar = 1.70158;
+        let t = factor.clamp(0.0, 1.0);
+        match *self {
+            Self::Linear => t,
+            Self::InQuad => t * t,
+            Self::OutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::InOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) * 0.5
+                }
+            }
+            Self::InCubic => t * t * t,
+            Self::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Self::InOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) * 0.5
+                }
+            }
+            Self::InSine => 1.0 - (t * PI * 0.5).cos(),
+            Self::OutSine => (t * PI * 0.5).sin(),
+            Self::InOutSine => -((t * PI).cos() - 1.0) * 0.5,
+            Self::InBack => (BACK + 1.0) * t * t * t - BACK * t * t,
+            Self::OutBack => {
+                let t = t - 1.0;
+                1.0 + (BACK + 1.0) * t * t * t + BACK * t * t
+            }
+            Self::InOutBack => {
+                let c = BACK * 1.525;
+                if t < 0.5 {
+                    (2.0 * t).powi(2) * ((c + 1.0) * 2.0 * t - c) * 0.5
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((c + 1.0) * (t * 2.0 - 2.0) + c) + 2.0) * 0.5
+                }
+            }
+            Self::OutBounce => {
+                const N: Scalar = 7.5625;
+                const D: Scalar = 2.75;
+                if t < 1.0 / D {
+                    N * t * t
+                } else if t < 2.0 / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+            Self::Steps(count) => {
+                let count = count.max(1) as Scalar;
+                (t * count).floor().min(count) / count
+            }
+            Self::CubicBezier(x1, y1, x2, y2) => {
+                fn bezier(a: Scalar, b: Scalar, t: Scalar) -> Scalar {
+                    let u = 1.0 - t;
+                    3.0 * u * u * t * a + 3.0 * u * t * t * b + t * t * t
+                }
+                // find curve parameter for given x with bisection, x(t) is monotonic for
+                // control points in range <0;1>.
+                let (mut low, mut high) = (0.0, 1.0);
+                let mut param = t;
+                for _ in 0..32 {
+                    let x = bezier(x1, x2, param);
+                    if (x - t).abs() < 1.0e-6 {
+                        break;
+                    }
+                    if x < t {
+                        low = param;
+                    } else {
+                        high = param;
+                    }
+                    param = (low + high) * 0.5;
+                }
+                bezier(y1, y2, param)
+            }
+        }
+    }
+}
+
 /// A [`MessageData`][crate::messenger::MessageData] implementation sent by running an
 /// [`Animation::Message`] animation
 #[derive(MessageData, Debug, Default, Clone)]
@@ -554,4 +1329,185 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn test_animator_playback() {
+        let animation = Animation::Value(AnimatedValue {
+            name: "value".to_owned(),
+            duration: 1.0,
+        });
+        let mut states = AnimatorStates::new("anim".to_owned(), animation);
+        let id = WidgetId::from_str("type:/widget").unwrap();
+        let (sender, _receiver) = channel();
+        let sender = MessageSender::new(sender);
+        states.process(0.25, &id, &sender);
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.25));
+        assert!(states.control("anim", AnimationControl::Pause));
+        states.process(0.25, &id, &sender);
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.25));
+        assert!(!states.in_progress());
+        assert!(states.in_progress_or_paused());
+        states.control("anim", AnimationControl::Resume);
+        states.control("anim", AnimationControl::SetSpeed(0.0));
+        states.process(0.25, &id, &sender);
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.25));
+        assert!(!states.in_progress());
+        assert!(states.is_paused("anim"));
+        states.control("anim", AnimationControl::SetSpeed(2.0));
+        states.process(0.25, &id, &sender);
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.75));
+        states.control("anim", AnimationControl::Reverse);
+        states.process(0.125, &id, &sender);
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.5));
+        states.control("anim", AnimationControl::SeekProgress(0.1));
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.1));
+        states.process(0.5, &id, &sender);
+        assert!(states.is_done());
+        assert!(!states.control("missing", AnimationControl::Pause));
+    }
+
+    #[test]
+    fn test_animator_ping_pong() {
+        let animation = Animation::PingPong(Box::new(Animation::Value(AnimatedValue {
+            name: "value".to_owned(),
+            duration: 1.0,
+        })));
+        let mut states = AnimatorStates::new("anim".to_owned(), animation);
+        let id = WidgetId::from_str("type:/widget").unwrap();
+        let (sender, _receiver) = channel();
+        let sender = MessageSender::new(sender);
+        states.process(1.5, &id, &sender);
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(1.0));
+        states.process(0.25, &id, &sender);
+        assert!(states.is_reversed("anim"));
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.75));
+        states.process(1.0, &id, &sender);
+        states.process(0.25, &id, &sender);
+        assert!(!states.is_reversed("anim"));
+        assert_eq!(states.value_progress_factor("anim", "value"), Some(0.25));
+        assert!(states.in_progress());
+    }
+
+    #[test]
+    fn test_animator_clip() {
+        let clip = AnimationClip::from_prefab(
+            serde_yaml::from_str(
+                r#"
+                tracks:
+                  opacity:
+                    Scalar:
+                      - time: 1
+                        value: 10
+                      - time: 0
+                        value: 0
+                  offset:
+                    Vec2:
+                      - time: 0
+                        value: { x: 0, y: 0 }
+                        easing: InQuad
+                      - time: 2
+                        value: { x: 4, y: 8 }
+                events:
+                  - time: 0.5
+                    name: half
+                    payload: 42
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(clip.duration(), 2.0);
+        let mut states = AnimatorStates::new("clip".to_owned(), Animation::Clip(clip));
+        let id = WidgetId::from_str("type:/widget").unwrap();
+        let (sender, receiver) = channel();
+        let sender = MessageSender::new(sender);
+        states.process(0.25, &id, &sender);
+        assert_eq!(states.track_scalar_or("clip", "opacity", 0.0), 2.5);
+        assert!(receiver.try_recv().is_err());
+        states.process(0.75, &id, &sender);
+        assert_eq!(states.track_scalar_or("clip", "opacity", 0.0), 10.0);
+        assert_eq!(
+            states.track_vec2_or("clip", "offset", Default::default()),
+            Vec2 { x: 1.0, y: 2.0 }
+        );
+        assert_eq!(states.value_progress_factor("clip", "offset"), Some(0.5));
+        let (_, message) = receiver.try_recv().unwrap();
+        let event = message.as_any().downcast_ref::<AnimationEvent>().unwrap();
+        assert_eq!(event.name, "half");
+        assert_eq!(event.payload::<u32>().unwrap(), 42);
+        assert_eq!(states.track_value("clip", "missing"), None);
+        states.process(1.5, &id, &sender);
+        assert!(states.is_done());
+    }
+
+    #[test]
+    fn test_animator_clip_events_only() {
+        let clip = AnimationClip {
+            events: vec![AnimationEventKeyframe {
+                time: 1.0,
+                event: AnimationEvent {
+                    name: "end".to_owned(),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        };
+        let mut states = AnimatorStates::new("clip".to_owned(), Animation::Clip(clip));
+        let id = WidgetId::from_str("type:/widget").unwrap();
+        let (sender, receiver) = channel();
+        let sender = MessageSender::new(sender);
+        states.process(0.5, &id, &sender);
+        assert!(!states.is_done());
+        assert!(receiver.try_recv().is_err());
+        states.process(0.75, &id, &sender);
+        let (_, message) = receiver.try_recv().unwrap();
+        let event = message.as_any().downcast_ref::<AnimationEvent>().unwrap();
+        assert_eq!(event.name, "end");
+        states.process(0.5, &id, &sender);
+        assert!(states.is_done());
+    }
+
+    #[test]
+    fn test_animator_clip_events_only_parallel() {
+        fn clip(name: &str, time: Scalar) -> Animation {
+            Animation::Clip(AnimationClip {
+                events: vec![AnimationEventKeyframe {
+                    time,
+                    event: AnimationEvent {
+                        name: name.to_owned(),
+                        ..Default::default()
+                    },
+                }],
+                ..Default::default()
+            })
+        }
+
+        let animation = Animation::Parallel(vec![clip("first", 1.0), clip("second", 2.0)]);
+        let mut states = AnimatorStates::new("clips".to_owned(), animation);
+        let id = WidgetId::from_str("type:/widget").unwrap();
+        let (sender, receiver) = channel();
+        let sender = MessageSender::new(sender);
+        let fired = || {
+            receiver
+                .try_iter()
+                .map(|(_, message)| {
+                    message
+                        .as_any()
+                        .downcast_ref::<AnimationEvent>()
+                        .unwrap()
+                        .name
+                        .to_owned()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert!(states.value_progress("clips", "").is_none());
+        states.process(1.5, &id, &sender);
+        assert_eq!(fired(), vec!["first".to_owned()]);
+        assert!(!states.is_done());
+        states.process(1.0, &id, &sender);
+        assert_eq!(fired(), vec!["second".to_owned()]);
+        states.process(0.5, &id, &sender);
+        assert!(states.is_done());
+        assert!(fired().is_empty());
+    }
 }
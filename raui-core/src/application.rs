@@ -70,7 +70,7 @@
 //!     // We apply the application layout
 //!     application
 //!         // We use the default layout engine, but you could make your own layout engine
-//!         .layout(&mapping, &mut DefaultLayoutEngine)
+//!         .layout(&mapping, &mut DefaultLayoutEngine::default())
 //!         .unwrap();
 //!
 //!     // we interact with UI by sending interaction messages to the engine. You would hook this
@@ -93,7 +93,10 @@
 //! ```
 
 use crate::{
-    animator::{AnimationUpdate, Animator, AnimatorStates},
+    animator::{
+        Animation, AnimationControl, AnimationRequest, AnimationTimeScale, AnimationUpdate,
+        Animator, AnimatorStates,
+    },
     interactive::InteractionsEngine,
     layout::{CoordsMapping, Layout, LayoutEngine},
     messenger::{Message, MessageData, MessageSender, Messages, Messenger},
@@ -102,30 +105,42 @@ use crate::{
     signals::{Signal, SignalSender},
     state::{State, StateUpdate},
     widget::{
-        component::{WidgetComponent, WidgetComponentPrefab},
+        component::{
+            containers::layout_transition_box::{start_layout_transition, LayoutTransitionProps},
+            WidgetComponent, WidgetComponentPrefab,
+        },
         context::{WidgetContext, WidgetMountOrChangeContext, WidgetUnmountContext},
         node::{WidgetNode, WidgetNodePrefab},
         unit::{
             area::{AreaBoxNode, AreaBoxNodePrefab},
             content::{
-                ContentBoxItem, ContentBoxItemNode, ContentBoxItemNodePrefab, ContentBoxNode,
-                ContentBoxNodePrefab,
+                ContentBoxItem, ContentBoxItemLayout, ContentBoxItemNode, ContentBoxItemNodePrefab,
+                ContentBoxNode, ContentBoxNodePrefab,
             },
+            custom::{CustomBoxNode, CustomBoxNodePrefab},
             flex::{
-                FlexBoxItem, FlexBoxItemNode, FlexBoxItemNodePrefab, FlexBoxNode, FlexBoxNodePrefab,
+                FlexBoxItem, FlexBoxItemLayout, FlexBoxItemNode, FlexBoxItemNodePrefab,
+                FlexBoxNode, FlexBoxNodePrefab,
             },
             grid::{
-                GridBoxItem, GridBoxItemNode, GridBoxItemNodePrefab, GridBoxNode, GridBoxNodePrefab,
+                GridBoxItem, GridBoxItemLayout, GridBoxItemNode, GridBoxItemNodePrefab,
+                GridBoxNode, GridBoxNodePrefab,
             },
             image::{ImageBoxNode, ImageBoxNodePrefab},
+            masonry::{
+                MasonryBoxItem, MasonryBoxItemLayout, MasonryBoxItemNode, MasonryBoxItemNodePrefab,
+                MasonryBoxNode, MasonryBoxNodePrefab,
+            },
             portal::{
                 PortalBox, PortalBoxNode, PortalBoxNodePrefab, PortalBoxSlot, PortalBoxSlotNode,
                 PortalBoxSlotNodePrefab,
             },
+            rich_text::{RichTextBoxNode, RichTextBoxNodePrefab},
             size::{SizeBoxNode, SizeBoxNodePrefab},
             text::{TextBoxNode, TextBoxNodePrefab},
             WidgetUnit, WidgetUnitNode, WidgetUnitNodePrefab,
         },
+        utils::{Rect, Transform},
         FnWidget, WidgetId, WidgetLifeCycle,
     },
     Prefab, PrefabError, PrefabValue, Scalar,
@@ -197,6 +212,8 @@ pub enum InvalidationCause {
     MessageReceived(WidgetId),
     /// An animation is in progress for a widget
     AnimationInProgress(WidgetId),
+    /// Exit transition of a departing widget has completed and it can be unmounted
+    ExitTransitionCompleted(WidgetId),
 }
 
 impl Default for InvalidationCause {
@@ -205,6 +222,122 @@ impl Default for InvalidationCause {
     }
 }
 
+#[derive(Clone)]
+enum ExitTransitionItemLayout {
+    Content(ContentBoxItemLayout),
+    Flex(FlexBoxItemLayout),
+    Grid(GridBoxItemLayout),
+    Masonry(MasonryBoxItemLayout),
+}
+
+struct ExitTransitionPlacement {
+    container: WidgetId,
+    index: usize,
+    possible_key: String,
+    layout: ExitTransitionItemLayout,
+}
+
+struct ExitTransition {
+    anim_id: String,
+    animation: Animation,
+    component: Option<WidgetComponent>,
+    placement: Option<ExitTransitionPlacement>,
+    departing: bool,
+}
+
+struct LayoutTransition {
+    props: LayoutTransitionProps,
+    rect: Option<Rect>,
+}
+
+trait ContainerItemNode: Sized {
+    fn slot_mut(&mut self) -> &mut WidgetNode;
+
+    fn exit_layout(&self) -> ExitTransitionItemLayout;
+
+    fn from_exit_layout(slot: WidgetNode, layout: &ExitTransitionItemLayout) -> Option<Self>;
+}
+
+impl ContainerItemNode for ContentBoxItemNode {
+    fn slot_mut(&mut self) -> &mut WidgetNode {
+        &mut self.slot
+    }
+
+    fn exit_layout(&self) -> ExitTransitionItemLayout {
+        ExitTransitionItemLayout::Content(self.layout.clone())
+    }
+
+    fn from_exit_layout(slot: WidgetNode, layout: &ExitTransitionItemLayout) -> Option<Self> {
+        match layout {
+            ExitTransitionItemLayout::Content(layout) => Some(Self {
+                slot,
+                layout: layout.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl ContainerItemNode for FlexBoxItemNode {
+    fn slot_mut(&mut self) -> &mut WidgetNode {
+        &mut self.slot
+    }
+
+    fn exit_layout(&self) -> ExitTransitionItemLayout {
+        ExitTransitionItemLayout::Flex(self.layout.clone())
+    }
+
+    fn from_exit_layout(slot: WidgetNode, layout: &ExitTransitionItemLayout) -> Option<Self> {
+        match layout {
+            ExitTransitionItemLayout::Flex(layout) => Some(Self {
+                slot,
+                layout: layout.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl ContainerItemNode for GridBoxItemNode {
+    fn slot_mut(&mut self) -> &mut WidgetNode {
+        &mut self.slot
+    }
+
+    fn exit_layout(&self) -> ExitTransitionItemLayout {
+        ExitTransitionItemLayout::Grid(self.layout.clone())
+    }
+
+    fn from_exit_layout(slot: WidgetNode, layout: &ExitTransitionItemLayout) -> Option<Self> {
+        match layout {
+            ExitTransitionItemLayout::Grid(layout) => Some(Self {
+                slot,
+                layout: layout.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl ContainerItemNode for MasonryBoxItemNode {
+    fn slot_mut(&mut self) -> &mut WidgetNode {
+        &mut self.slot
+    }
+
+    fn exit_layout(&self) -> ExitTransitionItemLayout {
+        ExitTransitionItemLayout::Masonry(self.layout.clone())
+    }
+
+    fn from_exit_layout(slot: WidgetNode, layout: &ExitTransitionItemLayout) -> Option<Self> {
+        match layout {
+            ExitTransitionItemLayout::Masonry(layout) => Some(Self {
+                slot,
+                layout: layout.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// Contains and orchestrates application layout, animations, interactions, etc.
 ///
 /// See the [`application`][self] module for more information and examples.
@@ -217,6 +350,11 @@ pub struct Application {
     states: HashMap<WidgetId, Props>,
     state_changes: HashMap<WidgetId, Props>,
     animators: HashMap<WidgetId, AnimatorStates>,
+    animators_time_scales: HashMap<WidgetId, Scalar>,
+    exit_transitions: HashMap<WidgetId, ExitTransition>,
+    departing_widgets: HashSet<WidgetId>,
+    processing_departing: bool,
+    layout_transitions: HashMap<WidgetId, LayoutTransition>,
     messages: HashMap<WidgetId, Messages>,
     signals: Vec<Signal>,
     #[allow(clippy::type_complexity)]
@@ -227,6 +365,9 @@ pub struct Application {
     change_notifier: ChangeNotifier,
     /// The amount of time between the last update, used when calculating animation progress
     pub animations_delta_time: Scalar,
+    /// Global multiplier of [`animations_delta_time`][Self::animations_delta_time], applied on top
+    /// of per-subtree [`AnimationTimeScale`]
+    pub animations_time_scale: Scalar,
 }
 
 impl Default for Application {
@@ -247,6 +388,11 @@ impl Application {
             states: Default::default(),
             state_changes: Default::default(),
             animators: Default::default(),
+            animators_time_scales: Default::default(),
+            exit_transitions: Default::default(),
+            departing_widgets: Default::default(),
+            processing_departing: false,
+            layout_transitions: Default::default(),
             messages: Default::default(),
             signals: Default::default(),
             unmount_closures: Default::default(),
@@ -255,6 +401,7 @@ impl Application {
             last_invalidation_cause: Default::default(),
             change_notifier: ChangeNotifier::default(),
             animations_delta_time: 0.0,
+            animations_time_scale: 1.0,
         }
     }
 
@@ -479,12 +626,16 @@ impl Application {
     }
 
     /// Calculate application layout
+    ///
+    /// Widgets with [layout transition][crate::widget::WidgetLifeCycle::layout_transition] that
+    /// got laid out at new place start their transitions here.
     #[inline]
     pub fn layout<L, E>(&mut self, mapping: &CoordsMapping, layout_engine: &mut L) -> Result<(), E>
     where
         L: LayoutEngine<E>,
     {
         self.layout = layout_engine.layout(mapping, &self.rendered_tree)?;
+        self.start_layout_transitions();
         Ok(())
     }
 
@@ -535,6 +686,40 @@ impl Application {
         }
     }
 
+    /// Get the [`AnimatorStates`] of the given widget
+    #[inline]
+    pub fn animator_states(&self, id: &WidgetId) -> Option<&AnimatorStates> {
+        self.animators.get(id)
+    }
+
+    /// Tells if given widget is departing (or is a descendant of one) and is still rendered only to
+    /// play its [exit transition][crate::widget::WidgetLifeCycle::exit]
+    #[inline]
+    pub fn is_widget_departing(&self, id: &WidgetId) -> bool {
+        self.departing_widgets.contains(id)
+    }
+
+    /// Apply playback control to the animation `anim_id` of the given widget
+    ///
+    /// Returns `false` if the widget has no such animation.
+    pub fn animation_control(
+        &mut self,
+        id: &WidgetId,
+        anim_id: &str,
+        control: AnimationControl,
+    ) -> bool {
+        match self.animators.get_mut(id) {
+            Some(states) => {
+                let result = states.control(anim_id, control);
+                if result {
+                    self.dirty = true;
+                }
+                result
+            }
+            None => false,
+        }
+    }
+
     /// Get the list of [signals][crate::signals] that have been sent by widgets
     #[inline]
     pub fn signals(&self) -> &[Signal] {
@@ -690,19 +875,44 @@ impl Application {
             self.dirty = true;
         }
         self.animations_delta_time = self.animations_delta_time.max(0.0);
+        self.animations_time_scale = self.animations_time_scale.max(0.0);
         self.last_invalidation_cause = InvalidationCause::None;
         self.render_changed = false;
         let changed_states = std::mem::take(&mut self.state_changes);
         let mut messages = std::mem::take(&mut self.messages);
-        let changed_animators = self.animators.values().any(|a| a.in_progress());
-        if !self.dirty && changed_states.is_empty() && messages.is_empty() && !changed_animators {
+        let changed_animators = self.animators_in_progress().next().is_some();
+        let completed_exit_transitions = self
+            .exit_transitions
+            .iter()
+            .filter(|(id, exit)| {
+                exit.departing
+                    && !self
+                        .animators
+                        .get(id)
+                        .and_then(|a| a.state(&exit.anim_id))
+                        .map(|a| a.in_progress() || a.is_paused())
+                        .unwrap_or_default()
+            })
+            .map(|(id, _)| id.to_owned())
+            .collect::<Vec<_>>();
+        if !self.dirty
+            && changed_states.is_empty()
+            && messages.is_empty()
+            && !changed_animators
+            && completed_exit_transitions.is_empty()
+        {
             return false;
         }
         if self.dirty {
             self.last_invalidation_cause = InvalidationCause::Forced;
         }
-        if let Some((id, _)) = self.animators.iter().find(|(_, a)| a.in_progress()) {
-            self.last_invalidation_cause = InvalidationCause::AnimationInProgress(id.to_owned());
+        for id in completed_exit_transitions {
+            self.exit_transitions.remove(&id);
+            self.last_invalidation_cause = InvalidationCause::ExitTransitionCompleted(id);
+        }
+        let animator_in_progress = self.animators_in_progress().next().cloned();
+        if let Some(id) = animator_in_progress {
+            self.last_invalidation_cause = InvalidationCause::AnimationInProgress(id);
         }
         if let Some((id, _)) = messages.iter().next() {
             self.last_invalidation_cause = InvalidationCause::MessageReceived(id.to_owned());
@@ -712,9 +922,6 @@ impl Application {
         }
         let (message_sender, message_receiver) = channel();
         let message_sender = MessageSender::new(message_sender);
-        for (k, a) in &mut self.animators {
-            a.process(self.animations_delta_time, &k, &message_sender);
-        }
         self.dirty = false;
         let old_states = std::mem::take(&mut self.states);
         let states = old_states
@@ -725,6 +932,7 @@ impl Application {
         let tree = self.tree.clone();
         let mut used_ids = HashSet::new();
         let mut new_states = HashMap::new();
+        self.departing_widgets.clear();
         let rendered_tree = self.process_node(
             tree,
             &states,
@@ -764,6 +972,11 @@ impl Application {
                 }
             })
             .collect();
+        self.exit_transitions.retain(|id, _| used_ids.contains(id));
+        self.animators_time_scales
+            .retain(|id, _| used_ids.contains(id));
+        self.layout_transitions
+            .retain(|id, _| used_ids.contains(id));
         while let Ok((id, message)) = message_receiver.try_recv() {
             if let Some(list) = self.messages.get_mut(&id) {
                 list.push(message);
@@ -777,7 +990,13 @@ impl Application {
         }
         self.animators = std::mem::take(&mut self.animators)
             .into_iter()
-            .filter_map(|(k, a)| if a.in_progress() { Some((k, a)) } else { None })
+            .filter_map(|(k, a)| {
+                if a.in_progress_or_paused() {
+                    Some((k, a))
+                } else {
+                    None
+                }
+            })
             .collect::<HashMap<_, _>>();
         if let Ok(tree) = rendered_tree.try_into() {
             self.rendered_tree = Self::teleport_portals(tree);
@@ -787,6 +1006,17 @@ impl Application {
         }
     }
 
+    fn animator_time_scale(&self, id: &WidgetId) -> Scalar {
+        self.animations_time_scale * self.animators_time_scales.get(id).copied().unwrap_or(1.0)
+    }
+
+    fn animators_in_progress(&self) -> impl Iterator<Item = &WidgetId> {
+        self.animators
+            .iter()
+            .filter(move |(id, a)| a.in_progress() && self.animator_time_scale(id) > 0.0)
+            .map(|(id, _)| id)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn process_node<'a, 'b>(
         &mut self,
@@ -872,6 +1102,30 @@ impl Application {
         path.push(key.clone());
         let id = WidgetId::new(&type_name, &path);
         used_ids.insert(id.clone());
+        let departing = self.processing_departing
+            || self
+                .exit_transitions
+                .get(&id)
+                .map(|exit| exit.departing)
+                .unwrap_or_default();
+        if departing {
+            self.departing_widgets.insert(id.clone());
+        }
+        // animators are advanced right when their widget gets processed, so time scale set by
+        // ancestors in this very pass applies immediately.
+        match shared_props.read_cloned::<AnimationTimeScale>() {
+            Ok(AnimationTimeScale(scale)) => {
+                self.animators_time_scales
+                    .insert(id.clone(), scale.max(0.0));
+            }
+            Err(_) => {
+                self.animators_time_scales.remove(&id);
+            }
+        }
+        let delta_time = self.animations_delta_time * self.animator_time_scale(&id);
+        if let Some(animator) = self.animators.get_mut(&id) {
+            animator.process(delta_time, &id, message_sender);
+        }
         if let Some(idref) = &mut idref {
             idref.write(id.to_owned());
         }
@@ -924,6 +1178,40 @@ impl Application {
                 (node, true)
             }
         };
+        match life_cycle.take_exit() {
+            Some((anim_id, animation)) => {
+                if let Some(exit) = self.exit_transitions.get_mut(&id) {
+                    exit.anim_id = anim_id;
+                    exit.animation = animation;
+                } else {
+                    self.exit_transitions.insert(
+                        id.to_owned(),
+                        ExitTransition {
+                            anim_id,
+                            animation,
+                            component: None,
+                            placement: None,
+                            departing: false,
+                        },
+                    );
+                }
+            }
+            None => {
+                self.exit_transitions.remove(&id);
+            }
+        }
+        match life_cycle.take_layout_transition() {
+            Some(props) => match self.layout_transitions.get_mut(&id) {
+                Some(transition) => transition.props = props,
+                None => {
+                    self.layout_transitions
+                        .insert(id.to_owned(), LayoutTransition { props, rect: None });
+                }
+            },
+            None => {
+                self.layout_transitions.remove(&id);
+            }
+        }
         let (mount, change, unmount) = life_cycle.unwrap();
         if mounted {
             if !mount.is_empty() {
@@ -977,14 +1265,24 @@ impl Application {
         if !unmount.is_empty() {
             self.unmount_closures.insert(id.clone(), unmount);
         }
-        while let Ok((name, data)) = animation_receiver.try_recv() {
-            if let Some(states) = self.animators.get_mut(&id) {
-                states.change(name, data);
-            } else if let Some(data) = data {
-                self.animators
-                    .insert(id.to_owned(), AnimatorStates::new(name, data));
+        while let Ok((name, request)) = animation_receiver.try_recv() {
+            match request {
+                AnimationRequest::Change(data) => {
+                    if let Some(states) = self.animators.get_mut(&id) {
+                        states.change(name, data);
+                    } else if let Some(data) = data {
+                        self.animators
+                            .insert(id.to_owned(), AnimatorStates::new(name, data));
+                    }
+                }
+                AnimationRequest::Control(control) => {
+                    if let Some(states) = self.animators.get_mut(&id) {
+                        states.control(&name, control);
+                    }
+                }
             }
         }
+        let processing_departing = std::mem::replace(&mut self.processing_departing, departing);
         let new_node = self.process_node(
             new_node,
             states,
@@ -998,6 +1296,7 @@ impl Application {
             signal_sender,
             process_context,
         );
+        self.processing_departing = processing_departing;
         while let Ok(data) = state_receiver.try_recv() {
             self.state_changes.insert(id.to_owned(), data);
         }
@@ -1019,7 +1318,11 @@ impl Application {
         process_context: &mut ProcessContext<'b>,
     ) -> WidgetNode {
         match &mut unit {
-            WidgetUnitNode::None | WidgetUnitNode::ImageBox(_) | WidgetUnitNode::TextBox(_) => {}
+            WidgetUnitNode::None
+            | WidgetUnitNode::ImageBox(_)
+            | WidgetUnitNode::TextBox(_)
+            | WidgetUnitNode::RichTextBox(_)
+            | WidgetUnitNode::CustomBox(_) => {}
             WidgetUnitNode::AreaBox(unit) => {
                 let slot = *std::mem::take(&mut unit.slot);
                 unit.slot = Box::new(self.process_node(
@@ -1104,75 +1407,67 @@ impl Application {
             },
             WidgetUnitNode::ContentBox(unit) => {
                 let items = std::mem::take(&mut unit.items);
-                unit.items = items
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, mut node)| {
-                        let slot = std::mem::take(&mut node.slot);
-                        node.slot = self.process_node(
-                            slot,
-                            states,
-                            path.clone(),
-                            messages,
-                            new_states,
-                            used_ids,
-                            format!("<{}>", i),
-                            master_shared_props.clone(),
-                            message_sender,
-                            signal_sender,
-                            process_context,
-                        );
-                        node
-                    })
-                    .collect::<Vec<_>>();
+                unit.items = self.process_container_items(
+                    &unit.id,
+                    items,
+                    states,
+                    &path,
+                    messages,
+                    new_states,
+                    used_ids,
+                    &master_shared_props,
+                    message_sender,
+                    signal_sender,
+                    process_context,
+                );
             }
             WidgetUnitNode::FlexBox(unit) => {
                 let items = std::mem::take(&mut unit.items);
-                unit.items = items
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, mut node)| {
-                        let slot = std::mem::take(&mut node.slot);
-                        node.slot = self.process_node(
-                            slot,
-                            states,
-                            path.clone(),
-                            messages,
-                            new_states,
-                            used_ids,
-                            format!("<{}>", i),
-                            master_shared_props.clone(),
-                            message_sender,
-                            signal_sender,
-                            process_context,
-                        );
-                        node
-                    })
-                    .collect::<Vec<_>>();
+                unit.items = self.process_container_items(
+                    &unit.id,
+                    items,
+                    states,
+                    &path,
+                    messages,
+                    new_states,
+                    used_ids,
+                    &master_shared_props,
+                    message_sender,
+                    signal_sender,
+                    process_context,
+                );
             }
             WidgetUnitNode::GridBox(unit) => {
                 let items = std::mem::take(&mut unit.items);
-                unit.items = items
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, mut node)| {
-                        let slot = std::mem::take(&mut node.slot);
-                        node.slot = self.process_node(
-                            slot,
-                            states,
-                            path.clone(),
-                            messages,
-                            new_states,
-                            used_ids,
-                            format!("<{}>", i),
-                            master_shared_props.clone(),
-                            message_sender,
-                            signal_sender,
-                            process_context,
-                        );
-                        node
-                    })
-                    .collect::<Vec<_>>();
+                unit.items = self.process_container_items(
+                    &unit.id,
+                    items,
+                    states,
+                    &path,
+                    messages,
+                    new_states,
+                    used_ids,
+                    &master_shared_props,
+                    message_sender,
+                    signal_sender,
+                    process_context,
+                );
+            }
+            WidgetUnitNode::MasonryBox(unit) => {
+                let items = std::mem::take(&mut unit.items);
+                unit.items = self.process_container_items(
+                    &unit.id,
+                    items,
+                    states,
+                    &path,
+                    messages,
+                    new_states,
+                    used_ids,
+                    &master_shared_props,
+                    message_sender,
+                    signal_sender,
+                    process_context,
+                );
             }
             WidgetUnitNode::SizeBox(unit) => {
                 let slot = *std::mem::take(&mut unit.slot);
@@ -1194,6 +1489,224 @@ impl Application {
         unit.into()
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn process_container_items<'a, 'b, T: ContainerItemNode>(
+        &mut self,
+        container: &WidgetId,
+        items: Vec<T>,
+        states: &'a HashMap<WidgetId, Props>,
+        path: &[String],
+        messages: &mut HashMap<WidgetId, Messages>,
+        new_states: &mut HashMap<WidgetId, Props>,
+        used_ids: &mut HashSet<WidgetId>,
+        master_shared_props: &Option<Props>,
+        message_sender: &MessageSender,
+        signal_sender: &Sender<Signal>,
+        process_context: &mut ProcessContext<'b>,
+    ) -> Vec<T> {
+        let mut present = HashSet::with_capacity(items.len());
+        let mut items = items
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut node)| {
+                let possible_key = format!("<{}>", i);
+                let slot = std::mem::take(node.slot_mut());
+                // snapshot newly mounted items and items with exit transitions so they can be
+                // rendered again after their parent stops providing them.
+                let snapshot = match &slot {
+                    WidgetNode::Component(component) => {
+                        let mut path = path.to_vec();
+                        path.push(
+                            component
+                                .key
+                                .clone()
+                                .unwrap_or_else(|| possible_key.clone()),
+                        );
+                        let id = WidgetId::new(&component.type_name, &path);
+                        present.insert(id.clone());
+                        if !states.contains_key(&id) || self.exit_transitions.contains_key(&id) {
+                            Some((id, component.clone()))
+                        } else {
+                            None
+                        }
+                    }
+                    _ => None,
+                };
+                // item provided again stops departing before it gets processed.
+                if let Some((id, _)) = &snapshot {
+                    if let Some(exit) = self.exit_transitions.get_mut(id) {
+                        if exit.departing {
+                            exit.departing = false;
+                            if let Some(states) = self.animators.get_mut(id) {
+                                states.change(exit.anim_id.clone(), None);
+                            }
+                        }
+                    }
+                }
+                *node.slot_mut() = self.process_node(
+                    slot,
+                    states,
+                    path.to_vec(),
+                    messages,
+                    new_states,
+                    used_ids,
+                    possible_key.clone(),
+                    master_shared_props.clone(),
+                    message_sender,
+                    signal_sender,
+                    process_context,
+                );
+                if let Some((id, component)) = snapshot {
+                    if let Some(exit) = self.exit_transitions.get_mut(&id) {
+                        exit.component = Some(component);
+                        exit.placement = Some(ExitTransitionPlacement {
+                            container: container.to_owned(),
+                            index: i,
+                            possible_key,
+                            layout: node.exit_layout(),
+                        });
+                    }
+                }
+                node
+            })
+            .collect::<Vec<_>>();
+        let animators = &mut self.animators;
+        let mut departing = self
+            .exit_transitions
+            .iter_mut()
+            .filter_map(|(id, exit)| {
+                let placement = exit.placement.as_ref()?;
+                if &placement.container != container || present.contains(id) {
+                    return None;
+                }
+                let component = exit.component.clone()?;
+                if !exit.departing {
+                    exit.departing = true;
+                    let animation = exit.animation.clone();
+                    if let Some(states) = animators.get_mut(id) {
+                        states.change(exit.anim_id.clone(), Some(animation));
+                    } else {
+                        animators.insert(
+                            id.to_owned(),
+                            AnimatorStates::new(exit.anim_id.clone(), animation),
+                        );
+                    }
+                }
+                Some((
+                    placement.index,
+                    placement.possible_key.clone(),
+                    placement.layout.clone(),
+                    component,
+                ))
+            })
+            .collect::<Vec<_>>();
+        departing.sort_by_key(|item| item.0);
+        for (index, possible_key, layout, component) in departing {
+            let slot = self.process_node(
+                WidgetNode::Component(component),
+                states,
+                path.to_vec(),
+                messages,
+                new_states,
+                used_ids,
+                possible_key,
+                master_shared_props.clone(),
+                message_sender,
+                signal_sender,
+                process_context,
+            );
+            if let Some(node) = T::from_exit_layout(slot, &layout) {
+                items.insert(index.min(items.len()), node);
+            }
+        }
+        items
+    }
+
+    // widgets with layout transition that got laid out at new place start their transitions and
+    // get their content transform inverted already for the frame rendered with this layout.
+    fn start_layout_transitions(&mut self) {
+        for (id, transition) in &mut self.layout_transitions {
+            let rect = match self.layout.items.get(id) {
+                Some(item) => item.ui_space,
+                None => continue,
+            };
+            let from = match transition.rect.replace(rect) {
+                Some(from) if from != rect => from,
+                _ => continue,
+            };
+            if transition.props.duration <= 0.0 {
+                continue;
+            }
+            let mut state = match self.state_changes.get(id) {
+                Some(state) => state.clone(),
+                None => self.states.get(id).cloned().unwrap_or_default(),
+            };
+            let animator = self.animators.entry(id.to_owned()).or_default();
+            let transform =
+                start_layout_transition(&transition.props, &mut state, animator, from, rect);
+            self.state_changes.insert(id.to_owned(), state);
+            if let Some(unit_transform) = Self::find_unit_transform(&mut self.rendered_tree, id) {
+                *unit_transform = transform;
+            }
+        }
+    }
+
+    fn find_unit_transform<'a>(
+        unit: &'a mut WidgetUnit,
+        id: &WidgetId,
+    ) -> Option<&'a mut Transform> {
+        match unit {
+            WidgetUnit::None | WidgetUnit::PortalBox(_) => None,
+            WidgetUnit::AreaBox(b) => Self::find_unit_transform(&mut b.slot, id),
+            WidgetUnit::ContentBox(b) => {
+                if &b.id == id {
+                    return Some(&mut b.transform);
+                }
+                b.items
+                    .iter_mut()
+                    .find_map(|item| Self::find_unit_transform(&mut item.slot, id))
+            }
+            WidgetUnit::FlexBox(b) => {
+                if &b.id == id {
+                    return Some(&mut b.transform);
+                }
+                b.items
+                    .iter_mut()
+                    .find_map(|item| Self::find_unit_transform(&mut item.slot, id))
+            }
+            WidgetUnit::GridBox(b) => {
+                if &b.id == id {
+                    return Some(&mut b.transform);
+                }
+                b.items
+                    .iter_mut()
+                    .find_map(|item| Self::find_unit_transform(&mut item.slot, id))
+            }
+            WidgetUnit::MasonryBox(b) => {
+                if &b.id == id {
+                    return Some(&mut b.transform);
+                }
+                b.items
+                    .iter_mut()
+                    .find_map(|item| Self::find_unit_transform(&mut item.slot, id))
+            }
+            WidgetUnit::SizeBox(b) => {
+                if &b.id == id {
+                    return Some(&mut b.transform);
+                }
+                Self::find_unit_transform(&mut b.slot, id)
+            }
+            WidgetUnit::ImageBox(b) if &b.id == id => Some(&mut b.transform),
+            WidgetUnit::TextBox(b) if &b.id == id => Some(&mut b.transform),
+            WidgetUnit::RichTextBox(b) if &b.id == id => Some(&mut b.transform),
+            WidgetUnit::CustomBox(b) if &b.id == id => Some(&mut b.transform),
+            WidgetUnit::ImageBox(_)
+            | WidgetUnit::TextBox(_)
+            | WidgetUnit::RichTextBox(_)
+            | WidgetUnit::CustomBox(_) => None,
+        }
+    }
+
     fn teleport_portals(mut root: WidgetUnit) -> WidgetUnit {
         let count = Self::estimate_portals(&root);
         if count == 0 {
@@ -1208,7 +1721,11 @@ impl Application {
     fn estimate_portals(unit: &WidgetUnit) -> usize {
         let mut count = 0;
         match unit {
-            WidgetUnit::None | WidgetUnit::ImageBox(_) | WidgetUnit::TextBox(_) => {}
+            WidgetUnit::None
+            | WidgetUnit::ImageBox(_)
+            | WidgetUnit::TextBox(_)
+            | WidgetUnit::RichTextBox(_)
+            | WidgetUnit::CustomBox(_) => {}
             WidgetUnit::AreaBox(b) => count += Self::estimate_portals(&b.slot),
             WidgetUnit::PortalBox(b) => {
                 count += Self::estimate_portals(match &*b.slot {
@@ -1233,6 +1750,11 @@ impl Application {
                     count += Self::estimate_portals(&item.slot);
                 }
             }
+            WidgetUnit::MasonryBox(b) => {
+                for item in &b.items {
+                    count += Self::estimate_portals(&item.slot);
+                }
+            }
             WidgetUnit::SizeBox(b) => count += Self::estimate_portals(&b.slot),
         }
         count
@@ -1240,7 +1762,11 @@ impl Application {
 
     fn consume_portals(unit: &mut WidgetUnit, bucket: &mut Vec<(WidgetId, PortalBoxSlot)>) {
         match unit {
-            WidgetUnit::None | WidgetUnit::ImageBox(_) | WidgetUnit::TextBox(_) => {}
+            WidgetUnit::None
+            | WidgetUnit::ImageBox(_)
+            | WidgetUnit::TextBox(_)
+            | WidgetUnit::RichTextBox(_)
+            | WidgetUnit::CustomBox(_) => {}
             WidgetUnit::AreaBox(b) => Self::consume_portals(&mut b.slot, bucket),
             WidgetUnit::PortalBox(b) => {
                 let PortalBox {
@@ -1272,6 +1798,11 @@ impl Application {
                     Self::consume_portals(&mut item.slot, bucket);
                 }
             }
+            WidgetUnit::MasonryBox(b) => {
+                for item in &mut b.items {
+                    Self::consume_portals(&mut item.slot, bucket);
+                }
+            }
             WidgetUnit::SizeBox(b) => Self::consume_portals(&mut b.slot, bucket),
         }
     }
@@ -1288,7 +1819,9 @@ impl Application {
                     WidgetUnit::None
                     | WidgetUnit::PortalBox(_)
                     | WidgetUnit::ImageBox(_)
-                    | WidgetUnit::TextBox(_) => {}
+                    | WidgetUnit::TextBox(_)
+                    | WidgetUnit::RichTextBox(_)
+                    | WidgetUnit::CustomBox(_) => {}
                     WidgetUnit::AreaBox(b) => {
                         match slot {
                             PortalBoxSlot::Slot(slot) => b.slot = Box::new(slot),
@@ -1366,6 +1899,22 @@ impl Application {
                             }
                         }
                     }
+                    WidgetUnit::MasonryBox(b) => {
+                        b.items.push(MasonryBoxItem {
+                            slot: match slot {
+                                PortalBoxSlot::Slot(slot) => slot,
+                                PortalBoxSlot::ContentItem(item) => item.slot,
+                                PortalBoxSlot::FlexItem(item) => item.slot,
+                                PortalBoxSlot::GridItem(item) => item.slot,
+                            },
+                            ..Default::default()
+                        });
+                        for item in &mut b.items {
+                            if !Self::inject_portals(&mut item.slot, portals) {
+                                return false;
+                            }
+                        }
+                    }
                     WidgetUnit::SizeBox(b) => {
                         match slot {
                             PortalBoxSlot::Slot(slot) => b.slot = Box::new(slot),
@@ -1448,6 +1997,9 @@ impl Application {
             WidgetUnitNode::GridBox(data) => {
                 WidgetUnitNodePrefab::GridBox(self.grid_box_to_prefab(data)?)
             }
+            WidgetUnitNode::MasonryBox(data) => {
+                WidgetUnitNodePrefab::MasonryBox(self.masonry_box_to_prefab(data)?)
+            }
             WidgetUnitNode::SizeBox(data) => {
                 WidgetUnitNodePrefab::SizeBox(self.size_box_to_prefab(data)?)
             }
@@ -1457,6 +2009,12 @@ impl Application {
             WidgetUnitNode::TextBox(data) => {
                 WidgetUnitNodePrefab::TextBox(self.text_box_to_prefab(data)?)
             }
+            WidgetUnitNode::RichTextBox(data) => {
+                WidgetUnitNodePrefab::RichTextBox(self.rich_text_box_to_prefab(data)?)
+            }
+            WidgetUnitNode::CustomBox(data) => {
+                WidgetUnitNodePrefab::CustomBox(self.custom_box_to_prefab(data)?)
+            }
         })
     }
 
@@ -1531,7 +2089,9 @@ impl Application {
                 })
                 .collect::<Result<_, ApplicationError>>()?,
             clipping: data.clipping,
+            layout_direction: data.layout_direction,
             transform: data.transform,
+            constraints: data.constraints.to_owned(),
         })
     }
 
@@ -1555,6 +2115,11 @@ impl Application {
             direction: data.direction,
             separation: data.separation,
             wrap: data.wrap,
+            wrap_reverse: data.wrap_reverse,
+            cross_separation: data.cross_separation,
+            justify_content: data.justify_content,
+            align_content: data.align_content,
+            layout_direction: data.layout_direction,
             transform: data.transform,
         })
     }
@@ -1578,6 +2143,37 @@ impl Application {
                 .collect::<Result<_, ApplicationError>>()?,
             cols: data.cols,
             rows: data.rows,
+            column_tracks: data.column_tracks.to_owned(),
+            row_tracks: data.row_tracks.to_owned(),
+            column_gap: data.column_gap,
+            row_gap: data.row_gap,
+            layout_direction: data.layout_direction,
+            transform: data.transform,
+        })
+    }
+
+    fn masonry_box_to_prefab(
+        &self,
+        data: &MasonryBoxNode,
+    ) -> Result<MasonryBoxNodePrefab, ApplicationError> {
+        Ok(MasonryBoxNodePrefab {
+            id: data.id.to_owned(),
+            props: self.props_registry.serialize(&data.props)?,
+            items: data
+                .items
+                .iter()
+                .map(|v| {
+                    Ok(MasonryBoxItemNodePrefab {
+                        slot: self.node_to_prefab(&v.slot)?,
+                        layout: v.layout.clone(),
+                    })
+                })
+                .collect::<Result<_, ApplicationError>>()?,
+            columns: data.columns,
+            min_column_width: data.min_column_width,
+            column_gap: data.column_gap,
+            row_gap: data.row_gap,
+            layout_direction: data.layout_direction,
             transform: data.transform,
         })
     }
@@ -1592,6 +2188,7 @@ impl Application {
             slot: Box::new(self.node_to_prefab(&data.slot)?),
             width: data.width,
             height: data.height,
+            constraints: data.constraints,
             margin: data.margin,
             transform: data.transform,
         })
@@ -1608,6 +2205,7 @@ impl Application {
             height: data.height,
             content_keep_aspect_ratio: data.content_keep_aspect_ratio,
             material: data.material.clone(),
+            baseline_offset: data.baseline_offset,
             transform: data.transform,
         })
     }
@@ -1625,12 +2223,44 @@ impl Application {
             horizontal_align: data.horizontal_align,
             vertical_align: data.vertical_align,
             direction: data.direction,
+            wrap: data.wrap,
+            overflow: data.overflow,
+            max_lines: data.max_lines,
+            line_height: data.line_height,
             font: data.font.clone(),
             color: data.color,
             transform: data.transform,
         })
     }
 
+    fn rich_text_box_to_prefab(
+        &self,
+        data: &RichTextBoxNode,
+    ) -> Result<RichTextBoxNodePrefab, ApplicationError> {
+        Ok(RichTextBoxNodePrefab {
+            id: data.id.to_owned(),
+            props: self.props_registry.serialize(&data.props)?,
+            spans: data.spans.clone(),
+            width: data.width,
+            height: data.height,
+            horizontal_align: data.horizontal_align,
+            vertical_align: data.vertical_align,
+            transform: data.transform,
+        })
+    }
+
+    fn custom_box_to_prefab(
+        &self,
+        data: &CustomBoxNode,
+    ) -> Result<CustomBoxNodePrefab, ApplicationError> {
+        Ok(CustomBoxNodePrefab {
+            id: data.id.to_owned(),
+            props: self.props_registry.serialize(&data.props)?,
+            type_name: data.type_name.clone(),
+            transform: data.transform,
+        })
+    }
+
     fn node_from_prefab(&self, data: WidgetNodePrefab) -> Result<WidgetNode, ApplicationError> {
         Ok(match data {
             WidgetNodePrefab::None => WidgetNode::None,
@@ -1696,6 +2326,9 @@ impl Application {
             WidgetUnitNodePrefab::GridBox(data) => {
                 WidgetUnitNode::GridBox(self.grid_box_from_prefab(data)?)
             }
+            WidgetUnitNodePrefab::MasonryBox(data) => {
+                WidgetUnitNode::MasonryBox(self.masonry_box_from_prefab(data)?)
+            }
             WidgetUnitNodePrefab::SizeBox(data) => {
                 WidgetUnitNode::SizeBox(self.size_box_from_prefab(data)?)
             }
@@ -1705,6 +2338,12 @@ impl Application {
             WidgetUnitNodePrefab::TextBox(data) => {
                 WidgetUnitNode::TextBox(self.text_box_from_prefab(data)?)
             }
+            WidgetUnitNodePrefab::RichTextBox(data) => {
+                WidgetUnitNode::RichTextBox(self.rich_text_box_from_prefab(data)?)
+            }
+            WidgetUnitNodePrefab::CustomBox(data) => {
+                WidgetUnitNode::CustomBox(self.custom_box_from_prefab(data)?)
+            }
         })
     }
 
@@ -1779,7 +2418,9 @@ impl Application {
                 })
                 .collect::<Result<_, ApplicationError>>()?,
             clipping: data.clipping,
+            layout_direction: data.layout_direction,
             transform: data.transform,
+            constraints: data.constraints,
         })
     }
 
@@ -1803,6 +2444,11 @@ impl Application {
             direction: data.direction,
             separation: data.separation,
             wrap: data.wrap,
+            wrap_reverse: data.wrap_reverse,
+            cross_separation: data.cross_separation,
+            justify_content: data.justify_content,
+            align_content: data.align_content,
+            layout_direction: data.layout_direction,
             transform: data.transform,
         })
     }
@@ -1826,6 +2472,37 @@ impl Application {
                 .collect::<Result<_, ApplicationError>>()?,
             cols: data.cols,
             rows: data.rows,
+            column_tracks: data.column_tracks,
+            row_tracks: data.row_tracks,
+            column_gap: data.column_gap,
+            row_gap: data.row_gap,
+            layout_direction: data.layout_direction,
+            transform: data.transform,
+        })
+    }
+
+    fn masonry_box_from_prefab(
+        &self,
+        data: MasonryBoxNodePrefab,
+    ) -> Result<MasonryBoxNode, ApplicationError> {
+        Ok(MasonryBoxNode {
+            id: data.id,
+            props: self.props_registry.deserialize(data.props)?,
+            items: data
+                .items
+                .into_iter()
+                .map(|v| {
+                    Ok(MasonryBoxItemNode {
+                        slot: self.node_from_prefab(v.slot)?,
+                        layout: v.layout,
+                    })
+                })
+                .collect::<Result<_, ApplicationError>>()?,
+            columns: data.columns,
+            min_column_width: data.min_column_width,
+            column_gap: data.column_gap,
+            row_gap: data.row_gap,
+            layout_direction: data.layout_direction,
             transform: data.transform,
         })
     }
@@ -1840,6 +2517,7 @@ impl Application {
             slot: Box::new(self.node_from_prefab(*data.slot)?),
             width: data.width,
             height: data.height,
+            constraints: data.constraints,
             margin: data.margin,
             transform: data.transform,
         })
@@ -1856,6 +2534,7 @@ impl Application {
             height: data.height,
             content_keep_aspect_ratio: data.content_keep_aspect_ratio,
             material: data.material,
+            baseline_offset: data.baseline_offset,
             transform: data.transform,
         })
     }
@@ -1873,11 +2552,43 @@ impl Application {
             horizontal_align: data.horizontal_align,
             vertical_align: data.vertical_align,
             direction: data.direction,
+            wrap: data.wrap,
+            overflow: data.overflow,
+            max_lines: data.max_lines,
+            line_height: data.line_height,
             font: data.font,
             color: data.color,
             transform: data.transform,
         })
     }
+
+    fn rich_text_box_from_prefab(
+        &self,
+        data: RichTextBoxNodePrefab,
+    ) -> Result<RichTextBoxNode, ApplicationError> {
+        Ok(RichTextBoxNode {
+            id: data.id,
+            props: self.props_registry.deserialize(data.props)?,
+            spans: data.spans,
+            width: data.width,
+            height: data.height,
+            horizontal_align: data.horizontal_align,
+            vertical_align: data.vertical_align,
+            transform: data.transform,
+        })
+    }
+
+    fn custom_box_from_prefab(
+        &self,
+        data: CustomBoxNodePrefab,
+    ) -> Result<CustomBoxNode, ApplicationError> {
+        Ok(CustomBoxNode {
+            id: data.id,
+            props: self.props_registry.deserialize(data.props)?,
+            type_name: data.type_name,
+            transform: data.transform,
+        })
+    }
 }
 
 /// Allows you to get mutable or immutable references to data exposed by the host of the RAUI
@@ -5,6 +5,7 @@ use crate::{
     widget::{
         component::{
             interactive::navigation::{NavDirection, NavJump, NavScroll, NavSignal, NavType},
+            rich_text_box::RichTextLinkMessage,
             RelativeLayoutListenerSignal, ResizeListenerSignal,
         },
         unit::WidgetUnit,
@@ -71,7 +72,7 @@ impl DefaultInteractionsEngineResult {
 pub struct DefaultInteractionsEngine {
     pub deselect_when_no_button_found: bool,
     resize_listeners: HashMap<WidgetId, Vec2>,
-    relative_layout_listeners: HashMap<WidgetId, (WidgetId, Vec2, Rect)>,
+    relative_layout_listeners: HashMap<WidgetId, (WidgetId, Vec2, Rect, Rect)>,
     interactions_queue: VecDeque<Interaction>,
     containers: HashMap<WidgetId, HashSet<WidgetId>>,
     items_owners: HashMap<WidgetId, WidgetId>,
@@ -83,6 +84,7 @@ pub struct DefaultInteractionsEngine {
     locked_widget: Option<WidgetId>,
     focused_text_input: Option<WidgetId>,
     sorted_items_ids: Vec<WidgetId>,
+    pressed_link: Option<(WidgetId, String)>,
 }
 
 impl DefaultInteractionsEngine {
@@ -116,6 +118,7 @@ impl DefaultInteractionsEngine {
             locked_widget: None,
             focused_text_input: None,
             sorted_items_ids: vec![],
+            pressed_link: None,
         }
     }
 
@@ -222,6 +225,11 @@ impl DefaultInteractionsEngine {
                     self.cache_sorted_items_ids_inner(&item.slot);
                 }
             }
+            WidgetUnit::MasonryBox(unit) => {
+                for item in &unit.items {
+                    self.cache_sorted_items_ids_inner(&item.slot);
+                }
+            }
             WidgetUnit::SizeBox(unit) => {
                 self.cache_sorted_items_ids_inner(&unit.slot);
             }
@@ -233,6 +241,11 @@ impl DefaultInteractionsEngine {
         if self.locked_widget.is_some() || self.selected_chain.last() == id.as_ref() {
             return false;
         }
+        if let Some(id) = &id {
+            if app.is_widget_departing(id) {
+                return false;
+            }
+        }
         match (self.selected_chain.is_empty(), id) {
             (false, None) => {
                 for id in std::mem::take(&mut self.selected_chain).iter().rev() {
@@ -717,7 +730,9 @@ impl DefaultInteractionsEngine {
                             if let (Some(wid), Some(oid)) =
                                 (idref.read(), self.find_scroll_view_content(&id))
                             {
-                                if let Some(rect) = app.layout_data().rect_relative_to(&wid, &oid) {
+                                if let Some(rect) =
+                                    app.layout_data().logical_rect_relative_to(&wid, &oid)
+                                {
                                     let aitem = app.layout_data().find_or_ui_space(oid.path());
                                     let bitem = app.layout_data().find_or_ui_space(id.path());
                                     let x = lerp(rect.left, rect.right, anchor.x);
@@ -756,141 +771,54 @@ impl DefaultInteractionsEngine {
         }
     }
 
+    /// Finds topmost button under the point, together with the point position normalized to
+    /// the button size (measured from the start edge for right-to-left laid out buttons).
     fn find_button(&self, app: &Application, x: Scalar, y: Scalar) -> Option<(WidgetId, Vec2)> {
-        self.find_button_inner(app, x, y, app.rendered_tree(), app.layout_data().ui_space)
+        let layout = app.layout_data();
+        layout
+            .hit_test(app.rendered_tree(), Vec2 { x, y })
+            .into_iter()
+            .filter(|hit| !app.is_widget_departing(&hit.id))
+            .find(|hit| self.buttons.contains_key(&hit.id))
+            .map(|hit| {
+                let (size, direction) = layout
+                    .items
+                    .get(&hit.id)
+                    .map(|item| (item.local_space.size(), item.direction))
+                    .unwrap_or_default();
+                let pos = Vec2 {
+                    x: if size.x > 0.0 {
+                        let x = hit.local_point.x / size.x;
+                        if direction.is_right_to_left() {
+                            1.0 - x
+                        } else {
+                            x
+                        }
+                    } else {
+                        0.0
+                    },
+                    y: if size.y > 0.0 {
+                        hit.local_point.y / size.y
+                    } else {
+                        0.0
+                    },
+                };
+                (hit.id, pos)
+            })
     }
 
-    fn find_button_inner(
-        &self,
-        app: &Application,
-        x: Scalar,
-        y: Scalar,
-        unit: &WidgetUnit,
-        mut clip: Rect,
-    ) -> Option<(WidgetId, Vec2)> {
-        if x < clip.left || x > clip.right || y < clip.top || y > clip.bottom {
-            return None;
-        }
-        let mut result = None;
-        if let Some(data) = unit.as_data() {
-            if self.buttons.contains_key(data.id()) {
-                if let Some(layout) = app.layout_data().items.get(data.id()) {
-                    let rect = layout.ui_space;
-                    if x >= rect.left && x <= rect.right && y >= rect.top && y <= rect.bottom {
-                        let size = rect.size();
-                        let pos = Vec2 {
-                            x: if size.x > 0.0 {
-                                (x - rect.left) / size.x
-                            } else {
-                                0.0
-                            },
-                            y: if size.y > 0.0 {
-                                (y - rect.top) / size.y
-                            } else {
-                                0.0
-                            },
-                        };
-                        result = Some((data.id().to_owned(), pos));
-                    }
-                }
-            }
-        }
-        match unit {
-            WidgetUnit::AreaBox(unit) => {
-                if let Some(id) = self.find_button_inner(app, x, y, &unit.slot, clip) {
-                    result = Some(id);
-                }
-            }
-            WidgetUnit::ContentBox(unit) => {
-                if unit.clipping {
-                    if let Some(item) = app.layout_data().items.get(&unit.id) {
-                        clip = item.ui_space;
-                    }
-                }
-                for item in &unit.items {
-                    if let Some(id) = self.find_button_inner(app, x, y, &item.slot, clip) {
-                        result = Some(id);
-                    }
-                }
-            }
-            WidgetUnit::FlexBox(unit) => {
-                for item in &unit.items {
-                    if let Some(id) = self.find_button_inner(app, x, y, &item.slot, clip) {
-                        result = Some(id);
-                    }
-                }
-            }
-            WidgetUnit::GridBox(unit) => {
-                for item in &unit.items {
-                    if let Some(id) = self.find_button_inner(app, x, y, &item.slot, clip) {
-                        result = Some(id);
-                    }
-                }
-            }
-            WidgetUnit::SizeBox(unit) => {
-                if let Some(id) = self.find_button_inner(app, x, y, &unit.slot, clip) {
-                    result = Some(id);
-                }
-            }
-            _ => {}
-        }
-        result
+    /// Finds link of rich text box span under the point.
+    fn find_link(&self, app: &Application, x: Scalar, y: Scalar) -> Option<(WidgetId, String)> {
+        app.layout_data()
+            .rich_text_link_at(app.rendered_tree(), Vec2 { x, y })
+            .filter(|(id, _)| !app.is_widget_departing(id))
     }
 
     fn does_hover_widget(&self, app: &Application, x: Scalar, y: Scalar) -> bool {
-        self.does_hover_widget_inner(app, x, y, app.rendered_tree())
-    }
-
-    fn does_hover_widget_inner(
-        &self,
-        app: &Application,
-        x: Scalar,
-        y: Scalar,
-        unit: &WidgetUnit,
-    ) -> bool {
-        if let Some(data) = unit.as_data() {
-            if let Some(layout) = app.layout_data().items.get(data.id()) {
-                let rect = layout.ui_space;
-                if x >= rect.left && x <= rect.right && y >= rect.top && y <= rect.bottom {
-                    return true;
-                }
-            }
-        }
-        match unit {
-            WidgetUnit::AreaBox(unit) => {
-                if self.does_hover_widget_inner(app, x, y, &unit.slot) {
-                    return true;
-                }
-            }
-            WidgetUnit::ContentBox(unit) => {
-                for item in &unit.items {
-                    if self.does_hover_widget_inner(app, x, y, &item.slot) {
-                        return true;
-                    }
-                }
-            }
-            WidgetUnit::FlexBox(unit) => {
-                for item in &unit.items {
-                    if self.does_hover_widget_inner(app, x, y, &item.slot) {
-                        return true;
-                    }
-                }
-            }
-            WidgetUnit::GridBox(unit) => {
-                for item in &unit.items {
-                    if self.does_hover_widget_inner(app, x, y, &item.slot) {
-                        return true;
-                    }
-                }
-            }
-            WidgetUnit::SizeBox(unit) => {
-                if self.does_hover_widget_inner(app, x, y, &unit.slot) {
-                    return true;
-                }
-            }
-            _ => {}
-        }
-        false
+        app.layout_data()
+            .hit_test(app.rendered_tree(), Vec2 { x, y })
+            .iter()
+            .any(|hit| !app.is_widget_departing(&hit.id))
     }
 }
 
@@ -924,13 +852,19 @@ impl InteractionsEngine<DefaultInteractionsEngineResult, ()> for DefaultInteract
             } else if let Some(signal) = signal.as_any().downcast_ref() {
                 match signal {
                     RelativeLayoutListenerSignal::Register(relative_to) => {
-                        if let (Some(item), Some(rect)) = (
+                        if let (Some(item), Some(rect), Some(safe_area)) = (
                             app.layout_data().items.get(relative_to),
-                            app.layout_data().rect_relative_to(id, relative_to),
+                            app.layout_data().logical_rect_relative_to(id, relative_to),
+                            app.layout_data().safe_area_relative_to(relative_to),
                         ) {
                             self.relative_layout_listeners.insert(
                                 id.to_owned(),
-                                (relative_to.to_owned(), item.local_space.size(), rect),
+                                (
+                                    relative_to.to_owned(),
+                                    item.local_space.size(),
+                                    rect,
+                                    safe_area,
+                                ),
                             );
                             to_relative_layout.insert(id.to_owned());
                         }
@@ -1050,10 +984,11 @@ impl InteractionsEngine<DefaultInteractionsEngineResult, ()> for DefaultInteract
                 }
             }
         }
-        for (k, (r, s, v)) in &mut self.relative_layout_listeners {
-            if let (Some(item), Some(rect)) = (
+        for (k, (r, s, v, a)) in &mut self.relative_layout_listeners {
+            if let (Some(item), Some(rect), Some(safe_area)) = (
                 app.layout_data().items.get(r),
-                app.layout_data().rect_relative_to(k, r),
+                app.layout_data().logical_rect_relative_to(k, r),
+                app.layout_data().safe_area_relative_to(r),
             ) {
                 let size = item.local_space.size();
                 if to_relative_layout.contains(k)
@@ -1068,6 +1003,15 @@ impl InteractionsEngine<DefaultInteractionsEngineResult, ()> for DefaultInteract
                     *s = size;
                     *v = rect;
                 }
+                if to_relative_layout.contains(k)
+                    || (a.left - safe_area.left).abs() >= 1.0e-6
+                    || (a.right - safe_area.right).abs() >= 1.0e-6
+                    || (a.top - safe_area.top).abs() >= 1.0e-6
+                    || (a.bottom - safe_area.bottom).abs() >= 1.0e-6
+                {
+                    app.send_message(k, RelativeLayoutListenerSignal::SafeAreaChange(safe_area));
+                    *a = safe_area;
+                }
             }
         }
         if let Some(idref) = to_select {
@@ -1202,7 +1146,16 @@ impl InteractionsEngine<DefaultInteractionsEngineResult, ()> for DefaultInteract
                     }
                 }
                 Interaction::PointerDown(button, Vec2 { x, y }) => {
-                    if let Some((found, pos)) = self.find_button(app, x, y) {
+                    let link = match button {
+                        PointerButton::Trigger => self.find_link(app, x, y),
+                        PointerButton::Context => None,
+                    };
+                    self.pressed_link = None;
+                    if let Some(link) = link {
+                        result.captured_pointer_location = true;
+                        result.captured_pointer_action = true;
+                        self.pressed_link = Some(link);
+                    } else if let Some((found, pos)) = self.find_button(app, x, y) {
                         self.select_item(app, Some(found));
                         result.captured_pointer_location = true;
                         let action = match button {
@@ -1235,13 +1188,29 @@ impl InteractionsEngine<DefaultInteractionsEngineResult, ()> for DefaultInteract
                         }
                     }
                 }
-                Interaction::PointerUp(button, _) => {
-                    let action = match button {
-                        PointerButton::Trigger => NavSignal::Accept(false),
-                        PointerButton::Context => NavSignal::Context(false),
-                    };
-                    if self.send_to_selected_button(app, false, action) {
+                Interaction::PointerUp(button, Vec2 { x, y }) => {
+                    // link gets clicked only when pointer is released over the pressed one.
+                    if let Some(pressed) = self.pressed_link.take() {
+                        result.captured_pointer_location = true;
                         result.captured_pointer_action = true;
+                        if self.find_link(app, x, y).as_ref() == Some(&pressed) {
+                            let (id, link) = pressed;
+                            app.send_message(
+                                &id,
+                                RichTextLinkMessage {
+                                    sender: id.to_owned(),
+                                    link,
+                                },
+                            );
+                        }
+                    } else {
+                        let action = match button {
+                            PointerButton::Trigger => NavSignal::Accept(false),
+                            PointerButton::Context => NavSignal::Context(false),
+                        };
+                        if self.send_to_selected_button(app, false, action) {
+                            result.captured_pointer_action = true;
+                        }
                     }
                 }
             }
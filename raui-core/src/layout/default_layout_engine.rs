@@ -1,45 +1,1058 @@
 use crate::{
-    layout::{CoordsMapping, Layout, LayoutEngine, LayoutItem, LayoutNode},
+    layout::{
+        hash_serializer::hash_serialized, CoordsMapping, CustomUnitMeasurementEngine, FontMetrics,
+        ImageMeasurementEngine, Layout, LayoutEngine, LayoutItem, LayoutNode, LayoutTextFragment,
+        TextMeasurementEngine,
+    },
     widget::{
         unit::{
             area::AreaBox,
-            content::ContentBox,
-            flex::FlexBox,
-            grid::GridBox,
-            image::{ImageBox, ImageBoxSizeValue},
+            content::{
+                ContentBox, ContentBoxItemLayout, LayoutConstraint, LayoutConstraintRelation,
+                LayoutConstraintStrength, LayoutConstraintTerm, LayoutConstraintVariable,
+            },
+            custom::CustomBox,
+            flex::{FlexBox, FlexBoxItemLayout},
+            grid::{GridBox, GridBoxTrackSize},
+            image::{ImageBox, ImageBoxMaterial, ImageBoxSizeValue},
+            masonry::MasonryBox,
+            rich_text::{RichTextBox, RichTextBoxSpanContent},
             size::{SizeBox, SizeBoxSizeValue},
-            text::{TextBox, TextBoxSizeValue},
+            text::{
+                TextBox, TextBoxFont, TextBoxHorizontalAlign, TextBoxOverflow, TextBoxSizeValue,
+                TextBoxVerticalAlign, TextBoxWrap,
+            },
             WidgetUnit,
         },
-        utils::{lerp, Rect, Vec2},
+        utils::{lerp, IntRect, LayoutDirection, Rect, Vec2},
         WidgetId,
     },
-    Scalar,
+    Integer, Scalar,
+};
+use cassowary::{strength, Constraint, Expression, RelationalOperator, Solver, Term, Variable};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::{Arc, Mutex, MutexGuard},
 };
-use std::collections::HashMap;
 
-#[derive(Debug, Default, Copy, Clone)]
-pub struct DefaultLayoutEngine;
+/// Layout of single node remembered between layout passes
+#[derive(Debug, Clone)]
+struct LayoutCacheEntry {
+    hash: u64,
+    size_available: Vec2,
+    local_space: Rect,
+    /// Children ids with their local space positioned by this node
+    children: Vec<(WidgetId, Rect)>,
+    fragments: Vec<LayoutTextFragment>,
+}
 
-impl DefaultLayoutEngine {
-    pub fn layout_node(size_available: Vec2, unit: &WidgetUnit) -> Option<LayoutNode> {
-        match unit {
-            WidgetUnit::None | WidgetUnit::PortalBox(_) => None,
-            WidgetUnit::AreaBox(b) => Self::layout_area_box(size_available, b),
-            WidgetUnit::ContentBox(b) => Self::layout_content_box(size_available, b),
-            WidgetUnit::FlexBox(b) => Self::layout_flex_box(size_available, b),
-            WidgetUnit::GridBox(b) => Self::layout_grid_box(size_available, b),
-            WidgetUnit::SizeBox(b) => Self::layout_size_box(size_available, b),
-            WidgetUnit::ImageBox(b) => Self::layout_image_box(size_available, b),
-            WidgetUnit::TextBox(b) => Self::layout_text_box(size_available, b),
+#[derive(Debug, Default, Clone)]
+struct LayoutCache {
+    /// Nodes laid out in previous pass
+    previous: HashMap<WidgetId, LayoutCacheEntry>,
+    /// Nodes laid out (or reused) in current pass
+    current: HashMap<WidgetId, LayoutCacheEntry>,
+    /// Content hashes of units in current pass
+    hashes: HashMap<WidgetId, u64>,
+    /// Minimal sizes measured in current pass, keyed by unit id, axis and available size
+    min_sizes: HashMap<(WidgetId, bool, [u64; 2]), Scalar>,
+    /// Constraints rejected by solver, keyed by content box id
+    rejected_constraints: HashMap<WidgetId, Vec<LayoutConstraint>>,
+}
+
+/// Layout cache used by layout methods taking `&self`, behind mutex to keep engine `Sync`.
+#[derive(Debug, Default)]
+struct SharedLayoutCache(Mutex<LayoutCache>);
+
+impl SharedLayoutCache {
+    fn lock(&self) -> MutexGuard<'_, LayoutCache> {
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn get_mut(&mut self) -> &mut LayoutCache {
+        self.0.get_mut().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl Clone for SharedLayoutCache {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.lock().clone()))
+    }
+}
+
+impl LayoutCache {
+    fn begin(&mut self, tree: &WidgetUnit, measurements: &CustomUnitMeasurements) {
+        self.previous = std::mem::take(&mut self.current);
+        self.hashes.clear();
+        self.min_sizes.clear();
+        hash_unit(tree, measurements, &mut self.hashes);
+    }
+
+    fn end(&mut self) {
+        self.previous.clear();
+        self.hashes.clear();
+        self.min_sizes.clear();
+        let current = &self.current;
+        self.rejected_constraints
+            .retain(|id, _| current.contains_key(id));
+    }
+
+    fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+        self.rejected_constraints.clear();
+    }
+
+    fn reject_constraints(&mut self, id: &WidgetId, constraints: Vec<LayoutConstraint>) {
+        if constraints.is_empty() {
+            self.rejected_constraints.remove(id);
+        } else {
+            self.rejected_constraints.insert(id.to_owned(), constraints);
+        }
+    }
+
+    fn restore(&mut self, id: &WidgetId, size_available: Vec2) -> Option<LayoutNode> {
+        let entry = self.previous.get(id)?;
+        if Some(&entry.hash) != self.hashes.get(id) || entry.size_available != size_available {
+            return None;
+        }
+        let node = Self::restore_node(&self.previous, id)?;
+        self.keep(&node);
+        Some(node)
+    }
+
+    fn restore_node(
+        entries: &HashMap<WidgetId, LayoutCacheEntry>,
+        id: &WidgetId,
+    ) -> Option<LayoutNode> {
+        let entry = entries.get(id)?;
+        let children = entry
+            .children
+            .iter()
+            .map(|(id, local_space)| {
+                let mut child = Self::restore_node(entries, id)?;
+                child.local_space = *local_space;
+                Some(child)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(LayoutNode {
+            id: id.to_owned(),
+            local_space: entry.local_space,
+            children,
+            fragments: entry.fragments.clone(),
+        })
+    }
+
+    fn keep(&mut self, node: &LayoutNode) {
+        if let Some(entry) = self.previous.remove(&node.id) {
+            self.current.insert(node.id.to_owned(), entry);
+        }
+        for child in &node.children {
+            self.keep(child);
+        }
+    }
+
+    fn store(&mut self, size_available: Vec2, node: &LayoutNode) {
+        if let Some(hash) = self.hashes.get(&node.id) {
+            self.current.insert(
+                node.id.to_owned(),
+                LayoutCacheEntry {
+                    hash: *hash,
+                    size_available,
+                    local_space: node.local_space,
+                    children: node
+                        .children
+                        .iter()
+                        .map(|child| (child.id.to_owned(), child.local_space))
+                        .collect(),
+                    fragments: node.fragments.clone(),
+                },
+            );
+        }
+    }
+}
+
+fn size_key(size: Vec2) -> [u64; 2] {
+    [size.x.to_bits() as u64, size.y.to_bits() as u64]
+}
+
+/// Calculates hashes of layout affecting content of unit and its descendants.
+fn hash_unit(
+    unit: &WidgetUnit,
+    measurements: &CustomUnitMeasurements,
+    hashes: &mut HashMap<WidgetId, u64>,
+) -> u64 {
+    let mut state = DefaultHasher::new();
+    let id = match unit {
+        WidgetUnit::None => return 0,
+        WidgetUnit::PortalBox(b) => {
+            b.id.hash(&mut state);
+            return state.finish();
+        }
+        WidgetUnit::AreaBox(b) => {
+            hash_unit(&b.slot, measurements, hashes).hash(&mut state);
+            &b.id
+        }
+        WidgetUnit::ContentBox(b) => {
+            for item in &b.items {
+                hash_unit(&item.slot, measurements, hashes).hash(&mut state);
+                hash_serialized(&item.layout, &mut state);
+            }
+            hash_serialized(&b.constraints, &mut state);
+            &b.id
+        }
+        WidgetUnit::FlexBox(b) => {
+            for item in &b.items {
+                hash_unit(&item.slot, measurements, hashes).hash(&mut state);
+                hash_serialized(&item.layout, &mut state);
+            }
+            hash_serialized(&b.direction, &mut state);
+            b.separation.to_bits().hash(&mut state);
+            b.wrap.hash(&mut state);
+            b.wrap_reverse.hash(&mut state);
+            b.cross_separation.map(|v| v.to_bits()).hash(&mut state);
+            hash_serialized(&b.justify_content, &mut state);
+            hash_serialized(&b.align_content, &mut state);
+            &b.id
+        }
+        WidgetUnit::GridBox(b) => {
+            for item in &b.items {
+                hash_unit(&item.slot, measurements, hashes).hash(&mut state);
+                hash_serialized(&item.layout, &mut state);
+            }
+            b.cols.hash(&mut state);
+            b.rows.hash(&mut state);
+            hash_serialized(&b.column_tracks, &mut state);
+            hash_serialized(&b.row_tracks, &mut state);
+            b.column_gap.to_bits().hash(&mut state);
+            b.row_gap.to_bits().hash(&mut state);
+            &b.id
+        }
+        WidgetUnit::MasonryBox(b) => {
+            for item in &b.items {
+                hash_unit(&item.slot, measurements, hashes).hash(&mut state);
+                hash_serialized(&item.layout, &mut state);
+            }
+            b.columns.hash(&mut state);
+            b.min_column_width.to_bits().hash(&mut state);
+            b.column_gap.to_bits().hash(&mut state);
+            b.row_gap.to_bits().hash(&mut state);
+            &b.id
+        }
+        WidgetUnit::SizeBox(b) => {
+            hash_unit(&b.slot, measurements, hashes).hash(&mut state);
+            hash_serialized(&b.width, &mut state);
+            hash_serialized(&b.height, &mut state);
+            hash_serialized(&b.constraints, &mut state);
+            hash_serialized(&b.margin, &mut state);
+            &b.id
+        }
+        WidgetUnit::ImageBox(b) => {
+            hash_serialized(b, &mut state);
+            &b.id
+        }
+        WidgetUnit::TextBox(b) => {
+            hash_serialized(b, &mut state);
+            &b.id
+        }
+        WidgetUnit::RichTextBox(b) => {
+            hash_serialized(b, &mut state);
+            &b.id
+        }
+        WidgetUnit::CustomBox(b) => {
+            b.type_name.hash(&mut state);
+            // units without registered measurement fill available space regardless of props.
+            if let Some(measurement) = measurements.0.get(&b.type_name) {
+                measurement.hash_custom_unit(b, &mut state);
+            }
+            &b.id
+        }
+    };
+    id.hash(&mut state);
+    let hash = state.finish();
+    if id.is_valid() {
+        hashes.insert(id.to_owned(), hash);
+    }
+    hash
+}
+
+/// Part of rich text box span placed in line
+#[derive(Debug, Clone)]
+struct RichTextPiece {
+    span: usize,
+    range: Range<usize>,
+    /// Offset from line start
+    x: Scalar,
+    width: Scalar,
+    ascent: Scalar,
+    descent: Scalar,
+}
+
+/// Line of rich text box
+#[derive(Debug, Default, Clone)]
+struct RichTextLine {
+    pieces: Vec<RichTextPiece>,
+    /// Width of line content, excluding trailing whitespaces
+    width: Scalar,
+    ascent: Scalar,
+    descent: Scalar,
+}
+
+impl RichTextLine {
+    fn push(&mut self, piece: RichTextPiece) {
+        self.ascent = self.ascent.max(piece.ascent);
+        self.descent = self.descent.max(piece.descent);
+        if let Some(last) = self.pieces.last_mut() {
+            // consecutive text of the same span gets merged into single fragment.
+            if last.span == piece.span
+                && last.range.end == piece.range.start
+                && !last.range.is_empty()
+            {
+                last.range.end = piece.range.end;
+                last.width = piece.x + piece.width - last.x;
+                return;
+            }
+        }
+        self.pieces.push(piece);
+    }
+}
+
+const TEXT_ELLIPSIS: &str = "\u{2026}";
+
+/// Number of font scales tried when shrinking text to fit text box
+const TEXT_SHRINK_ITERATIONS: usize = 10;
+
+/// Line of text box laid out by layout engine
+#[derive(Debug, Clone)]
+struct TextBoxLine {
+    /// Byte range of source text shown by line
+    range: Range<usize>,
+    text: String,
+    width: Scalar,
+}
+
+/// Lines of text box laid out by layout engine with font scaled to fit
+#[derive(Debug, Clone)]
+struct TextBoxLines {
+    lines: Vec<TextBoxLine>,
+    font_size: Scalar,
+    metrics: FontMetrics,
+    line_height: Scalar,
+    /// Lines not shown because of lines limit
+    truncated: bool,
+}
+
+impl TextBoxLines {
+    fn size(&self) -> Vec2 {
+        Vec2 {
+            x: self
+                .lines
+                .iter()
+                .fold(0.0, |width, line| line.width.max(width)),
+            y: self.lines.len() as Scalar * self.line_height,
         }
     }
+}
+
+/// Tells if lines of text box are laid out by layout engine. Text boxes with default wrapping and
+/// overflow settings are measured as a whole by text measurement engine and wrapped by renderers.
+fn is_text_box_laid_out(unit: &TextBox) -> bool {
+    unit.direction.is_horizontal()
+        && (unit.wrap != TextBoxWrap::Word
+            || unit.overflow != TextBoxOverflow::Visible
+            || unit.max_lines.is_some()
+            || unit.line_height.is_some())
+}
+
+/// Byte positions of characters boundaries in text, including text end.
+fn char_bounds(text: &str) -> Vec<usize> {
+    text.char_indices()
+        .map(|(index, _)| index)
+        .chain(std::iter::once(text.len()))
+        .collect()
+}
+
+/// Largest number in `0..=count` accepted by `fits`, assuming that numbers smaller than accepted
+/// one are accepted too.
+fn fit_count<F>(count: usize, mut fits: F) -> usize
+where
+    F: FnMut(usize) -> bool,
+{
+    let mut min = 0;
+    let mut max = count;
+    while min < max {
+        let mid = max - (max - min) / 2;
+        if fits(mid) {
+            min = mid;
+        } else {
+            max = mid - 1;
+        }
+    }
+    min
+}
+
+fn mirror_rect(rect: &mut Rect, width: Scalar) {
+    let left = rect.left;
+    rect.left = width - rect.right;
+    rect.right = width - left;
+}
+
+fn unit_layout_direction(unit: &WidgetUnit) -> Option<LayoutDirection> {
+    match unit {
+        WidgetUnit::ContentBox(unit) => unit.layout_direction,
+        WidgetUnit::FlexBox(unit) => unit.layout_direction,
+        WidgetUnit::GridBox(unit) => unit.layout_direction,
+        WidgetUnit::MasonryBox(unit) => unit.layout_direction,
+        _ => None,
+    }
+}
+
+/// Moves baseline aligned children of flex box line so their baselines (given as positions in
+/// container together with bottom margins of items) match the lowest one, returns bottom edge
+/// of moved items margins.
+fn align_flex_line_baselines(
+    children: &mut [LayoutNode],
+    baselines: &[(usize, Scalar, Scalar)],
+) -> Option<Scalar> {
+    let target = baselines
+        .iter()
+        .map(|(_, baseline, _)| *baseline)
+        .fold(None, |a: Option<Scalar>, b| Some(a.map_or(b, |a| a.max(b))))?;
+    let mut result: Scalar = 0.0;
+    for (index, baseline, margin) in baselines {
+        let space = &mut children[*index].local_space;
+        space.top += target - baseline;
+        space.bottom += target - baseline;
+        result = result.max(space.bottom + margin);
+    }
+    Some(result)
+}
 
-    pub fn layout_area_box(size_available: Vec2, unit: &AreaBox) -> Option<LayoutNode> {
+/// Rect of content box item resulting from its anchors, margin and offset
+fn content_item_rect(size_available: Vec2, layout: &ContentBoxItemLayout) -> Rect {
+    let left = lerp(0.0, size_available.x, layout.anchors.left);
+    let left = left + layout.margin.left + layout.offset.x;
+    let right = lerp(0.0, size_available.x, layout.anchors.right);
+    let right = right - layout.margin.right + layout.offset.x;
+    let top = lerp(0.0, size_available.y, layout.anchors.top);
+    let top = top + layout.margin.top + layout.offset.y;
+    let bottom = lerp(0.0, size_available.y, layout.anchors.bottom);
+    let bottom = bottom - layout.margin.bottom + layout.offset.y;
+    Rect {
+        left,
+        right,
+        top,
+        bottom,
+    }
+}
+
+/// Appends terms of constraint variable (of item with [left, right, top, bottom] variables, or of
+/// content box itself when there are none) to solver expression.
+fn push_constraint_term(
+    variable: LayoutConstraintVariable,
+    coefficient: f64,
+    item: Option<&[Variable; 4]>,
+    size_available: Vec2,
+    terms: &mut Vec<Term>,
+    constant: &mut f64,
+) {
+    let width = size_available.x as f64;
+    let height = size_available.y as f64;
+    let (vars, factors, value): (&[usize], &[f64], f64) = match variable {
+        LayoutConstraintVariable::Left => (&[0], &[1.0], 0.0),
+        LayoutConstraintVariable::Right => (&[1], &[1.0], width),
+        LayoutConstraintVariable::Top => (&[2], &[1.0], 0.0),
+        LayoutConstraintVariable::Bottom => (&[3], &[1.0], height),
+        LayoutConstraintVariable::Width => (&[1, 0], &[1.0, -1.0], width),
+        LayoutConstraintVariable::Height => (&[3, 2], &[1.0, -1.0], height),
+        LayoutConstraintVariable::CenterX => (&[0, 1], &[0.5, 0.5], width * 0.5),
+        LayoutConstraintVariable::CenterY => (&[2, 3], &[0.5, 0.5], height * 0.5),
+    };
+    if let Some(item) = item {
+        for (index, factor) in vars.iter().zip(factors.iter()) {
+            terms.push(item[*index] * (factor * coefficient));
+        }
+    } else {
+        *constant += value * coefficient;
+    }
+}
+
+/// Solves content box constraints, using items anchors as weak suggestions of their rects.
+/// Solves rectangles of content box items, returning them along with constraints rejected by
+/// solver.
+fn solve_content_box_constraints(
+    size_available: Vec2,
+    unit: &ContentBox,
+) -> (Vec<Rect>, Vec<LayoutConstraint>) {
+    let variables = unit
+        .items
+        .iter()
+        .map(|_| {
+            [
+                Variable::new(),
+                Variable::new(),
+                Variable::new(),
+                Variable::new(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let keys = unit
+        .items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| Some((item.slot.as_data()?.id().key(), index)))
+        .collect::<HashMap<_, _>>();
+    let mut solver = Solver::new();
+    for (item, [left, right, top, bottom]) in unit.items.iter().zip(variables.iter()) {
+        let rect = content_item_rect(size_available, &item.layout);
+        let constraints = &item.layout.constraints;
+        let width = *right - *left;
+        let height = *bottom - *top;
+        let mut list = vec![
+            Constraint::new(
+                width.clone(),
+                RelationalOperator::GreaterOrEqual,
+                strength::REQUIRED,
+            ),
+            Constraint::new(
+                height.clone(),
+                RelationalOperator::GreaterOrEqual,
+                strength::REQUIRED,
+            ),
+            Constraint::new(
+                width.clone() - constraints.min_width as f64,
+                RelationalOperator::GreaterOrEqual,
+                strength::STRONG,
+            ),
+            Constraint::new(
+                height.clone() - constraints.min_height as f64,
+                RelationalOperator::GreaterOrEqual,
+                strength::STRONG,
+            ),
+        ];
+        if let Some(max_width) = constraints.max_width {
+            list.push(Constraint::new(
+                width - max_width as f64,
+                RelationalOperator::LessOrEqual,
+                strength::STRONG,
+            ));
+        }
+        if let Some(max_height) = constraints.max_height {
+            list.push(Constraint::new(
+                height - max_height as f64,
+                RelationalOperator::LessOrEqual,
+                strength::STRONG,
+            ));
+        }
+        for (variable, value) in &[
+            (left, rect.left),
+            (right, rect.right),
+            (top, rect.top),
+            (bottom, rect.bottom),
+        ] {
+            list.push(Constraint::new(
+                **variable - *value as f64,
+                RelationalOperator::Equal,
+                strength::WEAK,
+            ));
+        }
+        // only required constraints keep item size non negative, so these never conflict.
+        for constraint in list {
+            let _ = solver.add_constraint(constraint);
+        }
+    }
+    let mut rejected = vec![];
+    'constraints: for constraint in &unit.constraints {
+        let mut terms = vec![];
+        let mut constant = -constraint.constant as f64;
+        let sides = [(&constraint.lhs, 1.0), (&constraint.rhs, -1.0)];
+        for (side, sign) in &sides {
+            for LayoutConstraintTerm {
+                item,
+                variable,
+                coefficient,
+            } in side.iter()
+            {
+                let vars = if item.is_empty() {
+                    None
+                } else if let Some(index) = keys.get(item.as_str()) {
+                    Some(&variables[*index])
+                } else {
+                    // skip constraints of items missing from content box.
+                    continue 'constraints;
+                };
+                push_constraint_term(
+                    *variable,
+                    *coefficient as f64 * sign,
+                    vars,
+                    size_available,
+                    &mut terms,
+                    &mut constant,
+                );
+            }
+        }
+        let relation = match constraint.relation {
+            LayoutConstraintRelation::Equal => RelationalOperator::Equal,
+            LayoutConstraintRelation::LessOrEqual => RelationalOperator::LessOrEqual,
+            LayoutConstraintRelation::GreaterOrEqual => RelationalOperator::GreaterOrEqual,
+        };
+        let strength = match constraint.strength {
+            LayoutConstraintStrength::Required => strength::REQUIRED,
+            LayoutConstraintStrength::Strong => strength::STRONG,
+            LayoutConstraintStrength::Medium => strength::MEDIUM,
+            LayoutConstraintStrength::Weak => strength::WEAK,
+            LayoutConstraintStrength::Custom(strong, medium, weak) => {
+                strength::create(strong as f64, medium as f64, weak as f64, 1.0)
+            }
+        };
+        // only required constraints can fail, when they conflict with previously added ones.
+        if solver
+            .add_constraint(Constraint::new(
+                Expression::new(terms, constant),
+                relation,
+                strength,
+            ))
+            .is_err()
+        {
+            rejected.push(constraint.to_owned());
+        }
+    }
+    let rects = variables
+        .iter()
+        .map(|[left, right, top, bottom]| Rect {
+            left: solver.get_value(*left) as Scalar,
+            right: solver.get_value(*right) as Scalar,
+            top: solver.get_value(*top) as Scalar,
+            bottom: solver.get_value(*bottom) as Scalar,
+        })
+        .collect();
+    (rects, rejected)
+}
+
+fn flex_item_main_limits(unit: &FlexBox, layout: &FlexBoxItemLayout) -> (Scalar, Scalar) {
+    if unit.direction.is_horizontal() {
+        let margin = layout.margin.left + layout.margin.right;
+        (
+            layout.constraints.min_width + margin,
+            layout.constraints.max_width() + margin,
+        )
+    } else {
+        let margin = layout.margin.top + layout.margin.bottom;
+        (
+            layout.constraints.min_height + margin,
+            layout.constraints.max_height() + margin,
+        )
+    }
+}
+
+/// Distributes main axis space difference between flex items (base size, weight, min, max)
+/// proportionally to their weights. Items that would cross their limits get frozen at them and
+/// the rest of the space is distributed again between the others.
+fn distribute_flex_space(items: &[(Scalar, Scalar, Scalar, Scalar)], diff: Scalar) -> Vec<Scalar> {
+    let mut sizes = items
+        .iter()
+        .map(|(base, _, _, _)| *base)
+        .collect::<Vec<_>>();
+    let mut frozen = items
+        .iter()
+        .map(|(_, weight, _, _)| *weight <= 0.0)
+        .collect::<Vec<_>>();
+    loop {
+        let mut weight = 0.0;
+        let mut space = diff;
+        for ((base, item_weight, _, _), (size, frozen)) in
+            items.iter().zip(sizes.iter().zip(frozen.iter()))
+        {
+            if *frozen {
+                space -= size - base;
+            } else {
+                weight += item_weight;
+            }
+        }
+        if weight <= 0.0 {
+            return sizes;
+        }
+        let mut violated = false;
+        for ((base, item_weight, min, max), (size, frozen)) in
+            items.iter().zip(sizes.iter_mut().zip(frozen.iter_mut()))
+        {
+            if *frozen {
+                continue;
+            }
+            let value = base + space * item_weight / weight;
+            if value < *min || value > *max {
+                *size = value.max(*min).min(*max);
+                *frozen = true;
+                violated = true;
+            } else {
+                *size = value;
+            }
+        }
+        if !violated {
+            return sizes;
+        }
+    }
+}
+
+/// Ranges of cells occupied by grid items (auto placed items go to first free cells in row-major
+/// order) together with number of columns and rows of grid.
+fn grid_box_cells(unit: &GridBox) -> (Vec<IntRect>, usize, usize) {
+    let mut columns = if unit.column_tracks.is_empty() {
+        unit.cols
+    } else {
+        unit.column_tracks.len()
+    };
+    let mut rows = if unit.row_tracks.is_empty() {
+        unit.rows
+    } else {
+        unit.row_tracks.len()
+    };
+    let mut occupied = HashSet::new();
+    for item in &unit.items {
+        if !item.layout.is_auto_placed() {
+            let rect = item.layout.space_occupancy;
+            for col in rect.left.max(0)..rect.right.max(0) {
+                for row in rect.top.max(0)..rect.bottom.max(0) {
+                    occupied.insert((col as usize, row as usize));
+                }
+            }
+        }
+    }
+    let auto_columns = columns.max(1);
+    let mut cursor = (0, 0);
+    let cells = unit
+        .items
+        .iter()
+        .map(|item| {
+            if !item.layout.is_auto_placed() {
+                let rect = item.layout.space_occupancy;
+                return IntRect {
+                    left: rect.left.max(0),
+                    right: rect.right.max(0),
+                    top: rect.top.max(0),
+                    bottom: rect.bottom.max(0),
+                };
+            }
+            let span_cols = item.layout.column_span.max(1).min(auto_columns);
+            let span_rows = item.layout.row_span.max(1);
+            loop {
+                let (col, row) = cursor;
+                if col + span_cols > auto_columns {
+                    cursor = (0, row + 1);
+                    continue;
+                }
+                let fits = (col..(col + span_cols))
+                    .all(|c| (row..(row + span_rows)).all(|r| !occupied.contains(&(c, r))));
+                if fits {
+                    for c in col..(col + span_cols) {
+                        for r in row..(row + span_rows) {
+                            occupied.insert((c, r));
+                        }
+                    }
+                    cursor = (col + span_cols, row);
+                    return IntRect {
+                        left: col as Integer,
+                        right: (col + span_cols) as Integer,
+                        top: row as Integer,
+                        bottom: (row + span_rows) as Integer,
+                    };
+                }
+                cursor = (col + 1, row);
+            }
+        })
+        .collect::<Vec<_>>();
+    for rect in &cells {
+        columns = columns.max(rect.right as usize);
+        rows = rows.max(rect.bottom as usize);
+    }
+    (cells, columns, rows)
+}
+
+/// Track definitions of grid axis, tracks beyond defined ones are content sized.
+fn grid_box_tracks(
+    tracks: &[GridBoxTrackSize],
+    uniform: usize,
+    count: usize,
+) -> Vec<GridBoxTrackSize> {
+    (0..count)
+        .map(|index| {
+            if tracks.is_empty() {
+                if index < uniform {
+                    GridBoxTrackSize::Fraction(1.0)
+                } else {
+                    GridBoxTrackSize::Content
+                }
+            } else {
+                tracks
+                    .get(index)
+                    .copied()
+                    .unwrap_or(GridBoxTrackSize::Content)
+            }
+        })
+        .collect()
+}
+
+/// Resolves sizes of grid tracks.
+///
+/// `items` contains cells range (start and end) along resolved axis and minimal size that item
+/// requires. When `available` space is `None`, flexible tracks get smallest sizes that fit items.
+fn resolve_grid_tracks(
+    tracks: &[GridBoxTrackSize],
+    gap: Scalar,
+    available: Option<Scalar>,
+    items: &[(usize, usize, Scalar)],
+) -> Vec<Scalar> {
+    let mut sizes = tracks
+        .iter()
+        .map(|track| match track {
+            GridBoxTrackSize::Exact(v) => v.max(0.0),
+            GridBoxTrackSize::MinMax(min, _) => min.max(0.0),
+            _ => 0.0,
+        })
+        .collect::<Vec<_>>();
+    let mut items = items
+        .iter()
+        .filter(|(start, end, _)| start < end && *end <= tracks.len())
+        .collect::<Vec<_>>();
+    items.sort_by_key(|(start, end, _)| end - start);
+    let gaps = |start: usize, end: usize| (end - start).saturating_sub(1) as Scalar * gap;
+    for (start, end, size) in &items {
+        let spanned = &tracks[*start..*end];
+        if spanned.iter().any(|track| track.is_flexible()) {
+            continue;
+        }
+        let content = spanned
+            .iter()
+            .filter(|track| **track == GridBoxTrackSize::Content)
+            .count();
+        if content == 0 {
+            continue;
+        }
+        let current = sizes[*start..*end].iter().sum::<Scalar>() + gaps(*start, *end);
+        let deficit = size - current;
+        if deficit > 0.0 {
+            for (index, track) in spanned.iter().enumerate() {
+                if *track == GridBoxTrackSize::Content {
+                    sizes[start + index] += deficit / content as Scalar;
+                }
+            }
+        }
+    }
+    let mut flexible = (0..tracks.len())
+        .filter(|index| tracks[*index].is_flexible())
+        .collect::<Vec<_>>();
+    if flexible.is_empty() {
+        return sizes;
+    }
+    let fixed = (0..tracks.len())
+        .filter(|index| !tracks[*index].is_flexible())
+        .map(|index| sizes[index])
+        .sum::<Scalar>();
+    let mut free = match available {
+        Some(available) => (available - fixed - gaps(0, tracks.len())).max(0.0),
+        None => {
+            // smallest fraction unit that lets every item fit into its flexible tracks.
+            let mut fraction: Scalar = 0.0;
+            for (start, end, size) in &items {
+                let spanned = &tracks[*start..*end];
+                let weight = spanned.iter().map(|track| track.fraction()).sum::<Scalar>();
+                if weight > 0.0 {
+                    let rest = (*start..*end)
+                        .filter(|index| !tracks[*index].is_flexible())
+                        .map(|index| sizes[index])
+                        .sum::<Scalar>();
+                    fraction = fraction.max((size - rest - gaps(*start, *end)) / weight);
+                }
+            }
+            flexible
+                .iter()
+                .map(|index| tracks[*index].fraction() * fraction)
+                .sum::<Scalar>()
+        }
+    };
+    // distribute free space and freeze tracks violating their min-max bounds until none does.
+    loop {
+        let weight = flexible
+            .iter()
+            .map(|index| tracks[*index].fraction())
+            .sum::<Scalar>();
+        let fraction = if weight > 0.0 { free / weight } else { 0.0 };
+        let mut frozen = vec![];
+        for index in &flexible {
+            if let GridBoxTrackSize::MinMax(min, max) = tracks[*index] {
+                let size = tracks[*index].fraction() * fraction;
+                if size < min {
+                    frozen.push((*index, min));
+                } else if size > max {
+                    frozen.push((*index, max));
+                }
+            }
+        }
+        if frozen.is_empty() {
+            for index in flexible {
+                sizes[index] = tracks[index].fraction() * fraction;
+            }
+            return sizes;
+        }
+        for (index, size) in frozen {
+            sizes[index] = size.max(0.0);
+            free = (free - size).max(0.0);
+            flexible.retain(|i| *i != index);
+        }
+    }
+}
+
+/// Custom units measurements by custom unit type name
+#[derive(Default, Clone)]
+struct CustomUnitMeasurements(HashMap<String, Arc<dyn CustomUnitMeasurementEngine + Send + Sync>>);
+
+impl std::fmt::Debug for CustomUnitMeasurements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// Layout engine that reuses layout of unchanged subtrees between passes
+///
+/// Nodes are cached by widget id, available size and hash of layout affecting unit content, and
+/// minimal sizes of units are memoized within single pass. Cache gets cleared whenever text or
+/// image measurement engine is accessed mutably, since measurements might change then.
+#[derive(Debug, Clone)]
+pub struct DefaultLayoutEngine<TME = (), IME = ()>
+where
+    TME: TextMeasurementEngine,
+    IME: ImageMeasurementEngine,
+{
+    text_measurement_engine: TME,
+    image_measurement_engine: IME,
+    custom_unit_measurements: CustomUnitMeasurements,
+    layout_direction: LayoutDirection,
+    cache: SharedLayoutCache,
+}
+
+impl Default for DefaultLayoutEngine {
+    fn default() -> Self {
+        Self::new(())
+    }
+}
+
+impl<TME> DefaultLayoutEngine<TME>
+where
+    TME: TextMeasurementEngine,
+{
+    pub fn new(text_measurement_engine: TME) -> Self {
+        Self::with_measurements(text_measurement_engine, ())
+    }
+}
+
+impl<TME, IME> DefaultLayoutEngine<TME, IME>
+where
+    TME: TextMeasurementEngine,
+    IME: ImageMeasurementEngine,
+{
+    pub fn with_measurements(text_measurement_engine: TME, image_measurement_engine: IME) -> Self {
+        Self {
+            text_measurement_engine,
+            image_measurement_engine,
+            custom_unit_measurements: Default::default(),
+            layout_direction: Default::default(),
+            cache: Default::default(),
+        }
+    }
+
+    /// Layout direction of containers that do not override it
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.layout_direction
+    }
+
+    pub fn set_layout_direction(&mut self, direction: LayoutDirection) {
+        self.layout_direction = direction;
+    }
+
+    /// Constraints of content boxes that solver rejected in last layout pass, keyed by content box
+    /// id. Required constraints get rejected when they conflict with the ones added before them,
+    /// layout then goes on without them.
+    pub fn rejected_constraints(&self) -> HashMap<WidgetId, Vec<LayoutConstraint>> {
+        self.cache.lock().rejected_constraints.clone()
+    }
+
+    /// Forgets layout of previous pass, so next pass lays out whole tree
+    pub fn clear_cache(&mut self) {
+        self.cache.get_mut().clear();
+    }
+
+    pub fn text_measurement_engine(&self) -> &TME {
+        &self.text_measurement_engine
+    }
+
+    pub fn text_measurement_engine_mut(&mut self) -> &mut TME {
+        self.clear_cache();
+        &mut self.text_measurement_engine
+    }
+
+    pub fn image_measurement_engine(&self) -> &IME {
+        &self.image_measurement_engine
+    }
+
+    pub fn image_measurement_engine_mut(&mut self) -> &mut IME {
+        self.clear_cache();
+        &mut self.image_measurement_engine
+    }
+
+    /// Registers measurement of custom units with given type name. Custom units without
+    /// registered measurement fill available space.
+    pub fn register_custom_unit<T>(&mut self, type_name: &str, measurement: T)
+    where
+        T: CustomUnitMeasurementEngine + Send + Sync + 'static,
+    {
+        self.clear_cache();
+        self.custom_unit_measurements
+            .0
+            .insert(type_name.to_owned(), Arc::new(measurement));
+    }
+
+    pub fn unregister_custom_unit(&mut self, type_name: &str) {
+        self.clear_cache();
+        self.custom_unit_measurements.0.remove(type_name);
+    }
+
+    pub fn with_custom_unit<T>(mut self, type_name: &str, measurement: T) -> Self
+    where
+        T: CustomUnitMeasurementEngine + Send + Sync + 'static,
+    {
+        self.register_custom_unit(type_name, measurement);
+        self
+    }
+
+    pub fn layout_node(&self, size_available: Vec2, unit: &WidgetUnit) -> Option<LayoutNode> {
+        let id = unit.as_data()?.id();
+        if let Some(node) = self.cache.lock().restore(id, size_available) {
+            return Some(node);
+        }
+        let node = match unit {
+            WidgetUnit::None | WidgetUnit::PortalBox(_) => None,
+            WidgetUnit::AreaBox(b) => self.layout_area_box(size_available, b),
+            WidgetUnit::ContentBox(b) => self.layout_content_box(size_available, b),
+            WidgetUnit::FlexBox(b) => self.layout_flex_box(size_available, b),
+            WidgetUnit::GridBox(b) => self.layout_grid_box(size_available, b),
+            WidgetUnit::MasonryBox(b) => self.layout_masonry_box(size_available, b),
+            WidgetUnit::SizeBox(b) => self.layout_size_box(size_available, b),
+            WidgetUnit::ImageBox(b) => self.layout_image_box(size_available, b),
+            WidgetUnit::TextBox(b) => self.layout_text_box(size_available, b),
+            WidgetUnit::RichTextBox(b) => self.layout_rich_text_box(size_available, b),
+            WidgetUnit::CustomBox(b) => self.layout_custom_box(size_available, b),
+        }?;
+        self.cache.lock().store(size_available, &node);
+        Some(node)
+    }
+
+    pub fn layout_area_box(&self, size_available: Vec2, unit: &AreaBox) -> Option<LayoutNode> {
         if !unit.id.is_valid() {
             return None;
         }
-        let (children, w, h) = if let Some(child) = Self::layout_node(size_available, &unit.slot) {
+        let (children, w, h) = if let Some(child) = self.layout_node(size_available, &unit.slot) {
             let w = child.local_space.width();
             let h = child.local_space.height();
             (vec![child], w, h)
@@ -56,32 +1069,46 @@ impl DefaultLayoutEngine {
             id: unit.id.to_owned(),
             local_space,
             children,
+            fragments: vec![],
         })
     }
 
-    pub fn layout_content_box(size_available: Vec2, unit: &ContentBox) -> Option<LayoutNode> {
+    pub fn layout_content_box(
+        &self,
+        size_available: Vec2,
+        unit: &ContentBox,
+    ) -> Option<LayoutNode> {
         if !unit.id.is_valid() {
             return None;
         }
+        let rects = if unit.constraints.is_empty() {
+            unit.items
+                .iter()
+                .map(|item| content_item_rect(size_available, &item.layout))
+                .collect::<Vec<_>>()
+        } else {
+            let (rects, rejected) = solve_content_box_constraints(size_available, unit);
+            self.cache.lock().reject_constraints(&unit.id, rejected);
+            rects
+        };
         let children = unit
             .items
             .iter()
-            .filter_map(|item| {
-                let left = lerp(0.0, size_available.x, item.layout.anchors.left);
-                let left = left + item.layout.margin.left + item.layout.offset.x;
-                let right = lerp(0.0, size_available.x, item.layout.anchors.right);
-                let right = right - item.layout.margin.right + item.layout.offset.x;
-                let top = lerp(0.0, size_available.y, item.layout.anchors.top);
-                let top = top + item.layout.margin.top + item.layout.offset.y;
-                let bottom = lerp(0.0, size_available.y, item.layout.anchors.bottom);
-                let bottom = bottom - item.layout.margin.bottom + item.layout.offset.y;
+            .zip(rects)
+            .filter_map(|(item, rect)| {
+                let Rect {
+                    left,
+                    right,
+                    top,
+                    bottom,
+                } = rect;
                 let width = (right - left).max(0.0);
                 let height = (bottom - top).max(0.0);
-                let size = Vec2 {
+                let size = item.layout.constraints.clamp(Vec2 {
                     x: width,
                     y: height,
-                };
-                if let Some(mut child) = Self::layout_node(size, &item.slot) {
+                });
+                if let Some(mut child) = self.layout_node(size, &item.slot) {
                     let diff = child.local_space.width() - width;
                     let ox = lerp(0.0, diff, item.layout.align.x);
                     child.local_space.left += left - ox;
@@ -105,26 +1132,28 @@ impl DefaultLayoutEngine {
                 bottom: size_available.y,
             },
             children,
+            fragments: vec![],
         })
     }
 
-    pub fn layout_flex_box(size_available: Vec2, unit: &FlexBox) -> Option<LayoutNode> {
+    pub fn layout_flex_box(&self, size_available: Vec2, unit: &FlexBox) -> Option<LayoutNode> {
         if !unit.id.is_valid() {
             return None;
         }
         if unit.wrap {
-            Some(Self::layout_flex_box_wrapping(size_available, unit))
+            Some(self.layout_flex_box_wrapping(size_available, unit))
         } else {
-            Some(Self::layout_flex_box_no_wrap(size_available, unit))
+            Some(self.layout_flex_box_no_wrap(size_available, unit))
         }
     }
 
-    pub fn layout_flex_box_wrapping(size_available: Vec2, unit: &FlexBox) -> LayoutNode {
-        let main_available = if unit.direction.is_horizontal() {
-            size_available.x
+    pub fn layout_flex_box_wrapping(&self, size_available: Vec2, unit: &FlexBox) -> LayoutNode {
+        let (main_available, cross_available_total) = if unit.direction.is_horizontal() {
+            (size_available.x, size_available.y)
         } else {
-            size_available.y
+            (size_available.y, size_available.x)
         };
+        let cross_separation = unit.cross_separation.unwrap_or(unit.separation);
         let (lines, count) = {
             let mut main = 0.0;
             let mut cross: Scalar = 0.0;
@@ -138,13 +1167,19 @@ impl DefaultLayoutEngine {
             let mut lines = vec![];
             let mut line = vec![];
             for item in items {
-                let local_main = item.layout.basis.unwrap_or_else(|| {
-                    if unit.direction.is_horizontal() {
-                        Self::calc_unit_min_width(size_available, &item.slot)
-                    } else {
-                        Self::calc_unit_min_height(size_available, &item.slot)
-                    }
-                });
+                let local_main = if unit.direction.is_horizontal() {
+                    item.layout.constraints.clamp_width(
+                        item.layout.basis.unwrap_or_else(|| {
+                            self.calc_unit_min_width(size_available, &item.slot)
+                        }),
+                    )
+                } else {
+                    item.layout.constraints.clamp_height(
+                        item.layout.basis.unwrap_or_else(|| {
+                            self.calc_unit_min_height(size_available, &item.slot)
+                        }),
+                    )
+                };
                 let local_main = local_main
                     + if unit.direction.is_horizontal() {
                         item.layout.margin.left + item.layout.margin.right
@@ -152,9 +1187,13 @@ impl DefaultLayoutEngine {
                         item.layout.margin.top + item.layout.margin.bottom
                     };
                 let local_cross = if unit.direction.is_horizontal() {
-                    Self::calc_unit_min_height(size_available, &item.slot)
+                    item.layout
+                        .constraints
+                        .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
                 } else {
-                    Self::calc_unit_min_width(size_available, &item.slot)
+                    item.layout
+                        .constraints
+                        .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                 };
                 let local_cross = local_cross
                     + if unit.direction.is_horizontal() {
@@ -178,24 +1217,37 @@ impl DefaultLayoutEngine {
             lines.push((main, cross, grow, line));
             (lines, count)
         };
+        let lines_cross = lines.iter().map(|(_, cross, _, _)| *cross).sum::<Scalar>()
+            + lines.len().saturating_sub(1) as Scalar * cross_separation;
+        let (cross_offset, cross_between, cross_extra) = unit
+            .align_content
+            .spacing(cross_available_total - lines_cross, lines.len());
+        let cross_separation = cross_separation + cross_between;
         let mut children = Vec::with_capacity(count);
         let mut main_max: Scalar = 0.0;
-        let mut cross_max = 0.0;
+        let mut cross_max = cross_offset;
         for (main, cross_available, grow, items) in lines {
+            let cross_available = cross_available + cross_extra;
             let diff = main_available - main;
-            let mut new_main = 0.0;
+            let space_left = if grow > 0.0 { 0.0 } else { diff };
+            let (offset, between) = unit.justify_content.spacing(space_left, items.len());
+            let separation = unit.separation + between;
+            let mut new_main = offset;
             let mut new_cross: Scalar = 0.0;
-            for (item, local_main, local_cross) in items {
-                let child_main = if main < main_available {
-                    local_main
-                        + if grow > 0.0 {
-                            diff * item.layout.grow / grow
-                        } else {
-                            0.0
-                        }
-                } else {
-                    local_main
-                };
+            let mut baselines = vec![];
+            let mains = if main < main_available {
+                let weighted = items
+                    .iter()
+                    .map(|(item, local_main, _)| {
+                        let (min, max) = flex_item_main_limits(unit, &item.layout);
+                        (*local_main, item.layout.grow, min, max)
+                    })
+                    .collect::<Vec<_>>();
+                distribute_flex_space(&weighted, diff)
+            } else {
+                items.iter().map(|(_, local_main, _)| *local_main).collect()
+            };
+            for ((item, _, local_cross), child_main) in items.into_iter().zip(mains) {
                 let child_main = (child_main
                     - if unit.direction.is_horizontal() {
                         item.layout.margin.left + item.layout.margin.right
@@ -211,6 +1263,11 @@ impl DefaultLayoutEngine {
                     })
                 .max(0.0);
                 let child_cross = lerp(child_cross, cross_available, item.layout.fill);
+                let child_cross = if unit.direction.is_horizontal() {
+                    item.layout.constraints.clamp_height(child_cross)
+                } else {
+                    item.layout.constraints.clamp_width(child_cross)
+                };
                 let rect = if unit.direction.is_horizontal() {
                     Vec2 {
                         x: child_main,
@@ -222,7 +1279,7 @@ impl DefaultLayoutEngine {
                         y: child_main,
                     }
                 };
-                if let Some(mut child) = Self::layout_node(rect, &item.slot) {
+                if let Some(mut child) = self.layout_node(rect, &item.slot) {
                     if unit.direction.is_horizontal() {
                         if unit.direction.is_order_ascending() {
                             child.local_space.left += new_main + item.layout.margin.left;
@@ -236,11 +1293,22 @@ impl DefaultLayoutEngine {
                                 size_available.x - left - new_main - item.layout.margin.right;
                         }
                         new_main += rect.x + item.layout.margin.left + item.layout.margin.right;
-                        let diff = lerp(
-                            0.0,
-                            cross_available - child.local_space.height(),
-                            item.layout.align,
-                        );
+                        let diff = if item.layout.align_baseline {
+                            let baseline =
+                                self.unit_baseline(&item.slot, child.local_space.height());
+                            baselines.push((
+                                children.len(),
+                                cross_max + item.layout.margin.top + baseline,
+                                item.layout.margin.bottom,
+                            ));
+                            0.0
+                        } else {
+                            lerp(
+                                0.0,
+                                cross_available - child.local_space.height(),
+                                item.layout.align,
+                            )
+                        };
                         child.local_space.top += cross_max + item.layout.margin.top + diff;
                         child.local_space.bottom += cross_max + item.layout.margin.top + diff;
                         new_cross = new_cross.max(rect.y);
@@ -266,15 +1334,36 @@ impl DefaultLayoutEngine {
                         child.local_space.right += cross_max + item.layout.margin.left + diff;
                         new_cross = new_cross.max(rect.x);
                     }
-                    new_main += unit.separation;
+                    new_main += separation;
                     children.push(child);
                 }
             }
-            new_main = (new_main - unit.separation).max(0.0);
+            if let Some(bottom) = align_flex_line_baselines(&mut children, &baselines) {
+                new_cross = new_cross.max(bottom - cross_max);
+            }
+            if cross_extra > 0.0 {
+                new_cross = new_cross.max(cross_available);
+            }
+            new_main = (new_main - separation).max(0.0);
             main_max = main_max.max(new_main);
-            cross_max += new_cross + unit.separation;
+            cross_max += new_cross + cross_separation;
+        }
+        cross_max = (cross_max - cross_separation).max(0.0);
+        if unit.wrap_reverse {
+            cross_max = cross_max.max(cross_available_total);
+            for child in &mut children {
+                let space = &mut child.local_space;
+                if unit.direction.is_horizontal() {
+                    let top = space.top;
+                    space.top = cross_max - space.bottom;
+                    space.bottom = cross_max - top;
+                } else {
+                    let left = space.left;
+                    space.left = cross_max - space.right;
+                    space.right = cross_max - left;
+                }
+            }
         }
-        cross_max = (cross_max - unit.separation).max(0.0);
         let local_space = if unit.direction.is_horizontal() {
             Rect {
                 left: 0.0,
@@ -294,10 +1383,11 @@ impl DefaultLayoutEngine {
             id: unit.id.to_owned(),
             local_space,
             children,
+            fragments: vec![],
         }
     }
 
-    pub fn layout_flex_box_no_wrap(size_available: Vec2, unit: &FlexBox) -> LayoutNode {
+    pub fn layout_flex_box_no_wrap(&self, size_available: Vec2, unit: &FlexBox) -> LayoutNode {
         let (main_available, cross_available) = if unit.direction.is_horizontal() {
             (size_available.x, size_available.y)
         } else {
@@ -306,7 +1396,6 @@ impl DefaultLayoutEngine {
         let mut main = 0.0;
         let mut cross: Scalar = 0.0;
         let mut grow = 0.0;
-        let mut shrink = 0.0;
         let items = unit
             .items
             .iter()
@@ -314,13 +1403,19 @@ impl DefaultLayoutEngine {
             .collect::<Vec<_>>();
         let mut axis_sizes = Vec::with_capacity(items.len());
         for item in &items {
-            let local_main = item.layout.basis.unwrap_or_else(|| {
-                if unit.direction.is_horizontal() {
-                    Self::calc_unit_min_width(size_available, &item.slot)
-                } else {
-                    Self::calc_unit_min_height(size_available, &item.slot)
-                }
-            });
+            let local_main = if unit.direction.is_horizontal() {
+                item.layout.constraints.clamp_width(
+                    item.layout
+                        .basis
+                        .unwrap_or_else(|| self.calc_unit_min_width(size_available, &item.slot)),
+                )
+            } else {
+                item.layout.constraints.clamp_height(
+                    item.layout
+                        .basis
+                        .unwrap_or_else(|| self.calc_unit_min_height(size_available, &item.slot)),
+                )
+            };
             let local_main = local_main
                 + if unit.direction.is_horizontal() {
                     item.layout.margin.left + item.layout.margin.right
@@ -328,9 +1423,13 @@ impl DefaultLayoutEngine {
                     item.layout.margin.top + item.layout.margin.bottom
                 };
             let local_cross = if unit.direction.is_horizontal() {
-                Self::calc_unit_min_height(size_available, &item.slot)
+                item.layout
+                    .constraints
+                    .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
             } else {
-                Self::calc_unit_min_width(size_available, &item.slot)
+                item.layout
+                    .constraints
+                    .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
             };
             let local_cross = local_cross
                 + if unit.direction.is_horizontal() {
@@ -342,34 +1441,37 @@ impl DefaultLayoutEngine {
             main += local_main;
             cross = cross.max(local_cross);
             grow += item.layout.grow;
-            shrink += item.layout.shrink;
             axis_sizes.push((local_main, local_cross));
         }
         main += items.len().saturating_sub(1) as Scalar * unit.separation;
         let diff = main_available - main;
-        let mut new_main = 0.0;
+        let space_left = if grow > 0.0 { 0.0 } else { diff };
+        let (offset, between) = unit.justify_content.spacing(space_left, items.len());
+        let separation = unit.separation + between;
+        let mains = {
+            let weighted = items
+                .iter()
+                .zip(axis_sizes.iter())
+                .map(|(item, (local_main, _))| {
+                    let weight = if main < main_available {
+                        item.layout.grow
+                    } else {
+                        item.layout.shrink
+                    };
+                    let (min, max) = flex_item_main_limits(unit, &item.layout);
+                    (*local_main, weight, min, max)
+                })
+                .collect::<Vec<_>>();
+            distribute_flex_space(&weighted, diff)
+        };
+        let mut new_main = offset;
         let mut new_cross: Scalar = 0.0;
-        let children = items
+        let mut baselines = vec![];
+        let mut count = 0;
+        let mut children = items
             .into_iter()
-            .zip(axis_sizes.into_iter())
-            .filter_map(|(item, axis_size)| {
-                let child_main = if main < main_available {
-                    axis_size.0
-                        + if grow > 0.0 {
-                            diff * item.layout.grow / grow
-                        } else {
-                            0.0
-                        }
-                } else if main > main_available {
-                    axis_size.0
-                        + if shrink > 0.0 {
-                            diff * item.layout.shrink / shrink
-                        } else {
-                            0.0
-                        }
-                } else {
-                    axis_size.0
-                };
+            .zip(axis_sizes.into_iter().zip(mains))
+            .filter_map(|(item, (axis_size, child_main))| {
                 let child_main = (child_main
                     - if unit.direction.is_horizontal() {
                         item.layout.margin.left + item.layout.margin.right
@@ -384,6 +1486,11 @@ impl DefaultLayoutEngine {
                         item.layout.margin.left + item.layout.margin.right
                     })
                 .max(0.0);
+                let child_cross = if unit.direction.is_horizontal() {
+                    item.layout.constraints.clamp_height(child_cross)
+                } else {
+                    item.layout.constraints.clamp_width(child_cross)
+                };
                 let rect = if unit.direction.is_horizontal() {
                     Vec2 {
                         x: child_main,
@@ -395,7 +1502,7 @@ impl DefaultLayoutEngine {
                         y: child_main,
                     }
                 };
-                if let Some(mut child) = Self::layout_node(rect, &item.slot) {
+                if let Some(mut child) = self.layout_node(rect, &item.slot) {
                     if unit.direction.is_horizontal() {
                         if unit.direction.is_order_ascending() {
                             child.local_space.left += new_main + item.layout.margin.left;
@@ -409,11 +1516,22 @@ impl DefaultLayoutEngine {
                                 size_available.x - left - new_main - item.layout.margin.right;
                         }
                         new_main += rect.x + item.layout.margin.left + item.layout.margin.right;
-                        let diff = lerp(
-                            0.0,
-                            cross_available - child.local_space.height(),
-                            item.layout.align,
-                        );
+                        let diff = if item.layout.align_baseline {
+                            let baseline =
+                                self.unit_baseline(&item.slot, child.local_space.height());
+                            baselines.push((
+                                count,
+                                item.layout.margin.top + baseline,
+                                item.layout.margin.bottom,
+                            ));
+                            0.0
+                        } else {
+                            lerp(
+                                0.0,
+                                cross_available - child.local_space.height(),
+                                item.layout.align,
+                            )
+                        };
                         child.local_space.top += item.layout.margin.top + diff;
                         child.local_space.bottom += item.layout.margin.top + diff;
                         new_cross = new_cross.max(rect.y);
@@ -439,14 +1557,18 @@ impl DefaultLayoutEngine {
                         child.local_space.right += item.layout.margin.left + diff;
                         new_cross = new_cross.max(rect.x);
                     }
-                    new_main += unit.separation;
+                    new_main += separation;
+                    count += 1;
                     Some(child)
                 } else {
                     None
                 }
             })
             .collect::<Vec<_>>();
-        new_main = (new_main - unit.separation).max(0.0);
+        if let Some(bottom) = align_flex_line_baselines(&mut children, &baselines) {
+            new_cross = new_cross.max(bottom);
+        }
+        new_main = (new_main - separation).max(0.0);
         let local_space = if unit.direction.is_horizontal() {
             Rect {
                 left: 0.0,
@@ -466,43 +1588,69 @@ impl DefaultLayoutEngine {
             id: unit.id.to_owned(),
             local_space,
             children,
+            fragments: vec![],
         }
     }
 
-    pub fn layout_grid_box(size_available: Vec2, unit: &GridBox) -> Option<LayoutNode> {
+    pub fn layout_grid_box(&self, size_available: Vec2, unit: &GridBox) -> Option<LayoutNode> {
         if !unit.id.is_valid() {
             return None;
         }
-        let cell_width = if unit.cols > 0 {
-            size_available.x / unit.cols as Scalar
-        } else {
-            0.0
+        let (cells, columns, rows) = grid_box_cells(unit);
+        let columns = grid_box_tracks(&unit.column_tracks, unit.cols, columns);
+        let rows = grid_box_tracks(&unit.row_tracks, unit.rows, rows);
+        let widths = resolve_grid_tracks(
+            &columns,
+            unit.column_gap,
+            Some(size_available.x),
+            &self.grid_box_items_sizes(size_available, unit, &cells, &columns, false),
+        );
+        let heights = resolve_grid_tracks(
+            &rows,
+            unit.row_gap,
+            Some(size_available.y),
+            &self.grid_box_items_sizes(size_available, unit, &cells, &rows, true),
+        );
+        let offsets = |sizes: &[Scalar], gap: Scalar| {
+            let mut offset = 0.0;
+            sizes
+                .iter()
+                .map(|size| {
+                    let result = offset;
+                    offset += size + gap;
+                    result
+                })
+                .collect::<Vec<_>>()
         };
-        let cell_height = if unit.rows > 0 {
-            size_available.y / unit.rows as Scalar
-        } else {
-            0.0
+        let lefts = offsets(&widths, unit.column_gap);
+        let tops = offsets(&heights, unit.row_gap);
+        let range = |offsets: &[Scalar], sizes: &[Scalar], start: Integer, end: Integer| {
+            let (start, end) = (start as usize, end as usize);
+            if start < end && end <= sizes.len() {
+                (offsets[start], offsets[end - 1] + sizes[end - 1])
+            } else {
+                (0.0, 0.0)
+            }
         };
         let children = unit
             .items
             .iter()
-            .filter_map(|item| {
-                let left = item.layout.space_occupancy.left as Scalar * cell_width;
-                let right = item.layout.space_occupancy.right as Scalar * cell_width;
-                let top = item.layout.space_occupancy.top as Scalar * cell_height;
-                let bottom = item.layout.space_occupancy.bottom as Scalar * cell_height;
+            .zip(cells.iter())
+            .filter_map(|(item, cell)| {
+                let (left, right) = range(&lefts, &widths, cell.left, cell.right);
+                let (top, bottom) = range(&tops, &heights, cell.top, cell.bottom);
                 let width =
                     (right - left - item.layout.margin.left - item.layout.margin.right).max(0.0);
                 let height =
                     (bottom - top - item.layout.margin.top - item.layout.margin.bottom).max(0.0);
-                let size = Vec2 {
+                let size = item.layout.constraints.clamp(Vec2 {
                     x: width,
                     y: height,
-                };
-                if let Some(mut child) = Self::layout_node(size, &item.slot) {
-                    let diff = size.x - child.local_space.width();
+                });
+                if let Some(mut child) = self.layout_node(size, &item.slot) {
+                    let diff = width - child.local_space.width();
                     let ox = lerp(0.0, diff, item.layout.horizontal_align);
-                    let diff = size.y - child.local_space.height();
+                    let diff = height - child.local_space.height();
                     let oy = lerp(0.0, diff, item.layout.vertical_align);
                     child.local_space.left += left + item.layout.margin.left - ox;
                     child.local_space.right += left + item.layout.margin.left - ox;
@@ -520,29 +1668,163 @@ impl DefaultLayoutEngine {
                 left: 0.0,
                 right: size_available.x,
                 top: 0.0,
-                bottom: size_available.y,
+                bottom: size_available.y,
+            },
+            children,
+            fragments: vec![],
+        })
+    }
+
+    /// Cells ranges along one grid axis with minimal sizes of items occupying them.
+    ///
+    /// Items are measured only if their size can affect tracks sizes.
+    fn grid_box_items_sizes(
+        &self,
+        size_available: Vec2,
+        unit: &GridBox,
+        cells: &[IntRect],
+        tracks: &[GridBoxTrackSize],
+        vertical: bool,
+    ) -> Vec<(usize, usize, Scalar)> {
+        if tracks
+            .iter()
+            .all(|track| matches!(track, GridBoxTrackSize::Exact(_)))
+        {
+            return vec![];
+        }
+        unit.items
+            .iter()
+            .zip(cells.iter())
+            .map(|(item, cell)| {
+                if vertical {
+                    let size = item
+                        .layout
+                        .constraints
+                        .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
+                        + item.layout.margin.top
+                        + item.layout.margin.bottom;
+                    (cell.top as usize, cell.bottom as usize, size)
+                } else {
+                    let size = item
+                        .layout
+                        .constraints
+                        .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
+                        + item.layout.margin.left
+                        + item.layout.margin.right;
+                    (cell.left as usize, cell.right as usize, size)
+                }
+            })
+            .collect()
+    }
+
+    pub fn layout_masonry_box(
+        &self,
+        size_available: Vec2,
+        unit: &MasonryBox,
+    ) -> Option<LayoutNode> {
+        if !unit.id.is_valid() {
+            return None;
+        }
+        let (placements, height) = self.masonry_box_placements(size_available, unit);
+        let children = unit
+            .items
+            .iter()
+            .zip(placements.into_iter())
+            .filter_map(|(item, rect)| {
+                let size = item.layout.constraints.clamp(rect.size());
+                let mut child = self.layout_node(size, &item.slot)?;
+                child.local_space.left += rect.left;
+                child.local_space.right += rect.left;
+                child.local_space.top += rect.top;
+                child.local_space.bottom += rect.top;
+                Some(child)
+            })
+            .collect::<Vec<_>>();
+        Some(LayoutNode {
+            id: unit.id.to_owned(),
+            local_space: Rect {
+                left: 0.0,
+                right: size_available.x,
+                top: 0.0,
+                bottom: height.max(size_available.y),
             },
             children,
+            fragments: vec![],
         })
     }
 
-    pub fn layout_size_box(size_available: Vec2, unit: &SizeBox) -> Option<LayoutNode> {
+    /// Rects of items (excluding margins) placed one by one in currently shortest column
+    /// (leftmost one when there are many), together with height of the tallest column.
+    fn masonry_box_placements(
+        &self,
+        size_available: Vec2,
+        unit: &MasonryBox,
+    ) -> (Vec<Rect>, Scalar) {
+        let count = unit.columns_count(size_available.x);
+        let column_width = ((size_available.x - (count - 1) as Scalar * unit.column_gap)
+            / count as Scalar)
+            .max(0.0);
+        let mut columns = vec![None; count];
+        let placements = unit
+            .items
+            .iter()
+            .map(|item| {
+                let mut index = 0;
+                let mut top = Scalar::INFINITY;
+                for (i, bottom) in columns.iter().enumerate() {
+                    let v = bottom.map(|v: Scalar| v + unit.row_gap).unwrap_or(0.0);
+                    if v < top {
+                        index = i;
+                        top = v;
+                    }
+                }
+                let width = item.layout.constraints.clamp_width(
+                    (column_width - item.layout.margin.left - item.layout.margin.right).max(0.0),
+                );
+                let height = item
+                    .layout
+                    .constraints
+                    .clamp_height(self.calc_unit_min_height(
+                        Vec2 {
+                            x: width,
+                            y: size_available.y,
+                        },
+                        &item.slot,
+                    ));
+                let left = index as Scalar * (column_width + unit.column_gap);
+                let rect = Rect {
+                    left: left + item.layout.margin.left,
+                    right: left + item.layout.margin.left + width,
+                    top: top + item.layout.margin.top,
+                    bottom: top + item.layout.margin.top + height,
+                };
+                columns[index] = Some(rect.bottom + item.layout.margin.bottom);
+                rect
+            })
+            .collect();
+        let height = columns
+            .into_iter()
+            .fold(0.0, |a: Scalar, b| a.max(b.unwrap_or(0.0)));
+        (placements, height)
+    }
+
+    pub fn layout_size_box(&self, size_available: Vec2, unit: &SizeBox) -> Option<LayoutNode> {
         if !unit.id.is_valid() {
             return None;
         }
-        let size = Vec2 {
+        let size = unit.constraints.clamp(Vec2 {
             x: match unit.width {
-                SizeBoxSizeValue::Content => Self::calc_unit_min_width(size_available, &unit.slot),
+                SizeBoxSizeValue::Content => self.calc_unit_min_width(size_available, &unit.slot),
                 SizeBoxSizeValue::Fill => size_available.x - unit.margin.left - unit.margin.right,
                 SizeBoxSizeValue::Exact(v) => v,
             },
             y: match unit.height {
-                SizeBoxSizeValue::Content => Self::calc_unit_min_height(size_available, &unit.slot),
+                SizeBoxSizeValue::Content => self.calc_unit_min_height(size_available, &unit.slot),
                 SizeBoxSizeValue::Fill => size_available.y - unit.margin.top - unit.margin.bottom,
                 SizeBoxSizeValue::Exact(v) => v,
             },
-        };
-        let children = if let Some(mut child) = Self::layout_node(size, &unit.slot) {
+        });
+        let children = if let Some(mut child) = self.layout_node(size, &unit.slot) {
             child.local_space.left += unit.margin.left;
             child.local_space.right += unit.margin.left;
             child.local_space.top += unit.margin.top;
@@ -561,65 +1843,775 @@ impl DefaultLayoutEngine {
             id: unit.id.to_owned(),
             local_space,
             children,
+            fragments: vec![],
         })
     }
 
-    pub fn layout_image_box(size_available: Vec2, unit: &ImageBox) -> Option<LayoutNode> {
+    pub fn layout_image_box(&self, size_available: Vec2, unit: &ImageBox) -> Option<LayoutNode> {
         if !unit.id.is_valid() {
             return None;
         }
+        // content sized image that cannot be measured behaves like filling one.
+        let size = self
+            .image_box_size(size_available, unit)
+            .unwrap_or(size_available);
         let local_space = Rect {
             left: 0.0,
             right: match unit.width {
                 ImageBoxSizeValue::Fill => size_available.x,
                 ImageBoxSizeValue::Exact(v) => v,
+                _ => size.x,
             },
             top: 0.0,
             bottom: match unit.height {
                 ImageBoxSizeValue::Fill => size_available.y,
                 ImageBoxSizeValue::Exact(v) => v,
+                _ => size.y,
             },
         };
         Some(LayoutNode {
             id: unit.id.to_owned(),
             local_space,
             children: vec![],
+            fragments: vec![],
         })
     }
 
-    pub fn layout_text_box(size_available: Vec2, unit: &TextBox) -> Option<LayoutNode> {
+    /// Native size of image box material (part of image selected by `source_rect`), or `None`
+    /// when image measurement engine cannot measure it.
+    pub fn measure_image(&self, unit: &ImageBox) -> Option<Vec2> {
+        if let ImageBoxMaterial::Image(image) = &unit.material {
+            let size = self.image_measurement_engine.measure_image(&image.id)?;
+            Some(match image.source_rect {
+                Some(rect) => Vec2 {
+                    x: size.x * rect.width(),
+                    y: size.y * rect.height(),
+                },
+                None => size,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Resolves size of image box with content sized axes measured, or `None` when there are
+    /// content sized axes but image cannot be measured.
+    ///
+    /// When image keeps content aspect ratio and only one axis is content sized, that axis is
+    /// calculated from the other one to match native image aspect ratio.
+    fn image_box_size(&self, size_available: Vec2, unit: &ImageBox) -> Option<Vec2> {
+        fn fixed(value: ImageBoxSizeValue, available: Scalar) -> Option<Scalar> {
+            match value {
+                ImageBoxSizeValue::Fill => Some(available),
+                ImageBoxSizeValue::Exact(v) => Some(v),
+                _ => None,
+            }
+        }
+
+        let width = fixed(unit.width, size_available.x);
+        let height = fixed(unit.height, size_available.y);
+        if let (Some(x), Some(y)) = (width, height) {
+            return Some(Vec2 { x, y });
+        }
+        let native = self.measure_image(unit)?;
+        let keep_aspect_ratio =
+            unit.content_keep_aspect_ratio.is_some() && native.x > 0.0 && native.y > 0.0;
+        let size = match (width, height) {
+            (Some(x), None) if keep_aspect_ratio => Vec2 {
+                x,
+                y: x * native.y / native.x,
+            },
+            (None, Some(y)) if keep_aspect_ratio => Vec2 {
+                x: y * native.x / native.y,
+                y,
+            },
+            (width, height) => Vec2 {
+                x: width.unwrap_or_else(|| native.x * unit.width.content_scale()),
+                y: height.unwrap_or_else(|| native.y * unit.height.content_scale()),
+            },
+        };
+        Some(size)
+    }
+
+    pub fn layout_text_box(&self, size_available: Vec2, unit: &TextBox) -> Option<LayoutNode> {
         if !unit.id.is_valid() {
             return None;
         }
+        let width = match unit.width {
+            TextBoxSizeValue::Content => self
+                .measure_text(size_available.x, unit)
+                .map(|size| size.x)
+                .unwrap_or(size_available.x),
+            TextBoxSizeValue::Fill => size_available.x,
+            TextBoxSizeValue::Exact(v) => v,
+        };
+        let height = match unit.height {
+            TextBoxSizeValue::Content => self
+                .measure_text(width, unit)
+                .map(|size| size.y)
+                .unwrap_or(size_available.y),
+            TextBoxSizeValue::Fill => size_available.y,
+            TextBoxSizeValue::Exact(v) => v,
+        };
         let local_space = Rect {
             left: 0.0,
-            right: match unit.width {
-                TextBoxSizeValue::Fill => size_available.x,
-                TextBoxSizeValue::Exact(v) => v,
-            },
+            right: width,
             top: 0.0,
-            bottom: match unit.height {
-                TextBoxSizeValue::Fill => size_available.y,
-                TextBoxSizeValue::Exact(v) => v,
-            },
+            bottom: height,
+        };
+        let fragments = if is_text_box_laid_out(unit) {
+            let max_height = match unit.height {
+                TextBoxSizeValue::Content => None,
+                _ => Some(height),
+            };
+            self.text_box_fragments(width, height, max_height, unit)
+        } else {
+            vec![]
         };
         Some(LayoutNode {
             id: unit.id.to_owned(),
             local_space,
             children: vec![],
+            fragments,
+        })
+    }
+
+    fn text_box_fragments(
+        &self,
+        width: Scalar,
+        height: Scalar,
+        max_height: Option<Scalar>,
+        unit: &TextBox,
+    ) -> Vec<LayoutTextFragment> {
+        let width = width.max(0.0);
+        let lines = self.text_box_lines(width, max_height, unit);
+        let content_height = lines.size().y;
+        let align = match unit.horizontal_align {
+            TextBoxHorizontalAlign::Left => 0.0,
+            TextBoxHorizontalAlign::Center => 0.5,
+            TextBoxHorizontalAlign::Right => 1.0,
+        };
+        let top = match unit.vertical_align {
+            TextBoxVerticalAlign::Top => 0.0,
+            TextBoxVerticalAlign::Middle => (height - content_height) * 0.5,
+            TextBoxVerticalAlign::Bottom => height - content_height,
+        };
+        let TextBoxLines {
+            lines,
+            font_size,
+            metrics,
+            line_height,
+            ..
+        } = lines;
+        let leading = (line_height - metrics.line_height()) * 0.5;
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let left = (width - line.width) * align;
+                let baseline = top + index as Scalar * line_height + leading + metrics.ascent;
+                LayoutTextFragment {
+                    span: 0,
+                    range: line.range,
+                    text: line.text,
+                    font_size,
+                    rect: Rect {
+                        left,
+                        right: left + line.width,
+                        top: baseline - metrics.ascent,
+                        bottom: baseline + metrics.descent,
+                    },
+                    baseline: metrics.ascent,
+                }
+            })
+            .collect()
+    }
+
+    /// Lays out lines of text box wrapped to fit `max_width`. Overflowing text gets elided or
+    /// shrunk to fit `max_width` and `max_height` (unlimited when not set).
+    fn text_box_lines(
+        &self,
+        max_width: Scalar,
+        max_height: Option<Scalar>,
+        unit: &TextBox,
+    ) -> TextBoxLines {
+        let max_width = max_width.max(0.0);
+        let lines = self.text_box_lines_scaled(max_width, max_height, unit, 1.0);
+        if unit.overflow != TextBoxOverflow::ShrinkToFit {
+            return lines;
+        }
+        let fits = |lines: &TextBoxLines| {
+            let size = lines.size();
+            !lines.truncated
+                && size.x <= max_width
+                && max_height.map(|height| size.y <= height).unwrap_or(true)
+        };
+        if fits(&lines) {
+            return lines;
+        }
+        let mut min = 0.0;
+        let mut max = 1.0;
+        let mut result = None;
+        for _ in 0..TEXT_SHRINK_ITERATIONS {
+            let scale = (min + max) * 0.5;
+            let lines = self.text_box_lines_scaled(max_width, max_height, unit, scale);
+            if fits(&lines) {
+                min = scale;
+                result = Some(lines);
+            } else {
+                max = scale;
+            }
+        }
+        result.unwrap_or_else(|| self.text_box_lines_scaled(max_width, max_height, unit, max))
+    }
+
+    fn text_box_lines_scaled(
+        &self,
+        max_width: Scalar,
+        max_height: Option<Scalar>,
+        unit: &TextBox,
+        scale: Scalar,
+    ) -> TextBoxLines {
+        let font = TextBoxFont {
+            name: unit.font.name.to_owned(),
+            size: unit.font.size * scale,
+        };
+        let metrics = self.font_metrics(&font);
+        let line_height = unit
+            .line_height
+            .map(|v| v * scale)
+            .unwrap_or_else(|| metrics.line_height());
+        let mut ranges = self.wrap_text(&unit.text, &font, max_width, unit.wrap);
+        let mut limit = unit.max_lines.unwrap_or(usize::MAX);
+        if let Some(max_height) = max_height {
+            if line_height > 0.0 {
+                let count = (max_height / line_height + 1.0e-4).floor().max(0.0) as usize;
+                match unit.overflow {
+                    TextBoxOverflow::Clip => limit = limit.min(count),
+                    TextBoxOverflow::EllipsisEnd | TextBoxOverflow::EllipsisMiddle => {
+                        limit = limit.min(count.max(1))
+                    }
+                    TextBoxOverflow::Visible | TextBoxOverflow::ShrinkToFit => {}
+                }
+            }
+        }
+        let truncated = ranges.len() > limit;
+        ranges.truncate(limit);
+        let count = ranges.len();
+        let lines = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(index, range)| {
+                let text = &unit.text[range.clone()];
+                // last shown line gets ellipsis replacing all text that is not shown.
+                let elided = truncated && index + 1 == count;
+                match unit.overflow {
+                    TextBoxOverflow::EllipsisEnd if elided => {
+                        let range = range.start..unit.text.len();
+                        self.elide_text_end(text, range, &font, max_width)
+                    }
+                    TextBoxOverflow::EllipsisMiddle if elided => {
+                        let rest = unit.text[range.start..].trim_end().replace('\n', " ");
+                        let range = range.start..unit.text.len();
+                        self.elide_text_middle(&rest, range, &font, max_width)
+                    }
+                    overflow => {
+                        let line = self.text_line(text, range.clone(), &font);
+                        if line.width <= max_width {
+                            return line;
+                        }
+                        match overflow {
+                            TextBoxOverflow::Clip => self.clip_text(text, range, &font, max_width),
+                            TextBoxOverflow::EllipsisEnd => {
+                                self.elide_text_end(text, range, &font, max_width)
+                            }
+                            TextBoxOverflow::EllipsisMiddle => {
+                                self.elide_text_middle(text, range, &font, max_width)
+                            }
+                            TextBoxOverflow::Visible | TextBoxOverflow::ShrinkToFit => line,
+                        }
+                    }
+                }
+            })
+            .collect();
+        TextBoxLines {
+            lines,
+            font_size: font.size,
+            metrics,
+            line_height,
+            truncated,
+        }
+    }
+
+    fn text_line(&self, text: &str, range: Range<usize>, font: &TextBoxFont) -> TextBoxLine {
+        TextBoxLine {
+            range,
+            text: text.to_owned(),
+            width: self.measure_text_width(text, font),
+        }
+    }
+
+    fn clip_text(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        font: &TextBoxFont,
+        max_width: Scalar,
+    ) -> TextBoxLine {
+        let bounds = char_bounds(text);
+        let count = fit_count(bounds.len() - 1, |count| {
+            self.measure_text_width(&text[..bounds[count]], font) <= max_width
+        });
+        self.text_line(&text[..bounds[count]], range, font)
+    }
+
+    fn elide_text_end(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        font: &TextBoxFont,
+        max_width: Scalar,
+    ) -> TextBoxLine {
+        let bounds = char_bounds(text);
+        let elided =
+            |count: usize| format!("{}{}", text[..bounds[count]].trim_end(), TEXT_ELLIPSIS);
+        let count = fit_count(bounds.len() - 1, |count| {
+            self.measure_text_width(&elided(count), font) <= max_width
+        });
+        self.text_line(&elided(count), range, font)
+    }
+
+    fn elide_text_middle(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        font: &TextBoxFont,
+        max_width: Scalar,
+    ) -> TextBoxLine {
+        let bounds = char_bounds(text);
+        let chars = bounds.len() - 1;
+        let elided = |count: usize| {
+            let head = bounds[count - count / 2];
+            let tail = bounds[chars - count / 2];
+            format!(
+                "{}{}{}",
+                text[..head].trim_end(),
+                TEXT_ELLIPSIS,
+                text[tail..].trim_start()
+            )
+        };
+        let count = fit_count(chars.saturating_sub(1), |count| {
+            self.measure_text_width(&elided(count), font) <= max_width
+        });
+        self.text_line(&elided(count), range, font)
+    }
+
+    /// Breaks text into lines (as byte ranges excluding whitespaces at line breaks) at new lines
+    /// and wherever wrap mode allows to keep lines within `max_width`.
+    fn wrap_text(
+        &self,
+        text: &str,
+        font: &TextBoxFont,
+        max_width: Scalar,
+        wrap: TextBoxWrap,
+    ) -> Vec<Range<usize>> {
+        let mut result = vec![];
+        let mut start = 0;
+        for line in text.split('\n') {
+            let end = start + line.len();
+            if wrap == TextBoxWrap::None {
+                result.push(start..end);
+            } else {
+                self.wrap_text_line(text, start..end, font, max_width, wrap, &mut result);
+            }
+            start = end + 1;
+        }
+        result
+    }
+
+    fn wrap_text_line(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        font: &TextBoxFont,
+        max_width: Scalar,
+        wrap: TextBoxWrap,
+        result: &mut Vec<Range<usize>>,
+    ) {
+        let mut start = range.start;
+        loop {
+            let rest = &text[start..range.end];
+            if rest.is_empty() {
+                result.push(start..start);
+                return;
+            }
+            let trimmed = rest.trim_end();
+            if self.measure_text_width(trimmed, font) <= max_width {
+                result.push(start..(start + trimmed.len()));
+                return;
+            }
+            let bounds = char_bounds(rest);
+            let count = fit_count(bounds.len() - 1, |count| {
+                self.measure_text_width(rest[..bounds[count]].trim_end(), font) <= max_width
+            });
+            let mut end = bounds[count.max(1)];
+            if wrap == TextBoxWrap::Word {
+                let breaks_word = rest[..end]
+                    .chars()
+                    .next_back()
+                    .map(|c| !c.is_whitespace())
+                    .unwrap_or(false)
+                    && rest[end..]
+                        .chars()
+                        .next()
+                        .map(|c| !c.is_whitespace())
+                        .unwrap_or(false);
+                if breaks_word {
+                    // words wider than line are broken between characters.
+                    if let Some(position) = rest[..end].rfind(char::is_whitespace) {
+                        if !rest[..position].trim_end().is_empty() {
+                            end = position;
+                        }
+                    }
+                }
+            }
+            result.push(start..(start + rest[..end].trim_end().len()));
+            start += end + (rest[end..].len() - rest[end..].trim_start().len());
+            if start >= range.end {
+                return;
+            }
+        }
+    }
+
+    pub fn layout_rich_text_box(
+        &self,
+        size_available: Vec2,
+        unit: &RichTextBox,
+    ) -> Option<LayoutNode> {
+        if !unit.id.is_valid() {
+            return None;
+        }
+        let width = match unit.width {
+            TextBoxSizeValue::Content => self.measure_rich_text(size_available.x, unit).x,
+            TextBoxSizeValue::Fill => size_available.x,
+            TextBoxSizeValue::Exact(v) => v,
+        };
+        let lines = self.rich_text_lines(width, unit);
+        let content_height = lines
+            .iter()
+            .map(|line| line.ascent + line.descent)
+            .sum::<Scalar>();
+        let height = match unit.height {
+            TextBoxSizeValue::Content => content_height,
+            TextBoxSizeValue::Fill => size_available.y,
+            TextBoxSizeValue::Exact(v) => v,
+        };
+        let align = match unit.horizontal_align {
+            TextBoxHorizontalAlign::Left => 0.0,
+            TextBoxHorizontalAlign::Center => 0.5,
+            TextBoxHorizontalAlign::Right => 1.0,
+        };
+        let mut top = match unit.vertical_align {
+            TextBoxVerticalAlign::Top => 0.0,
+            TextBoxVerticalAlign::Middle => (height - content_height) * 0.5,
+            TextBoxVerticalAlign::Bottom => height - content_height,
+        };
+        let mut fragments = Vec::with_capacity(lines.iter().map(|line| line.pieces.len()).sum());
+        for line in lines {
+            let left = (width - line.width) * align;
+            let baseline = top + line.ascent;
+            for piece in line.pieces {
+                let span = &unit.spans[piece.span];
+                let text = span
+                    .text()
+                    .and_then(|text| text.get(piece.range.clone()))
+                    .unwrap_or_default()
+                    .to_owned();
+                fragments.push(LayoutTextFragment {
+                    span: piece.span,
+                    range: piece.range,
+                    text,
+                    font_size: span.font.size,
+                    rect: Rect {
+                        left: left + piece.x,
+                        right: left + piece.x + piece.width,
+                        top: baseline - piece.ascent,
+                        bottom: baseline + piece.descent,
+                    },
+                    baseline: piece.ascent,
+                });
+            }
+            top = baseline + line.descent;
+        }
+        Some(LayoutNode {
+            id: unit.id.to_owned(),
+            local_space: Rect {
+                left: 0.0,
+                right: width,
+                top: 0.0,
+                bottom: height,
+            },
+            children: vec![],
+            fragments,
+        })
+    }
+
+    /// Size of rich text box content with lines wrapped to fit `max_width`.
+    pub fn measure_rich_text(&self, max_width: Scalar, unit: &RichTextBox) -> Vec2 {
+        self.rich_text_lines(max_width, unit)
+            .iter()
+            .fold(Vec2::default(), |size, line| Vec2 {
+                x: size.x.max(line.width),
+                y: size.y + line.ascent + line.descent,
+            })
+    }
+
+    /// Breaks spans of rich text box into lines at whitespaces (or inside whitespaces runs when
+    /// needed) and new lines. Words wider than `max_width` are not broken.
+    fn rich_text_lines(&self, max_width: Scalar, unit: &RichTextBox) -> Vec<RichTextLine> {
+        let mut lines = vec![];
+        let mut line = RichTextLine::default();
+        // position of next piece in line, including trailing whitespaces.
+        let mut cursor = 0.0;
+        // pieces of word not placed in line yet, positioned relative to word start.
+        let mut word = vec![];
+        let mut word_width = 0.0;
+        let place_word = |line: &mut RichTextLine,
+                          lines: &mut Vec<RichTextLine>,
+                          cursor: &mut Scalar,
+                          word: &mut Vec<RichTextPiece>,
+                          word_width: &mut Scalar| {
+            if word.is_empty() {
+                return;
+            }
+            if line.width > 0.0 && *cursor + *word_width > max_width {
+                lines.push(std::mem::take(line));
+                *cursor = 0.0;
+            }
+            for mut piece in word.drain(..) {
+                piece.x += *cursor;
+                line.push(piece);
+            }
+            *cursor += *word_width;
+            *word_width = 0.0;
+            line.width = *cursor;
+        };
+        for (index, span) in unit.spans.iter().enumerate() {
+            let text = match &span.content {
+                RichTextBoxSpanContent::Text(text) => text,
+                RichTextBoxSpanContent::Image(image) => {
+                    word.push(RichTextPiece {
+                        span: index,
+                        range: 0..0,
+                        x: word_width,
+                        width: image.width,
+                        ascent: image.height,
+                        descent: 0.0,
+                    });
+                    word_width += image.width;
+                    continue;
+                }
+            };
+            let metrics = self.font_metrics(&span.font);
+            let mut start = 0;
+            while start < text.len() {
+                let rest = &text[start..];
+                let first = rest.chars().next().unwrap();
+                if first == '\n' {
+                    place_word(
+                        &mut line,
+                        &mut lines,
+                        &mut cursor,
+                        &mut word,
+                        &mut word_width,
+                    );
+                    line.ascent = line.ascent.max(metrics.ascent);
+                    line.descent = line.descent.max(metrics.descent);
+                    lines.push(std::mem::replace(
+                        &mut line,
+                        RichTextLine {
+                            ascent: metrics.ascent,
+                            descent: metrics.descent,
+                            ..Default::default()
+                        },
+                    ));
+                    cursor = 0.0;
+                    start += 1;
+                    continue;
+                }
+                let whitespace = first.is_whitespace();
+                let length = rest
+                    .find(|c: char| c == '\n' || c.is_whitespace() != whitespace)
+                    .unwrap_or(rest.len());
+                let piece = &rest[..length];
+                let width = self.measure_text_width(piece, &span.font);
+                let range = start..(start + length);
+                if whitespace {
+                    place_word(
+                        &mut line,
+                        &mut lines,
+                        &mut cursor,
+                        &mut word,
+                        &mut word_width,
+                    );
+                    line.push(RichTextPiece {
+                        span: index,
+                        range,
+                        x: cursor,
+                        width,
+                        ascent: metrics.ascent,
+                        descent: metrics.descent,
+                    });
+                    cursor += width;
+                } else {
+                    word.push(RichTextPiece {
+                        span: index,
+                        range,
+                        x: word_width,
+                        width,
+                        ascent: metrics.ascent,
+                        descent: metrics.descent,
+                    });
+                    word_width += width;
+                }
+                start += length;
+            }
+        }
+        place_word(
+            &mut line,
+            &mut lines,
+            &mut cursor,
+            &mut word,
+            &mut word_width,
+        );
+        if !line.pieces.is_empty() || line.ascent + line.descent > 0.0 {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Width of single line text, approximated from font size (half of it per character) when
+    /// text measurement engine cannot measure it.
+    fn measure_text_width(&self, text: &str, font: &TextBoxFont) -> Scalar {
+        let measure = |text: String| {
+            let unit = TextBox {
+                text,
+                width: TextBoxSizeValue::Content,
+                height: TextBoxSizeValue::Content,
+                font: font.to_owned(),
+                ..Default::default()
+            };
+            self.measure_text(Scalar::INFINITY, &unit)
+                .map(|size| size.x)
+        };
+        let result = if text.chars().all(char::is_whitespace) {
+            // measured text bounds usually skip trailing whitespaces, so their advance gets
+            // measured between two characters.
+            measure(format!("x{}x", text)).and_then(|width| Some(width - measure("xx".to_owned())?))
+        } else {
+            measure(text.to_owned())
+        };
+        result.unwrap_or_else(|| text.chars().count() as Scalar * font.size * 0.5)
+    }
+
+    pub fn layout_custom_box(&self, size_available: Vec2, unit: &CustomBox) -> Option<LayoutNode> {
+        if !unit.id.is_valid() {
+            return None;
+        }
+        let size = self
+            .measure_custom_unit(size_available, unit)
+            .unwrap_or(size_available);
+        Some(LayoutNode {
+            id: unit.id.to_owned(),
+            local_space: Rect {
+                left: 0.0,
+                right: size.x,
+                top: 0.0,
+                bottom: size.y,
+            },
+            children: vec![],
+            fragments: vec![],
         })
     }
 
-    fn calc_unit_min_width(size_available: Vec2, unit: &WidgetUnit) -> Scalar {
+    /// Size of custom unit content measured by measurement registered for its type name.
+    pub fn measure_custom_unit(&self, size_available: Vec2, unit: &CustomBox) -> Option<Vec2> {
+        self.custom_unit_measurements
+            .0
+            .get(&unit.type_name)?
+            .measure_custom_unit(size_available, unit)
+    }
+
+    /// Measures text of given text box wrapped at `max_width`, or `None` when text measurement
+    /// engine cannot measure it. Text boxes with wrapping or overflow settings other than
+    /// default ones are measured by their lines laid out by layout engine.
+    pub fn measure_text(&self, max_width: Scalar, unit: &TextBox) -> Option<Vec2> {
+        if is_text_box_laid_out(unit) {
+            return Some(self.text_box_lines(max_width.max(0.0), None, unit).size());
+        }
+        self.text_measurement_engine
+            .measure_text(max_width.max(0.0), unit)
+    }
+
+    /// Font metrics reported by text measurement engine, approximated from font size for fonts
+    /// it cannot measure.
+    pub fn font_metrics(&self, font: &TextBoxFont) -> FontMetrics {
+        self.text_measurement_engine
+            .font_metrics(font)
+            .unwrap_or_else(|| FontMetrics::approximated(font.size))
+    }
+
+    /// Distance from top edge of unit with given height to its first text baseline. Units
+    /// without text baseline are aligned by their bottom edge.
+    pub fn unit_baseline(&self, unit: &WidgetUnit, height: Scalar) -> Scalar {
+        match unit {
+            WidgetUnit::AreaBox(b) => self.unit_baseline(&b.slot, height),
+            WidgetUnit::SizeBox(b) => b.margin.top + self.unit_baseline(&b.slot, height),
+            WidgetUnit::ImageBox(b) => height - b.baseline_offset,
+            WidgetUnit::TextBox(b) => {
+                let metrics = self.font_metrics(&b.font);
+                let leading = b
+                    .line_height
+                    .map(|v| (v - metrics.line_height()) * 0.5)
+                    .unwrap_or(0.0);
+                match b.vertical_align {
+                    TextBoxVerticalAlign::Top => leading + metrics.ascent,
+                    TextBoxVerticalAlign::Middle => {
+                        (height - metrics.line_height()) * 0.5 + metrics.ascent
+                    }
+                    TextBoxVerticalAlign::Bottom => height - leading - metrics.descent,
+                }
+            }
+            _ => height,
+        }
+    }
+
+    fn calc_unit_min_width(&self, size_available: Vec2, unit: &WidgetUnit) -> Scalar {
+        let key = match unit.as_data() {
+            Some(data) if data.id().is_valid() => {
+                (data.id().to_owned(), false, size_key(size_available))
+            }
+            _ => return self.calc_unit_min_width_uncached(size_available, unit),
+        };
+        if let Some(result) = self.cache.lock().min_sizes.get(&key) {
+            return *result;
+        }
+        let result = self.calc_unit_min_width_uncached(size_available, unit);
+        self.cache.lock().min_sizes.insert(key, result);
+        result
+    }
+
+    fn calc_unit_min_width_uncached(&self, size_available: Vec2, unit: &WidgetUnit) -> Scalar {
         match unit {
             WidgetUnit::None | WidgetUnit::PortalBox(_) => 0.0,
-            WidgetUnit::AreaBox(b) => Self::calc_unit_min_width(size_available, &b.slot),
-            WidgetUnit::ContentBox(b) => Self::calc_content_box_min_width(size_available, b),
-            WidgetUnit::FlexBox(b) => Self::calc_flex_box_min_width(size_available, b),
-            WidgetUnit::GridBox(b) => Self::calc_grid_box_min_width(size_available, b),
+            WidgetUnit::AreaBox(b) => self.calc_unit_min_width(size_available, &b.slot),
+            WidgetUnit::ContentBox(b) => self.calc_content_box_min_width(size_available, b),
+            WidgetUnit::FlexBox(b) => self.calc_flex_box_min_width(size_available, b),
+            WidgetUnit::GridBox(b) => self.calc_grid_box_min_width(size_available, b),
+            WidgetUnit::MasonryBox(b) => self.calc_masonry_box_min_width(size_available, b),
             WidgetUnit::SizeBox(b) => {
-                (match b.width {
-                    SizeBoxSizeValue::Content => Self::calc_unit_min_width(size_available, &b.slot),
+                b.constraints.clamp_width(match b.width {
+                    SizeBoxSizeValue::Content => self.calc_unit_min_width(size_available, &b.slot),
                     SizeBoxSizeValue::Fill => 0.0,
                     SizeBoxSizeValue::Exact(v) => v,
                 }) + b.margin.left
@@ -628,18 +2620,39 @@ impl DefaultLayoutEngine {
             WidgetUnit::ImageBox(b) => match b.width {
                 ImageBoxSizeValue::Fill => 0.0,
                 ImageBoxSizeValue::Exact(v) => v,
+                _ => self
+                    .image_box_size(size_available, b)
+                    .map(|size| size.x)
+                    .unwrap_or(0.0),
             },
             WidgetUnit::TextBox(b) => match b.width {
+                TextBoxSizeValue::Content => self
+                    .measure_text(size_available.x, b)
+                    .map(|size| size.x)
+                    .unwrap_or(0.0),
                 TextBoxSizeValue::Fill => 0.0,
                 TextBoxSizeValue::Exact(v) => v,
             },
+            WidgetUnit::RichTextBox(b) => match b.width {
+                TextBoxSizeValue::Content => self.measure_rich_text(size_available.x, b).x,
+                TextBoxSizeValue::Fill => 0.0,
+                TextBoxSizeValue::Exact(v) => v,
+            },
+            WidgetUnit::CustomBox(b) => self
+                .measure_custom_unit(size_available, b)
+                .map(|size| size.x)
+                .unwrap_or(0.0),
         }
     }
 
-    fn calc_content_box_min_width(size_available: Vec2, unit: &ContentBox) -> Scalar {
+    /// Layout constraints of content box are not taken into account, only items anchors.
+    fn calc_content_box_min_width(&self, size_available: Vec2, unit: &ContentBox) -> Scalar {
         let mut result: Scalar = 0.0;
         for item in &unit.items {
-            let size = Self::calc_unit_min_width(size_available, &item.slot)
+            let size = item
+                .layout
+                .constraints
+                .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                 + item.layout.margin.left
                 + item.layout.margin.right;
             let width = item.layout.anchors.right - item.layout.anchors.left;
@@ -649,21 +2662,24 @@ impl DefaultLayoutEngine {
         result
     }
 
-    fn calc_flex_box_min_width(size_available: Vec2, unit: &FlexBox) -> Scalar {
+    fn calc_flex_box_min_width(&self, size_available: Vec2, unit: &FlexBox) -> Scalar {
         if unit.direction.is_horizontal() {
-            Self::calc_horizontal_flex_box_min_width(size_available, unit)
+            self.calc_horizontal_flex_box_min_width(size_available, unit)
         } else {
-            Self::calc_vertical_flex_box_min_width(size_available, unit)
+            self.calc_vertical_flex_box_min_width(size_available, unit)
         }
     }
 
-    fn calc_horizontal_flex_box_min_width(size_available: Vec2, unit: &FlexBox) -> Scalar {
+    fn calc_horizontal_flex_box_min_width(&self, size_available: Vec2, unit: &FlexBox) -> Scalar {
         if unit.wrap {
             let mut result: Scalar = 0.0;
             let mut line = 0.0;
             let mut first = true;
             for item in &unit.items {
-                let size = Self::calc_unit_min_width(size_available, &item.slot)
+                let size = item
+                    .layout
+                    .constraints
+                    .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                     + item.layout.margin.left
                     + item.layout.margin.right;
                 if first || line + size <= size_available.x {
@@ -682,7 +2698,10 @@ impl DefaultLayoutEngine {
         } else {
             let mut result = 0.0;
             for item in &unit.items {
-                result += Self::calc_unit_min_width(size_available, &item.slot)
+                result += item
+                    .layout
+                    .constraints
+                    .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                     + item.layout.margin.left
                     + item.layout.margin.right;
             }
@@ -690,7 +2709,7 @@ impl DefaultLayoutEngine {
         }
     }
 
-    fn calc_vertical_flex_box_min_width(size_available: Vec2, unit: &FlexBox) -> Scalar {
+    fn calc_vertical_flex_box_min_width(&self, size_available: Vec2, unit: &FlexBox) -> Scalar {
         if unit.wrap {
             let mut result = 0.0;
             let mut line_length = 0.0;
@@ -698,10 +2717,16 @@ impl DefaultLayoutEngine {
             let mut lines: usize = 0;
             let mut first = true;
             for item in &unit.items {
-                let width = Self::calc_unit_min_width(size_available, &item.slot)
+                let width = item
+                    .layout
+                    .constraints
+                    .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                     + item.layout.margin.left
                     + item.layout.margin.right;
-                let height = Self::calc_unit_min_height(size_available, &item.slot)
+                let height = item
+                    .layout
+                    .constraints
+                    .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
                     + item.layout.margin.top
                     + item.layout.margin.bottom;
                 if first || line_length + height <= size_available.y {
@@ -721,10 +2746,15 @@ impl DefaultLayoutEngine {
             }
             result += line;
             lines += 1;
-            result + (lines.saturating_sub(1) as Scalar) * unit.separation
+            result
+                + (lines.saturating_sub(1) as Scalar)
+                    * unit.cross_separation.unwrap_or(unit.separation)
         } else {
             unit.items.iter().fold(0.0, |a, item| {
-                (Self::calc_unit_min_width(size_available, &item.slot)
+                (item
+                    .layout
+                    .constraints
+                    .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                     + item.layout.margin.left
                     + item.layout.margin.right)
                     .max(a)
@@ -732,34 +2762,61 @@ impl DefaultLayoutEngine {
         }
     }
 
-    fn calc_grid_box_min_width(size_available: Vec2, unit: &GridBox) -> Scalar {
-        let mut result: Scalar = 0.0;
-        for item in &unit.items {
-            let size = Self::calc_unit_min_width(size_available, &item.slot)
+    fn calc_grid_box_min_width(&self, size_available: Vec2, unit: &GridBox) -> Scalar {
+        let (cells, columns, _) = grid_box_cells(unit);
+        let tracks = grid_box_tracks(&unit.column_tracks, unit.cols, columns);
+        let items = self.grid_box_items_sizes(size_available, unit, &cells, &tracks, false);
+        resolve_grid_tracks(&tracks, unit.column_gap, None, &items)
+            .into_iter()
+            .sum::<Scalar>()
+            + tracks.len().saturating_sub(1) as Scalar * unit.column_gap
+    }
+
+    fn calc_masonry_box_min_width(&self, size_available: Vec2, unit: &MasonryBox) -> Scalar {
+        let count = unit.columns.max(1);
+        let column = unit.items.iter().fold(0.0, |a: Scalar, item| {
+            (item
+                .layout
+                .constraints
+                .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                 + item.layout.margin.left
-                + item.layout.margin.right;
-            let size = if size > 0.0 {
-                (item.layout.space_occupancy.width() as Scalar * size) / unit.cols as Scalar
-            } else {
-                0.0
-            };
-            result = result.max(size);
+                + item.layout.margin.right)
+                .max(a)
+        });
+        let column = if unit.columns > 0 {
+            column
+        } else {
+            column.max(unit.min_column_width)
+        };
+        count as Scalar * column + (count - 1) as Scalar * unit.column_gap
+    }
+
+    fn calc_unit_min_height(&self, size_available: Vec2, unit: &WidgetUnit) -> Scalar {
+        let key = match unit.as_data() {
+            Some(data) if data.id().is_valid() => {
+                (data.id().to_owned(), true, size_key(size_available))
+            }
+            _ => return self.calc_unit_min_height_uncached(size_available, unit),
+        };
+        if let Some(result) = self.cache.lock().min_sizes.get(&key) {
+            return *result;
         }
+        let result = self.calc_unit_min_height_uncached(size_available, unit);
+        self.cache.lock().min_sizes.insert(key, result);
         result
     }
 
-    fn calc_unit_min_height(size_available: Vec2, unit: &WidgetUnit) -> Scalar {
+    fn calc_unit_min_height_uncached(&self, size_available: Vec2, unit: &WidgetUnit) -> Scalar {
         match unit {
             WidgetUnit::None | WidgetUnit::PortalBox(_) => 0.0,
-            WidgetUnit::AreaBox(b) => Self::calc_unit_min_height(size_available, &b.slot),
-            WidgetUnit::ContentBox(b) => Self::calc_content_box_min_height(size_available, b),
-            WidgetUnit::FlexBox(b) => Self::calc_flex_box_min_height(size_available, b),
-            WidgetUnit::GridBox(b) => Self::calc_grid_box_min_height(size_available, b),
+            WidgetUnit::AreaBox(b) => self.calc_unit_min_height(size_available, &b.slot),
+            WidgetUnit::ContentBox(b) => self.calc_content_box_min_height(size_available, b),
+            WidgetUnit::FlexBox(b) => self.calc_flex_box_min_height(size_available, b),
+            WidgetUnit::GridBox(b) => self.calc_grid_box_min_height(size_available, b),
+            WidgetUnit::MasonryBox(b) => self.masonry_box_placements(size_available, b).1,
             WidgetUnit::SizeBox(b) => {
-                (match b.height {
-                    SizeBoxSizeValue::Content => {
-                        Self::calc_unit_min_height(size_available, &b.slot)
-                    }
+                b.constraints.clamp_height(match b.height {
+                    SizeBoxSizeValue::Content => self.calc_unit_min_height(size_available, &b.slot),
                     SizeBoxSizeValue::Fill => 0.0,
                     SizeBoxSizeValue::Exact(v) => v,
                 }) + b.margin.top
@@ -768,18 +2825,50 @@ impl DefaultLayoutEngine {
             WidgetUnit::ImageBox(b) => match b.height {
                 ImageBoxSizeValue::Fill => 0.0,
                 ImageBoxSizeValue::Exact(v) => v,
+                _ => self
+                    .image_box_size(size_available, b)
+                    .map(|size| size.y)
+                    .unwrap_or(0.0),
             },
             WidgetUnit::TextBox(b) => match b.height {
+                TextBoxSizeValue::Content => {
+                    let width = match b.width {
+                        TextBoxSizeValue::Exact(v) => v,
+                        _ => size_available.x,
+                    };
+                    self.measure_text(width, b)
+                        .map(|size| size.y)
+                        .unwrap_or(0.0)
+                }
+                TextBoxSizeValue::Fill => 0.0,
+                TextBoxSizeValue::Exact(v) => v,
+            },
+            WidgetUnit::RichTextBox(b) => match b.height {
+                TextBoxSizeValue::Content => {
+                    let width = match b.width {
+                        TextBoxSizeValue::Exact(v) => v,
+                        _ => size_available.x,
+                    };
+                    self.measure_rich_text(width, b).y
+                }
                 TextBoxSizeValue::Fill => 0.0,
                 TextBoxSizeValue::Exact(v) => v,
             },
+            WidgetUnit::CustomBox(b) => self
+                .measure_custom_unit(size_available, b)
+                .map(|size| size.y)
+                .unwrap_or(0.0),
         }
     }
 
-    fn calc_content_box_min_height(size_available: Vec2, unit: &ContentBox) -> Scalar {
+    /// Layout constraints of content box are not taken into account, only items anchors.
+    fn calc_content_box_min_height(&self, size_available: Vec2, unit: &ContentBox) -> Scalar {
         let mut result: Scalar = 0.0;
         for item in &unit.items {
-            let size = Self::calc_unit_min_height(size_available, &item.slot)
+            let size = item
+                .layout
+                .constraints
+                .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
                 + item.layout.margin.top
                 + item.layout.margin.bottom;
             let height = item.layout.anchors.bottom - item.layout.anchors.top;
@@ -789,15 +2878,15 @@ impl DefaultLayoutEngine {
         result
     }
 
-    fn calc_flex_box_min_height(size_available: Vec2, unit: &FlexBox) -> Scalar {
+    fn calc_flex_box_min_height(&self, size_available: Vec2, unit: &FlexBox) -> Scalar {
         if unit.direction.is_horizontal() {
-            Self::calc_horizontal_flex_box_min_height(size_available, unit)
+            self.calc_horizontal_flex_box_min_height(size_available, unit)
         } else {
-            Self::calc_vertical_flex_box_min_height(size_available, unit)
+            self.calc_vertical_flex_box_min_height(size_available, unit)
         }
     }
 
-    fn calc_horizontal_flex_box_min_height(size_available: Vec2, unit: &FlexBox) -> Scalar {
+    fn calc_horizontal_flex_box_min_height(&self, size_available: Vec2, unit: &FlexBox) -> Scalar {
         if unit.wrap {
             let mut result = 0.0;
             let mut line_length = 0.0;
@@ -805,10 +2894,16 @@ impl DefaultLayoutEngine {
             let mut lines: usize = 0;
             let mut first = true;
             for item in &unit.items {
-                let width = Self::calc_unit_min_width(size_available, &item.slot)
+                let width = item
+                    .layout
+                    .constraints
+                    .clamp_width(self.calc_unit_min_width(size_available, &item.slot))
                     + item.layout.margin.left
                     + item.layout.margin.right;
-                let height = Self::calc_unit_min_height(size_available, &item.slot)
+                let height = item
+                    .layout
+                    .constraints
+                    .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
                     + item.layout.margin.top
                     + item.layout.margin.bottom;
                 if first || line_length + width <= size_available.x {
@@ -828,24 +2923,41 @@ impl DefaultLayoutEngine {
             }
             result += line;
             lines += 1;
-            result + (lines.saturating_sub(1) as Scalar) * unit.separation
+            result
+                + (lines.saturating_sub(1) as Scalar)
+                    * unit.cross_separation.unwrap_or(unit.separation)
         } else {
-            unit.items.iter().fold(0.0, |a, item| {
-                (Self::calc_unit_min_height(size_available, &item.slot)
-                    + item.layout.margin.top
-                    + item.layout.margin.bottom)
-                    .max(a)
-            })
+            let mut result: Scalar = 0.0;
+            let mut ascent: Scalar = 0.0;
+            let mut descent: Scalar = 0.0;
+            for item in &unit.items {
+                let height = item
+                    .layout
+                    .constraints
+                    .clamp_height(self.calc_unit_min_height(size_available, &item.slot));
+                let size = height + item.layout.margin.top + item.layout.margin.bottom;
+                if item.layout.align_baseline {
+                    let baseline = item.layout.margin.top + self.unit_baseline(&item.slot, height);
+                    ascent = ascent.max(baseline);
+                    descent = descent.max(size - baseline);
+                } else {
+                    result = result.max(size);
+                }
+            }
+            result.max(ascent + descent)
         }
     }
 
-    fn calc_vertical_flex_box_min_height(size_available: Vec2, unit: &FlexBox) -> Scalar {
+    fn calc_vertical_flex_box_min_height(&self, size_available: Vec2, unit: &FlexBox) -> Scalar {
         if unit.wrap {
             let mut result: Scalar = 0.0;
             let mut line = 0.0;
             let mut first = true;
             for item in &unit.items {
-                let size = Self::calc_unit_min_height(size_available, &item.slot)
+                let size = item
+                    .layout
+                    .constraints
+                    .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
                     + item.layout.margin.top
                     + item.layout.margin.bottom;
                 if first || line + size <= size_available.y {
@@ -864,7 +2976,10 @@ impl DefaultLayoutEngine {
         } else {
             let mut result = 0.0;
             for item in &unit.items {
-                result += Self::calc_unit_min_height(size_available, &item.slot)
+                result += item
+                    .layout
+                    .constraints
+                    .clamp_height(self.calc_unit_min_height(size_available, &item.slot))
                     + item.layout.margin.top
                     + item.layout.margin.bottom;
             }
@@ -872,20 +2987,14 @@ impl DefaultLayoutEngine {
         }
     }
 
-    fn calc_grid_box_min_height(size_available: Vec2, unit: &GridBox) -> Scalar {
-        let mut result: Scalar = 0.0;
-        for item in &unit.items {
-            let size = Self::calc_unit_min_height(size_available, &item.slot)
-                + item.layout.margin.top
-                + item.layout.margin.bottom;
-            let size = if size > 0.0 {
-                (item.layout.space_occupancy.height() as Scalar * size) / unit.cols as Scalar
-            } else {
-                0.0
-            };
-            result = result.max(size);
-        }
-        result
+    fn calc_grid_box_min_height(&self, size_available: Vec2, unit: &GridBox) -> Scalar {
+        let (cells, _, rows) = grid_box_cells(unit);
+        let tracks = grid_box_tracks(&unit.row_tracks, unit.rows, rows);
+        let items = self.grid_box_items_sizes(size_available, unit, &cells, &tracks, true);
+        resolve_grid_tracks(&tracks, unit.row_gap, None, &items)
+            .into_iter()
+            .sum::<Scalar>()
+            + tracks.len().saturating_sub(1) as Scalar * unit.row_gap
     }
 
     fn unpack_node(
@@ -898,6 +3007,7 @@ impl DefaultLayoutEngine {
             id,
             local_space,
             children,
+            fragments,
         } = node;
         let ui_space = Rect {
             left: local_space.left + ui_space.left,
@@ -914,21 +3024,114 @@ impl DefaultLayoutEngine {
                 local_space,
                 ui_space,
                 parent: parent.cloned(),
+                layer: 0,
+                direction: Default::default(),
+                fragments,
             },
         );
     }
+
+    /// Mirrors horizontal placement of children of right-to-left laid out units.
+    ///
+    /// Done on already laid out tree, so layout cache stays direction agnostic.
+    fn mirror_node(node: &mut LayoutNode, unit: &WidgetUnit, direction: LayoutDirection) {
+        let direction = unit_layout_direction(unit).unwrap_or(direction);
+        if direction.is_right_to_left() {
+            let width = node.local_space.width();
+            for child in &mut node.children {
+                mirror_rect(&mut child.local_space, width);
+            }
+        }
+        let units = match unit.as_data() {
+            Some(data) => data.get_children(),
+            None => return,
+        };
+        let mut units = units.into_iter();
+        for child in &mut node.children {
+            let unit = units.find(|unit| {
+                unit.as_data()
+                    .map(|data| data.id() == &child.id)
+                    .unwrap_or_default()
+            });
+            if let Some(unit) = unit {
+                Self::mirror_node(child, unit, direction);
+            }
+        }
+    }
+
+    fn unpack_tree(
+        unit: &WidgetUnit,
+        layer: Integer,
+        direction: LayoutDirection,
+        items: &mut HashMap<WidgetId, LayoutItem>,
+    ) {
+        let direction = unit_layout_direction(unit).unwrap_or(direction);
+        if let Some(item) = unit.as_data().and_then(|data| items.get_mut(data.id())) {
+            item.layer = layer;
+            item.direction = direction;
+        }
+        match unit {
+            WidgetUnit::AreaBox(unit) => Self::unpack_tree(&unit.slot, layer, direction, items),
+            WidgetUnit::ContentBox(unit) => {
+                for item in &unit.items {
+                    let layer = layer + item.layout.z_index;
+                    Self::unpack_tree(&item.slot, layer, direction, items);
+                }
+            }
+            WidgetUnit::FlexBox(unit) => {
+                for item in &unit.items {
+                    let layer = layer + item.layout.z_index;
+                    Self::unpack_tree(&item.slot, layer, direction, items);
+                }
+            }
+            WidgetUnit::GridBox(unit) => {
+                for item in &unit.items {
+                    let layer = layer + item.layout.z_index;
+                    Self::unpack_tree(&item.slot, layer, direction, items);
+                }
+            }
+            WidgetUnit::MasonryBox(unit) => {
+                for item in &unit.items {
+                    let layer = layer + item.layout.z_index;
+                    Self::unpack_tree(&item.slot, layer, direction, items);
+                }
+            }
+            WidgetUnit::SizeBox(unit) => Self::unpack_tree(&unit.slot, layer, direction, items),
+            _ => {}
+        }
+    }
 }
 
-impl LayoutEngine<()> for DefaultLayoutEngine {
+impl<TME, IME> LayoutEngine<()> for DefaultLayoutEngine<TME, IME>
+where
+    TME: TextMeasurementEngine,
+    IME: ImageMeasurementEngine,
+{
     fn layout(&mut self, mapping: &CoordsMapping, tree: &WidgetUnit) -> Result<Layout, ()> {
         let ui_space = mapping.virtual_area();
-        if let Some(root) = Self::layout_node(ui_space.size(), tree) {
+        let safe_area = mapping.virtual_safe_area();
+        self.cache
+            .get_mut()
+            .begin(tree, &self.custom_unit_measurements);
+        let root = self.layout_node(ui_space.size(), tree);
+        self.cache.get_mut().end();
+        if let Some(mut root) = root {
+            if self.layout_direction.is_right_to_left() {
+                mirror_rect(&mut root.local_space, ui_space.width());
+            }
+            Self::mirror_node(&mut root, tree, self.layout_direction);
             let mut items = HashMap::with_capacity(root.count());
             Self::unpack_node(None, ui_space, root, &mut items);
-            Ok(Layout { ui_space, items })
+            Self::unpack_tree(tree, 0, self.layout_direction, &mut items);
+            Ok(Layout {
+                ui_space,
+                safe_area,
+                items,
+            })
         } else {
             Ok(Layout {
                 ui_space,
+                safe_area,
                 items: Default::default(),
             })
         }
@@ -0,0 +1,344 @@
+use serde::{
+    ser::{
+        Error, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Serialize, Serializer,
+};
+use std::{fmt, hash::Hasher};
+
+/// Feeds serialized value directly into hasher. Floats are hashed by their bits.
+pub(crate) fn hash_serialized<T, H>(value: &T, state: &mut H)
+where
+    T: Serialize + ?Sized,
+    H: Hasher,
+{
+    let _ = value.serialize(HashSerializer(state));
+}
+
+#[derive(Debug)]
+pub(crate) struct HashSerializerError;
+
+impl fmt::Display for HashSerializerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Could not hash serialized value")
+    }
+}
+
+impl std::error::Error for HashSerializerError {}
+
+impl Error for HashSerializerError {
+    fn custom<T: fmt::Display>(_: T) -> Self {
+        Self
+    }
+}
+
+struct HashSerializer<'a, H: Hasher>(&'a mut H);
+
+impl<'a, H: Hasher> HashSerializer<'a, H> {
+    fn tag(&mut self, tag: u8) {
+        self.0.write_u8(tag);
+    }
+
+    fn reborrow(&mut self) -> HashSerializer<'_, H> {
+        HashSerializer(self.0)
+    }
+}
+
+impl<'a, H: Hasher> Serializer for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(mut self, v: bool) -> Result<(), Self::Error> {
+        self.tag(0);
+        self.0.write_u8(v as u8);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(mut self, v: i64) -> Result<(), Self::Error> {
+        self.tag(1);
+        self.0.write_i64(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(mut self, v: u64) -> Result<(), Self::Error> {
+        self.tag(2);
+        self.0.write_u64(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(mut self, v: f64) -> Result<(), Self::Error> {
+        self.tag(3);
+        self.0.write_u64(v.to_bits());
+        Ok(())
+    }
+
+    fn serialize_char(mut self, v: char) -> Result<(), Self::Error> {
+        self.tag(4);
+        self.0.write_u32(v as u32);
+        Ok(())
+    }
+
+    fn serialize_str(mut self, v: &str) -> Result<(), Self::Error> {
+        self.tag(5);
+        self.0.write_usize(v.len());
+        self.0.write(v.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<(), Self::Error> {
+        self.tag(6);
+        self.0.write_usize(v.len());
+        self.0.write(v);
+        Ok(())
+    }
+
+    fn serialize_none(mut self) -> Result<(), Self::Error> {
+        self.tag(7);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(mut self, value: &T) -> Result<(), Self::Error> {
+        self.tag(8);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(mut self) -> Result<(), Self::Error> {
+        self.tag(9);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Self::Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        mut self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.tag(10);
+        self.0.write_u32(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        mut self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.tag(11);
+        self.0.write_u32(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(mut self, len: Option<usize>) -> Result<Self, Self::Error> {
+        self.tag(12);
+        // sequences of unknown length are terminated in `SerializeSeq::end` instead.
+        if let Some(len) = len {
+            self.0.write_usize(len);
+        }
+        Ok(self)
+    }
+
+    fn serialize_tuple(mut self, _: usize) -> Result<Self, Self::Error> {
+        self.tag(13);
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<Self, Self::Error> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, Self::Error> {
+        self.tag(14);
+        self.0.write_u32(variant_index);
+        Ok(self)
+    }
+
+    fn serialize_map(mut self, len: Option<usize>) -> Result<Self, Self::Error> {
+        self.tag(15);
+        if let Some(len) = len {
+            self.0.write_usize(len);
+        }
+        Ok(self)
+    }
+
+    fn serialize_struct(mut self, _: &'static str, _: usize) -> Result<Self, Self::Error> {
+        self.tag(16);
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, Self::Error> {
+        self.tag(17);
+        self.0.write_u32(variant_index);
+        Ok(self)
+    }
+}
+
+impl<'a, H: Hasher> SerializeSeq for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(mut self) -> Result<(), Self::Error> {
+        self.tag(255);
+        Ok(())
+    }
+}
+
+impl<'a, H: Hasher> SerializeTuple for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, H: Hasher> SerializeTupleStruct for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, H: Hasher> SerializeTupleVariant for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, H: Hasher> SerializeMap for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        key.serialize(self.reborrow())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(mut self) -> Result<(), Self::Error> {
+        self.tag(255);
+        Ok(())
+    }
+}
+
+impl<'a, H: Hasher> SerializeStruct for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        key.serialize(self.reborrow())?;
+        value.serialize(self.reborrow())
+    }
+
+    fn end(mut self) -> Result<(), Self::Error> {
+        self.tag(255);
+        Ok(())
+    }
+}
+
+impl<'a, H: Hasher> SerializeStructVariant for HashSerializer<'a, H> {
+    type Ok = ();
+    type Error = HashSerializerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        key.serialize(self.reborrow())?;
+        value.serialize(self.reborrow())
+    }
+
+    fn end(mut self) -> Result<(), Self::Error> {
+        self.tag(255);
+        Ok(())
+    }
+}
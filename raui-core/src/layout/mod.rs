@@ -1,22 +1,116 @@
 //! Layout engine
 
 pub mod default_layout_engine;
+mod hash_serializer;
 
 use crate::{
     widget::{
-        unit::WidgetUnit,
-        utils::{Rect, Vec2},
+        unit::{
+            custom::CustomBox,
+            text::{TextBox, TextBoxFont},
+            WidgetUnit,
+        },
+        utils::{LayoutDirection, Rect, Vec2},
         WidgetId,
     },
-    Scalar,
+    Integer, Scalar,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeSet, HashMap},
+    hash::Hasher,
+    ops::Range,
+};
 
 pub trait LayoutEngine<E> {
     fn layout(&mut self, mapping: &CoordsMapping, tree: &WidgetUnit) -> Result<Layout, E>;
 }
 
+/// Font metrics provider used by layout engines to size text boxes to their content
+pub trait TextMeasurementEngine {
+    /// Returns size of text box content with lines wrapped to fit `max_width`, or `None` when
+    /// text cannot be measured (unknown font for example).
+    fn measure_text(&self, max_width: Scalar, unit: &TextBox) -> Option<Vec2>;
+
+    /// Returns vertical metrics of font used to align text baselines, or `None` when font is
+    /// unknown.
+    ///
+    /// Renderers shipped with RAUI do not provide text measurement engines, so unless application
+    /// uses its own engine, baselines are aligned by [`FontMetrics::approximated`].
+    fn font_metrics(&self, _font: &TextBoxFont) -> Option<FontMetrics> {
+        None
+    }
+}
+
+impl TextMeasurementEngine for () {
+    fn measure_text(&self, _: Scalar, _: &TextBox) -> Option<Vec2> {
+        None
+    }
+}
+
+/// Vertical metrics of single line of text
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FontMetrics {
+    /// Distance from top of line to baseline
+    #[serde(default)]
+    pub ascent: Scalar,
+    /// Distance from baseline to bottom of line
+    #[serde(default)]
+    pub descent: Scalar,
+}
+
+impl FontMetrics {
+    /// Metrics approximated from font size, used for fonts that cannot be measured.
+    pub fn approximated(size: Scalar) -> Self {
+        Self {
+            ascent: size * 0.8,
+            descent: size * 0.2,
+        }
+    }
+
+    pub fn line_height(&self) -> Scalar {
+        self.ascent + self.descent
+    }
+}
+
+/// Image size provider used by layout engines to size image boxes to their content
+pub trait ImageMeasurementEngine {
+    /// Returns native size of image with given id, or `None` when image size is unknown.
+    fn measure_image(&self, id: &str) -> Option<Vec2>;
+}
+
+impl ImageMeasurementEngine for () {
+    fn measure_image(&self, _: &str) -> Option<Vec2> {
+        None
+    }
+}
+
+impl ImageMeasurementEngine for HashMap<String, Vec2> {
+    fn measure_image(&self, id: &str) -> Option<Vec2> {
+        self.get(id).copied()
+    }
+}
+
+impl<T> ImageMeasurementEngine for &T
+where
+    T: ImageMeasurementEngine,
+{
+    fn measure_image(&self, id: &str) -> Option<Vec2> {
+        T::measure_image(self, id)
+    }
+}
+
+/// Size provider of custom units, registered in layout engines under custom unit type name
+pub trait CustomUnitMeasurementEngine {
+    /// Returns size of custom unit content fitting in `size_available`, or `None` when unit
+    /// fills available space.
+    fn measure_custom_unit(&self, size_available: Vec2, unit: &CustomBox) -> Option<Vec2>;
+
+    /// Hashes content of custom unit (usually its props) that affects its measured size. Layout
+    /// of units with unchanged hash is reused between layout passes.
+    fn hash_custom_unit(&self, unit: &CustomBox, state: &mut dyn Hasher);
+}
+
 struct LayoutSortedItems<'a>(Vec<(&'a WidgetId, &'a LayoutItem)>);
 
 impl<'a> LayoutSortedItems<'a> {
@@ -38,6 +132,9 @@ impl<'a> std::fmt::Debug for LayoutSortedItems<'a> {
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Layout {
     pub ui_space: Rect,
+    /// Part of UI space not covered by display notches or overscan
+    #[serde(default)]
+    pub safe_area: Rect,
     pub items: HashMap<WidgetId, LayoutItem>,
 }
 
@@ -45,6 +142,7 @@ impl std::fmt::Debug for Layout {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Layout")
             .field("ui_space", &self.ui_space)
+            .field("safe_area", &self.safe_area)
             .field("items", &LayoutSortedItems::new(&self.items))
             .finish()
     }
@@ -75,17 +173,48 @@ impl Layout {
                 local_space: self.ui_space,
                 ui_space: self.ui_space,
                 parent: None,
+                layer: 0,
+                direction: Default::default(),
+                fragments: vec![],
             },
         }
     }
 
+    /// Converts layout into real coords, rounding rects to physical pixel grid when pixel
+    /// snapping is enabled.
+    ///
+    /// Snapped local space of item is its snapped ui space relative to snapped ui space of its
+    /// parent, so nested items stay aligned with the pixel grid.
     pub fn virtual_to_real(&self, mapping: &CoordsMapping) -> Self {
+        let ui_space = mapping.snap_rect(mapping.virtual_to_real_rect(self.ui_space, false));
+        let snapped_ui_space = |item: &LayoutItem| {
+            mapping.snap_rect(mapping.virtual_to_real_rect(item.ui_space, false))
+        };
         Self {
-            ui_space: mapping.virtual_to_real_rect(self.ui_space, false),
+            ui_space,
+            safe_area: mapping.snap_rect(mapping.virtual_to_real_rect(self.safe_area, false)),
             items: self
                 .items
                 .iter()
-                .map(|(k, v)| (k.to_owned(), v.virtual_to_real(mapping)))
+                .map(|(k, v)| {
+                    let mut item = v.virtual_to_real(mapping);
+                    if mapping.pixel_snapping() {
+                        let parent_space = v
+                            .parent
+                            .as_ref()
+                            .and_then(|parent| self.items.get(parent))
+                            .map(snapped_ui_space)
+                            .unwrap_or(ui_space);
+                        item.ui_space = snapped_ui_space(v);
+                        item.local_space = Rect {
+                            left: item.ui_space.left - parent_space.left,
+                            right: item.ui_space.right - parent_space.left,
+                            top: item.ui_space.top - parent_space.top,
+                            bottom: item.ui_space.bottom - parent_space.top,
+                        };
+                    }
+                    (k.to_owned(), item)
+                })
                 .collect::<HashMap<_, _>>(),
         }
     }
@@ -93,6 +222,7 @@ impl Layout {
     pub fn real_to_virtual(&self, mapping: &CoordsMapping) -> Self {
         Self {
             ui_space: mapping.real_to_virtual_rect(self.ui_space, false),
+            safe_area: mapping.real_to_virtual_rect(self.safe_area, false),
             items: self
                 .items
                 .iter()
@@ -101,6 +231,169 @@ impl Layout {
         }
     }
 
+    /// Sorted list of render layers used by layout items, always containing default layer.
+    ///
+    /// Renderers render items layer by layer, keeping tree order of items within the same layer.
+    pub fn layers(&self) -> Vec<Integer> {
+        self.items
+            .values()
+            .map(|item| item.layer)
+            .chain(std::iter::once(0))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Same as [`Layout::rect_relative_to`] but mirrored horizontally within `to` item when it
+    /// is laid out right-to-left, so rectangle left side is the one closer to the start edge.
+    pub fn logical_rect_relative_to(&self, id: &WidgetId, to: &WidgetId) -> Option<Rect> {
+        let rect = self.rect_relative_to(id, to)?;
+        let item = self.items.get(to)?;
+        Some(Self::logical_rect(item, rect))
+    }
+
+    /// Safe area rect relative to widget, clamped to its bounds and mirrored when it is
+    /// right-to-left.
+    pub fn safe_area_relative_to(&self, to: &WidgetId) -> Option<Rect> {
+        let item = self.items.get(to)?;
+        let width = item.ui_space.width();
+        let height = item.ui_space.height();
+        let left = (self.safe_area.left - item.ui_space.left)
+            .max(0.0)
+            .min(width);
+        let top = (self.safe_area.top - item.ui_space.top)
+            .max(0.0)
+            .min(height);
+        let rect = Rect {
+            left,
+            right: (self.safe_area.right - item.ui_space.left)
+                .min(width)
+                .max(left),
+            top,
+            bottom: (self.safe_area.bottom - item.ui_space.top)
+                .min(height)
+                .max(top),
+        };
+        Some(Self::logical_rect(item, rect))
+    }
+
+    fn logical_rect(item: &LayoutItem, rect: Rect) -> Rect {
+        if item.direction.is_right_to_left() {
+            let width = item.ui_space.width();
+            Rect {
+                left: width - rect.right,
+                right: width - rect.left,
+                ..rect
+            }
+        } else {
+            rect
+        }
+    }
+
+    /// Returns stack of widgets under given point (in layout space), topmost first.
+    ///
+    /// Point gets mapped through transforms of widget units on its way down the tree, and content
+    /// of clipping content boxes can be hit only within their bounds. Widgets on higher layers come
+    /// first, widgets on the same layer come in reversed render order.
+    pub fn hit_test(&self, tree: &WidgetUnit, point: Vec2) -> Vec<LayoutHit> {
+        let mut result = vec![];
+        self.hit_test_inner(tree, point, &mut result);
+        result.reverse();
+        result.sort_by(|(a, _), (b, _)| b.cmp(a));
+        result.into_iter().map(|(_, hit)| hit).collect()
+    }
+
+    fn hit_test_inner(
+        &self,
+        unit: &WidgetUnit,
+        point: Vec2,
+        result: &mut Vec<(Integer, LayoutHit)>,
+    ) {
+        let (id, item) = match unit
+            .as_data()
+            .and_then(|data| Some((data.id(), self.items.get(data.id())?)))
+        {
+            Some(pair) => pair,
+            None => return,
+        };
+        let size = item.local_space.size();
+        let point = Vec2 {
+            x: point.x - item.local_space.left,
+            y: point.y - item.local_space.top,
+        };
+        let transform = match unit {
+            WidgetUnit::ContentBox(unit) => Some(&unit.transform),
+            WidgetUnit::FlexBox(unit) => Some(&unit.transform),
+            WidgetUnit::GridBox(unit) => Some(&unit.transform),
+            WidgetUnit::MasonryBox(unit) => Some(&unit.transform),
+            WidgetUnit::SizeBox(unit) => Some(&unit.transform),
+            WidgetUnit::ImageBox(unit) => Some(&unit.transform),
+            WidgetUnit::TextBox(unit) => Some(&unit.transform),
+            WidgetUnit::RichTextBox(unit) => Some(&unit.transform),
+            WidgetUnit::CustomBox(unit) => Some(&unit.transform),
+            _ => None,
+        };
+        let point = match transform {
+            Some(transform) => match transform.unproject_point(point, size) {
+                Some(point) => point,
+                None => return,
+            },
+            None => point,
+        };
+        let inside = point.x >= 0.0 && point.y >= 0.0 && point.x <= size.x && point.y <= size.y;
+        if inside {
+            result.push((
+                item.layer,
+                LayoutHit {
+                    id: id.to_owned(),
+                    local_point: point,
+                },
+            ));
+        }
+        match unit {
+            WidgetUnit::AreaBox(unit) => self.hit_test_inner(&unit.slot, point, result),
+            WidgetUnit::ContentBox(unit) => {
+                if unit.clipping && !inside {
+                    return;
+                }
+                let mut items = unit.items.iter().collect::<Vec<_>>();
+                items.sort_by(|a, b| a.layout.depth.partial_cmp(&b.layout.depth).unwrap());
+                for item in items {
+                    self.hit_test_inner(&item.slot, point, result);
+                }
+            }
+            WidgetUnit::FlexBox(unit) => {
+                for item in &unit.items {
+                    self.hit_test_inner(&item.slot, point, result);
+                }
+            }
+            WidgetUnit::GridBox(unit) => {
+                for item in &unit.items {
+                    self.hit_test_inner(&item.slot, point, result);
+                }
+            }
+            WidgetUnit::MasonryBox(unit) => {
+                for item in &unit.items {
+                    self.hit_test_inner(&item.slot, point, result);
+                }
+            }
+            WidgetUnit::SizeBox(unit) => self.hit_test_inner(&unit.slot, point, result),
+            _ => {}
+        }
+    }
+
+    /// Returns link of rich text box span under point (in layout space), together with id of
+    /// that rich text box. Only topmost widget under point is taken into account.
+    pub fn rich_text_link_at(&self, tree: &WidgetUnit, point: Vec2) -> Option<(WidgetId, String)> {
+        let hit = self.hit_test(tree, point).into_iter().next()?;
+        if let WidgetUnit::RichTextBox(unit) = tree.find(&hit.id)? {
+            let link = unit.link_at(self.items.get(&hit.id)?, hit.local_point)?;
+            Some((hit.id, link.to_owned()))
+        } else {
+            None
+        }
+    }
+
     pub fn rect_relative_to(&self, id: &WidgetId, to: &WidgetId) -> Option<Rect> {
         let a = self.items.get(id)?;
         let b = self.items.get(to)?;
@@ -115,11 +408,48 @@ impl Layout {
     }
 }
 
+/// Widget found under point with [`Layout::hit_test`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutHit {
+    pub id: WidgetId,
+    /// Hit point in local space of the widget (relative to its top-left corner)
+    pub local_point: Vec2,
+}
+
+/// Laid out part of text: line of text box (see [`crate::widget::unit::text::TextBox`]) or part
+/// of rich text box span (see [`crate::widget::unit::rich_text::RichTextBox`])
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutTextFragment {
+    /// Index of span in rich text box, zero for text box lines
+    #[serde(default)]
+    pub span: usize,
+    /// Byte range of source text shown by this fragment (including text replaced by ellipsis),
+    /// empty for image spans
+    #[serde(default)]
+    pub range: Range<usize>,
+    /// Text shown by this fragment
+    #[serde(default)]
+    pub text: String,
+    /// Font size of fragment text, smaller than text box font size when text got shrunk to fit
+    #[serde(default)]
+    pub font_size: Scalar,
+    /// Fragment rectangle in local space of text box
+    #[serde(default)]
+    pub rect: Rect,
+    /// Distance from top of fragment rectangle to text baseline
+    #[serde(default)]
+    pub baseline: Scalar,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct LayoutNode {
     pub id: WidgetId,
     pub local_space: Rect,
     pub children: Vec<LayoutNode>,
+    /// Text fragments of text box or rich text box
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<LayoutTextFragment>,
 }
 
 impl LayoutNode {
@@ -133,6 +463,16 @@ pub struct LayoutItem {
     pub local_space: Rect,
     pub ui_space: Rect,
     pub parent: Option<WidgetId>,
+    /// Render layer of the item, accumulated from `z_index` of item layouts up the tree
+    #[serde(default)]
+    pub layer: Integer,
+    /// Layout direction of the item content
+    #[serde(default)]
+    pub direction: LayoutDirection,
+    /// Text fragments of text box or rich text box
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<LayoutTextFragment>,
 }
 
 impl LayoutItem {
@@ -141,6 +481,17 @@ impl LayoutItem {
             local_space: mapping.virtual_to_real_rect(self.local_space, true),
             ui_space: mapping.virtual_to_real_rect(self.ui_space, false),
             parent: self.parent.to_owned(),
+            layer: self.layer,
+            direction: self.direction,
+            fragments: self
+                .fragments
+                .iter()
+                .map(|fragment| LayoutTextFragment {
+                    rect: mapping.virtual_to_real_rect(fragment.rect, true),
+                    baseline: fragment.baseline * mapping.scale().y,
+                    ..fragment.to_owned()
+                })
+                .collect(),
         }
     }
 
@@ -149,6 +500,17 @@ impl LayoutItem {
             local_space: mapping.real_to_virtual_rect(self.local_space, true),
             ui_space: mapping.real_to_virtual_rect(self.ui_space, false),
             parent: self.parent.to_owned(),
+            layer: self.layer,
+            direction: self.direction,
+            fragments: self
+                .fragments
+                .iter()
+                .map(|fragment| LayoutTextFragment {
+                    rect: mapping.real_to_virtual_rect(fragment.rect, true),
+                    baseline: fragment.baseline / mapping.scale().y,
+                    ..fragment.to_owned()
+                })
+                .collect(),
         }
     }
 }
@@ -157,6 +519,7 @@ impl LayoutEngine<()> for () {
     fn layout(&mut self, mapping: &CoordsMapping, _: &WidgetUnit) -> Result<Layout, ()> {
         Ok(Layout {
             ui_space: mapping.virtual_area(),
+            safe_area: mapping.virtual_safe_area(),
             items: Default::default(),
         })
     }
@@ -185,10 +548,20 @@ pub struct CoordsMapping {
     scale: Vec2,
     #[serde(default)]
     offset: Vec2,
+    /// Real area in logical coords (before scale factor)
     #[serde(default)]
     real_area: Rect,
     #[serde(default)]
     virtual_area: Rect,
+    /// Device scale factor (DPI) mapping real logical coords into physical pixels
+    #[serde(default = "CoordsMapping::default_scale_factor")]
+    scale_factor: Scalar,
+    /// Rounds layout rects to physical pixel grid in `Layout::virtual_to_real`
+    #[serde(default)]
+    pixel_snapping: bool,
+    /// Safe area insets in real coords (before scale factor), covered by notches or overscan
+    #[serde(default)]
+    safe_area_insets: Rect,
 }
 
 impl Default for CoordsMapping {
@@ -198,6 +571,10 @@ impl Default for CoordsMapping {
 }
 
 impl CoordsMapping {
+    fn default_scale_factor() -> Scalar {
+        1.0
+    }
+
     pub fn new(real_area: Rect) -> Self {
         Self {
             scale: 1.0.into(),
@@ -209,6 +586,9 @@ impl CoordsMapping {
                 top: 0.0,
                 bottom: real_area.height(),
             },
+            scale_factor: 1.0,
+            pixel_snapping: false,
+            safe_area_insets: Default::default(),
         }
     }
 
@@ -224,6 +604,9 @@ impl CoordsMapping {
                     top: 0.0,
                     bottom: real_area.height(),
                 },
+                scale_factor: 1.0,
+                pixel_snapping: false,
+                safe_area_insets: Default::default(),
             },
             CoordsMappingScaling::Stretch(size) => {
                 let vw = size.x;
@@ -250,6 +633,9 @@ impl CoordsMapping {
                         top: 0.0,
                         bottom: vh,
                     },
+                    scale_factor: 1.0,
+                    pixel_snapping: false,
+                    safe_area_insets: Default::default(),
                 }
             }
             CoordsMappingScaling::FitHorizontal(vw) => {
@@ -267,6 +653,9 @@ impl CoordsMapping {
                         top: 0.0,
                         bottom: vh,
                     },
+                    scale_factor: 1.0,
+                    pixel_snapping: false,
+                    safe_area_insets: Default::default(),
                 }
             }
             CoordsMappingScaling::FitVertical(vh) => {
@@ -284,6 +673,9 @@ impl CoordsMapping {
                         top: 0.0,
                         bottom: vh,
                     },
+                    scale_factor: 1.0,
+                    pixel_snapping: false,
+                    safe_area_insets: Default::default(),
                 }
             }
             CoordsMappingScaling::FitMinimum(size) => {
@@ -330,19 +722,74 @@ impl CoordsMapping {
                         top: 0.0,
                         bottom: vh,
                     },
+                    scale_factor: 1.0,
+                    pixel_snapping: false,
+                    safe_area_insets: Default::default(),
                 }
             }
         }
     }
 
+    /// Sets device scale factor (DPI) applied on top of virtual to real scaling.
+    pub fn with_scale_factor(mut self, scale_factor: Scalar) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Enables rounding of layout rects to physical pixel grid.
+    pub fn with_pixel_snapping(mut self, pixel_snapping: bool) -> Self {
+        self.pixel_snapping = pixel_snapping;
+        self
+    }
+
+    /// Sets safe area insets (in real coords, before scale factor) excluded from safe area.
+    pub fn with_safe_area_insets(mut self, insets: Rect) -> Self {
+        self.safe_area_insets = insets;
+        self
+    }
+
+    /// Total scale of virtual coords into physical pixels (virtual scaling times scale factor).
     #[inline]
     pub fn scale(&self) -> Vec2 {
-        self.scale
+        Vec2 {
+            x: self.scale.x * self.scale_factor,
+            y: self.scale.y * self.scale_factor,
+        }
     }
 
+    /// Offset of virtual area in physical pixels.
     #[inline]
     pub fn offset(&self) -> Vec2 {
-        self.offset
+        Vec2 {
+            x: self.offset.x * self.scale_factor,
+            y: self.offset.y * self.scale_factor,
+        }
+    }
+
+    #[inline]
+    pub fn scale_factor(&self) -> Scalar {
+        self.scale_factor
+    }
+
+    #[inline]
+    pub fn pixel_snapping(&self) -> bool {
+        self.pixel_snapping
+    }
+
+    #[inline]
+    pub fn safe_area_insets(&self) -> Rect {
+        self.safe_area_insets
+    }
+
+    /// Real area in physical pixels, same space as `scale` and `offset` map into.
+    #[inline]
+    pub fn real_area(&self) -> Rect {
+        Rect {
+            left: self.real_area.left * self.scale_factor,
+            right: self.real_area.right * self.scale_factor,
+            top: self.real_area.top * self.scale_factor,
+            bottom: self.real_area.bottom * self.scale_factor,
+        }
     }
 
     #[inline]
@@ -350,71 +797,114 @@ impl CoordsMapping {
         self.virtual_area
     }
 
+    /// Part of virtual area not covered by safe area insets.
+    ///
+    /// Letterboxing offset of virtual area already counts into insets.
+    pub fn virtual_safe_area(&self) -> Rect {
+        let left = (self.safe_area_insets.left - self.offset.x).max(0.0) / self.scale.x;
+        let right = (self.safe_area_insets.right - self.offset.x).max(0.0) / self.scale.x;
+        let top = (self.safe_area_insets.top - self.offset.y).max(0.0) / self.scale.y;
+        let bottom = (self.safe_area_insets.bottom - self.offset.y).max(0.0) / self.scale.y;
+        let left = self.virtual_area.left + left;
+        let top = self.virtual_area.top + top;
+        Rect {
+            left,
+            right: (self.virtual_area.right - right).max(left),
+            top,
+            bottom: (self.virtual_area.bottom - bottom).max(top),
+        }
+    }
+
     #[inline]
     pub fn virtual_to_real_vec2(&self, coord: Vec2, local_space: bool) -> Vec2 {
+        let scale = self.scale();
         if local_space {
             Vec2 {
-                x: coord.x * self.scale.x,
-                y: coord.y * self.scale.y,
+                x: coord.x * scale.x,
+                y: coord.y * scale.y,
             }
         } else {
+            let offset = self.offset();
             Vec2 {
-                x: self.offset.x + (coord.x * self.scale.x),
-                y: self.offset.y + (coord.y * self.scale.y),
+                x: offset.x + (coord.x * scale.x),
+                y: offset.y + (coord.y * scale.y),
             }
         }
     }
 
     #[inline]
     pub fn real_to_virtual_vec2(&self, coord: Vec2, local_space: bool) -> Vec2 {
+        let scale = self.scale();
         if local_space {
             Vec2 {
-                x: coord.x / self.scale.x,
-                y: coord.y / self.scale.y,
+                x: coord.x / scale.x,
+                y: coord.y / scale.y,
             }
         } else {
+            let offset = self.offset();
             Vec2 {
-                x: (coord.x - self.offset.x) / self.scale.x,
-                y: (coord.y - self.offset.y) / self.scale.y,
+                x: (coord.x - offset.x) / scale.x,
+                y: (coord.y - offset.y) / scale.y,
             }
         }
     }
 
     #[inline]
     pub fn virtual_to_real_rect(&self, area: Rect, local_space: bool) -> Rect {
+        let scale = self.scale();
         if local_space {
             Rect {
-                left: area.left * self.scale.x,
-                right: area.right * self.scale.x,
-                top: area.top * self.scale.y,
-                bottom: area.bottom * self.scale.y,
+                left: area.left * scale.x,
+                right: area.right * scale.x,
+                top: area.top * scale.y,
+                bottom: area.bottom * scale.y,
             }
         } else {
+            let offset = self.offset();
             Rect {
-                left: self.offset.x + (area.left * self.scale.x),
-                right: self.offset.x + (area.right * self.scale.x),
-                top: self.offset.y + (area.top * self.scale.y),
-                bottom: self.offset.y + (area.bottom * self.scale.y),
+                left: offset.x + (area.left * scale.x),
+                right: offset.x + (area.right * scale.x),
+                top: offset.y + (area.top * scale.y),
+                bottom: offset.y + (area.bottom * scale.y),
             }
         }
     }
 
     #[inline]
     pub fn real_to_virtual_rect(&self, area: Rect, local_space: bool) -> Rect {
+        let scale = self.scale();
         if local_space {
             Rect {
-                left: area.left / self.scale.x,
-                right: area.right / self.scale.x,
-                top: area.top / self.scale.y,
-                bottom: area.bottom / self.scale.y,
+                left: area.left / scale.x,
+                right: area.right / scale.x,
+                top: area.top / scale.y,
+                bottom: area.bottom / scale.y,
             }
         } else {
+            let offset = self.offset();
             Rect {
-                left: (area.left - self.offset.x) / self.scale.x,
-                right: (area.right - self.offset.x) / self.scale.x,
-                top: (area.top - self.offset.y) / self.scale.y,
-                bottom: (area.bottom - self.offset.y) / self.scale.y,
+                left: (area.left - offset.x) / scale.x,
+                right: (area.right - offset.x) / scale.x,
+                top: (area.top - offset.y) / scale.y,
+                bottom: (area.bottom - offset.y) / scale.y,
             }
         }
     }
+
+    /// Rounds edges of real rect to physical pixel grid when pixel snapping is enabled.
+    ///
+    /// Edges are rounded independently so adjacent rects keep sharing their edges.
+    #[inline]
+    pub fn snap_rect(&self, area: Rect) -> Rect {
+        if self.pixel_snapping {
+            Rect {
+                left: area.left.round(),
+                right: area.right.round(),
+                top: area.top.round(),
+                bottom: area.bottom.round(),
+            }
+        } else {
+            area
+        }
+    }
 }
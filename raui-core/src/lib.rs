@@ -136,20 +136,26 @@ pub mod prelude {
             component::{
                 containers::{
                     anchor_box::*, content_box::*, context_box::*, flex_box::*, grid_box::*,
-                    hidden_box::*, horizontal_box::*, portal_box::*, scroll_box::*, size_box::*,
-                    switch_box::*, tabs_box::*, tooltip_box::*, variant_box::*, vertical_box::*,
-                    wrap_box::*,
+                    hidden_box::*, horizontal_box::*, layout_transition_box::*, masonry_box::*,
+                    portal_box::*, safe_area_box::*, scroll_box::*, size_box::*, switch_box::*,
+                    tabs_box::*, tooltip_box::*, variant_box::*, vertical_box::*,
+                    virtual_list_box::*, wrap_box::*,
                 },
+                custom_box::*,
                 image_box::*,
                 interactive::*,
                 interactive::{button::*, input_field::*, navigation::*, scroll_view::*},
+                rich_text_box::*,
                 space_box::*,
                 text_box::*,
             },
             context::*,
             node::*,
             unit::*,
-            unit::{area::*, content::*, flex::*, grid::*, image::*, size::*, text::*},
+            unit::{
+                area::*, content::*, custom::*, flex::*, grid::*, image::*, masonry::*,
+                rich_text::*, size::*, text::*,
+            },
             utils::*,
         },
         Integer, LogKind, Logger, MessageData, Prefab, PrefabError, PrintLogger, PropsData, Scalar,
@@ -21,6 +21,9 @@ pub struct AnchorProps {
     pub outer_box_size: Vec2,
     #[serde(default)]
     pub inner_box_rect: Rect,
+    /// Part of outer box not covered by display notches or overscan
+    #[serde(default)]
+    pub safe_area_rect: Rect,
 }
 
 #[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
@@ -105,12 +108,17 @@ pub fn use_anchor_box(context: &mut WidgetContext) {
         let prev = data;
         let mut dirty = false;
         for msg in context.messenger.messages {
-            if let Some(RelativeLayoutListenerSignal::Change(size, rect)) =
-                msg.as_any().downcast_ref()
-            {
-                data.outer_box_size = *size;
-                data.inner_box_rect = *rect;
-                dirty = true;
+            match msg.as_any().downcast_ref() {
+                Some(RelativeLayoutListenerSignal::Change(size, rect)) => {
+                    data.outer_box_size = *size;
+                    data.inner_box_rect = *rect;
+                    dirty = true;
+                }
+                Some(RelativeLayoutListenerSignal::SafeAreaChange(rect)) => {
+                    data.safe_area_rect = *rect;
+                    dirty = true;
+                }
+                _ => {}
             }
         }
         if dirty {
@@ -174,6 +182,73 @@ pub fn pivot_point_to_anchor(pivot: Vec2, anchor: &AnchorProps) -> Vec2 {
     Vec2 { x, y }
 }
 
+/// Maps point normalized within safe area into point normalized within outer box.
+pub fn safe_area_point_to_anchor(point: Vec2, anchor: &AnchorProps) -> Vec2 {
+    let x = if anchor.outer_box_size.x > 0.0 {
+        let v = lerp(
+            anchor.safe_area_rect.left,
+            anchor.safe_area_rect.right,
+            point.x,
+        );
+        v / anchor.outer_box_size.x
+    } else {
+        point.x
+    };
+    let y = if anchor.outer_box_size.y > 0.0 {
+        let v = lerp(
+            anchor.safe_area_rect.top,
+            anchor.safe_area_rect.bottom,
+            point.y,
+        );
+        v / anchor.outer_box_size.y
+    } else {
+        point.y
+    };
+    Vec2 { x, y }
+}
+
+/// Maps content box item anchors relative to safe area into anchors relative to outer box.
+pub fn safe_area_anchors(anchors: Rect, anchor: &AnchorProps) -> Rect {
+    let Vec2 { x: left, y: top } = safe_area_point_to_anchor(
+        Vec2 {
+            x: anchors.left,
+            y: anchors.top,
+        },
+        anchor,
+    );
+    let Vec2 {
+        x: right,
+        y: bottom,
+    } = safe_area_point_to_anchor(
+        Vec2 {
+            x: anchors.right,
+            y: anchors.bottom,
+        },
+        anchor,
+    );
+    Rect {
+        left,
+        right,
+        top,
+        bottom,
+    }
+}
+
+/// Margin that keeps content of inner box within safe area.
+pub fn safe_area_margin(anchor: &AnchorProps) -> Rect {
+    if anchor.outer_box_size.x <= 0.0 || anchor.outer_box_size.y <= 0.0 {
+        return Default::default();
+    }
+    let inner = anchor.inner_box_rect;
+    let safe = anchor.safe_area_rect;
+    Rect {
+        left: (safe.left - inner.left).max(0.0),
+        right: (inner.right - safe.right).max(0.0),
+        top: (safe.top - inner.top).max(0.0),
+        bottom: (inner.bottom - safe.bottom).max(0.0),
+    }
+}
+
 /// (anchor point, align factor)
 pub fn pivot_to_anchor_and_align(pivot: &PivotBoxProps, anchor: &AnchorProps) -> (Vec2, Vec2) {
     (pivot_point_to_anchor(pivot.pivot, anchor), pivot.align)
@@ -9,8 +9,10 @@ use crate::{
         },
         context::WidgetContext,
         node::WidgetNode,
-        unit::content::{ContentBoxItemLayout, ContentBoxItemNode, ContentBoxNode},
-        utils::Transform,
+        unit::content::{
+            ContentBoxItemLayout, ContentBoxItemNode, ContentBoxNode, LayoutConstraint,
+        },
+        utils::{LayoutDirection, Transform},
     },
     PropsData,
 };
@@ -24,9 +26,18 @@ pub struct ContentBoxProps {
     /// Whether or not to clip the parts of items that overflow outside of the box bounds
     #[serde(default)]
     pub clipping: bool,
+    /// Layout direction of the box content, inherited from parent when not set
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
     /// The transform to apply to the box and it's contents
     #[serde(default)]
     pub transform: Transform,
+    /// Linear constraints between items (referenced by their keys), used to lay them out instead
+    /// of their anchors when not empty
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<LayoutConstraint>,
 }
 
 #[pre_hooks(use_nav_container_active, use_nav_jump_direction_active, use_nav_item)]
@@ -64,6 +75,12 @@ pub fn nav_content_box(mut context: WidgetContext) -> WidgetNode {
 ///
 /// With a [`content_box`] you can get more control over the layout of it's children by adding the
 /// [`ContentBoxItemLayout`] properties to any of it's children.
+///
+/// # Constraints
+///
+/// Setting [`ContentBoxProps::constraints`] makes the box solve linear constraints between its
+/// children (such as "a.right == b.left - 8") to find their rects, treating their anchors and
+/// margins as weak suggestions.
 pub fn content_box(context: WidgetContext) -> WidgetNode {
     let WidgetContext {
         id,
@@ -74,7 +91,9 @@ pub fn content_box(context: WidgetContext) -> WidgetNode {
 
     let ContentBoxProps {
         clipping,
+        layout_direction,
         transform,
+        constraints,
     } = props.read_cloned_or_default();
 
     let items = listed_slots
@@ -95,7 +114,9 @@ pub fn content_box(context: WidgetContext) -> WidgetNode {
             props: props.clone(),
             items,
             clipping,
+            layout_direction,
             transform,
+            constraints,
         }
     }}}
 }
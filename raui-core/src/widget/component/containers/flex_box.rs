@@ -7,8 +7,11 @@ use crate::{
         },
         context::WidgetContext,
         node::WidgetNode,
-        unit::flex::{FlexBoxDirection, FlexBoxItemLayout, FlexBoxItemNode, FlexBoxNode},
-        utils::Transform,
+        unit::flex::{
+            FlexBoxAlignContent, FlexBoxDirection, FlexBoxItemLayout, FlexBoxItemNode,
+            FlexBoxJustifyContent, FlexBoxNode,
+        },
+        utils::{LayoutDirection, Transform},
     },
     PropsData, Scalar,
 };
@@ -25,6 +28,18 @@ pub struct FlexBoxProps {
     #[serde(default)]
     pub wrap: bool,
     #[serde(default)]
+    pub wrap_reverse: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cross_separation: Option<Scalar>,
+    #[serde(default)]
+    pub justify_content: FlexBoxJustifyContent,
+    #[serde(default)]
+    pub align_content: FlexBoxAlignContent,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
     pub transform: Transform,
 }
 
@@ -60,6 +75,11 @@ pub fn flex_box(context: WidgetContext) -> WidgetNode {
         direction,
         separation,
         wrap,
+        wrap_reverse,
+        cross_separation,
+        justify_content,
+        align_content,
+        layout_direction,
         transform,
     } = props.read_cloned_or_default();
 
@@ -83,6 +103,11 @@ pub fn flex_box(context: WidgetContext) -> WidgetNode {
             direction,
             separation,
             wrap,
+            wrap_reverse,
+            cross_separation,
+            justify_content,
+            align_content,
+            layout_direction,
             transform,
         }
     }}}
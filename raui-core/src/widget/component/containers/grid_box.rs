@@ -7,10 +7,10 @@ use crate::{
         },
         context::WidgetContext,
         node::WidgetNode,
-        unit::grid::{GridBoxItemLayout, GridBoxItemNode, GridBoxNode},
-        utils::Transform,
+        unit::grid::{GridBoxItemLayout, GridBoxItemNode, GridBoxNode, GridBoxTrackSize},
+        utils::{LayoutDirection, Transform},
     },
-    PropsData,
+    PropsData, Scalar,
 };
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +23,19 @@ pub struct GridBoxProps {
     #[serde(default)]
     pub rows: usize,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub column_tracks: Vec<GridBoxTrackSize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub row_tracks: Vec<GridBoxTrackSize>,
+    #[serde(default)]
+    pub column_gap: Scalar,
+    #[serde(default)]
+    pub row_gap: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
     pub transform: Transform,
 }
 
@@ -57,6 +70,11 @@ pub fn grid_box(context: WidgetContext) -> WidgetNode {
     let GridBoxProps {
         cols,
         rows,
+        column_tracks,
+        row_tracks,
+        column_gap,
+        row_gap,
+        layout_direction,
         transform,
     } = props.read_cloned_or_default();
 
@@ -79,6 +97,11 @@ pub fn grid_box(context: WidgetContext) -> WidgetNode {
             items,
             cols,
             rows,
+            column_tracks,
+            row_tracks,
+            column_gap,
+            row_gap,
+            layout_direction,
             transform,
         }
     }}}
@@ -10,7 +10,7 @@ use crate::{
         },
         context::WidgetContext,
         node::WidgetNode,
-        unit::flex::FlexBoxDirection,
+        unit::flex::{FlexBoxDirection, FlexBoxJustifyContent},
         utils::Transform,
     },
     PropsData, Scalar,
@@ -26,6 +26,8 @@ pub struct HorizontalBoxProps {
     #[serde(default)]
     pub reversed: bool,
     #[serde(default)]
+    pub justify_content: FlexBoxJustifyContent,
+    #[serde(default)]
     pub transform: Transform,
 }
 
@@ -64,6 +66,7 @@ pub fn horizontal_box(context: WidgetContext) -> WidgetNode {
     let HorizontalBoxProps {
         separation,
         reversed,
+        justify_content,
         transform,
     } = props.read_cloned_or_default();
 
@@ -75,7 +78,9 @@ pub fn horizontal_box(context: WidgetContext) -> WidgetNode {
         },
         separation,
         wrap: false,
+        justify_content,
         transform,
+        ..Default::default()
     });
 
     widget! {
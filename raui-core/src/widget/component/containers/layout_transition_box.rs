@@ -0,0 +1,196 @@
+//! Container that smoothly animates its content between old and new layout rectangles
+//!
+//! Whenever layout of a [`layout_transition_box`] changes (list got reordered, panel got resized),
+//! its content is first transformed back to where it was rendered before and then animated towards
+//! its new place - technique known as FLIP (First, Last, Invert, Play).
+//!
+//! Layout changes are detected by [`Application::layout`] right after new layout gets computed,
+//! so the first frame rendered with new layout already shows content at its old place.
+//!
+//! [`Application::layout`]: crate::application::Application::layout
+
+use crate::{
+    animator::{AnimatedValue, Animation, AnimatorStates, Easing},
+    pre_hooks,
+    props::Props,
+    unpack_named_slots, widget,
+    widget::{
+        context::WidgetContext,
+        node::WidgetNode,
+        unit::content::{ContentBoxItemLayout, ContentBoxItemNode, ContentBoxNode},
+        utils::{lerp, Rect, Transform, Vec2},
+    },
+    PropsData, Scalar,
+};
+use serde::{Deserialize, Serialize};
+
+const ANIMATION: &str = "layout-transition";
+const VALUE: &str = "factor";
+
+/// The properties of a [`layout_transition_box`] component
+#[derive(PropsData, Debug, Copy, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct LayoutTransitionProps {
+    /// Duration of transition in seconds, transitions are disabled when not positive
+    #[serde(default = "LayoutTransitionProps::default_duration")]
+    pub duration: Scalar,
+    #[serde(default = "LayoutTransitionProps::default_easing")]
+    pub easing: Easing,
+    /// Whether or not to animate position changes
+    #[serde(default = "LayoutTransitionProps::default_enabled")]
+    pub translate: bool,
+    /// Whether or not to animate size changes (content gets scaled)
+    #[serde(default = "LayoutTransitionProps::default_enabled")]
+    pub scale: bool,
+}
+
+impl Default for LayoutTransitionProps {
+    fn default() -> Self {
+        Self {
+            duration: Self::default_duration(),
+            easing: Self::default_easing(),
+            translate: Self::default_enabled(),
+            scale: Self::default_enabled(),
+        }
+    }
+}
+
+impl LayoutTransitionProps {
+    fn default_duration() -> Scalar {
+        0.25
+    }
+
+    fn default_easing() -> Easing {
+        Easing::OutCubic
+    }
+
+    fn default_enabled() -> bool {
+        true
+    }
+}
+
+/// State of [`layout_transition_box`] holding rectangles that transition goes between
+#[derive(PropsData, Debug, Default, Copy, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct LayoutTransitionState {
+    #[serde(default)]
+    pub from: Rect,
+    #[serde(default)]
+    pub to: Rect,
+}
+
+impl LayoutTransitionState {
+    /// Rectangle where content is rendered at given (eased) transition factor
+    pub fn rect(&self, factor: Scalar) -> Rect {
+        Rect {
+            left: lerp(self.from.left, self.to.left, factor),
+            right: lerp(self.from.right, self.to.right, factor),
+            top: lerp(self.from.top, self.to.top, factor),
+            bottom: lerp(self.from.bottom, self.to.bottom, factor),
+        }
+    }
+
+    /// Transform that moves content from its final rectangle to the one at given (eased)
+    /// transition factor
+    pub fn transform(&self, factor: Scalar, props: &LayoutTransitionProps) -> Transform {
+        let rect = self.rect(factor);
+        let mut result = Transform::default();
+        if props.translate {
+            result.translation = Vec2 {
+                x: rect.left - self.to.left,
+                y: rect.top - self.to.top,
+            };
+        }
+        if props.scale {
+            let size = self.to.size();
+            if size.x > 0.0 {
+                result.scale.x = rect.width() / size.x;
+            }
+            if size.y > 0.0 {
+                result.scale.y = rect.height() / size.y;
+            }
+        }
+        result
+    }
+}
+
+/// Starts transition of widget which layout changed from `from` to `to` rectangle, storing it in
+/// widget `state` and `animator`, and returns transform of its content for the frame rendered with
+/// the new layout.
+pub(crate) fn start_layout_transition(
+    props: &LayoutTransitionProps,
+    state: &mut Props,
+    animator: &mut AnimatorStates,
+    from: Rect,
+    to: Rect,
+) -> Transform {
+    // when retargeting running transition, continue from where content is now.
+    let from = if animator.has(ANIMATION) {
+        let factor = animator.value_progress_factor_or(ANIMATION, VALUE, 1.0);
+        state
+            .read_cloned_or_default::<LayoutTransitionState>()
+            .rect(props.easing.apply(factor))
+    } else {
+        from
+    };
+    let transition = LayoutTransitionState { from, to };
+    state.write(transition);
+    animator.change(
+        ANIMATION.to_owned(),
+        Some(Animation::Value(AnimatedValue {
+            name: VALUE.to_owned(),
+            duration: props.duration,
+        })),
+    );
+    transition.transform(props.easing.apply(0.0), props)
+}
+
+pub fn use_layout_transition(context: &mut WidgetContext) {
+    let props = context
+        .props
+        .read_cloned_or_default::<LayoutTransitionProps>();
+    context.life_cycle.layout_transition(props);
+}
+
+/// A container that animates its content between previous and current layout rectangles
+///
+/// Transition can be configured with [`LayoutTransitionProps`].
+#[pre_hooks(use_layout_transition)]
+pub fn layout_transition_box(mut context: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        id,
+        props,
+        state,
+        animator,
+        named_slots,
+        ..
+    } = context;
+    unpack_named_slots!(named_slots => content);
+
+    let transform = if animator.has(ANIMATION) {
+        let props = props.read_cloned_or_default::<LayoutTransitionProps>();
+        let factor = animator.value_progress_factor_or(ANIMATION, VALUE, 1.0);
+        state
+            .read_cloned_or_default::<LayoutTransitionState>()
+            .transform(props.easing.apply(factor), &props)
+    } else {
+        Transform::default()
+    };
+
+    widget! {{{
+        ContentBoxNode {
+            id: id.to_owned(),
+            props: props.clone(),
+            items: vec![ContentBoxItemNode {
+                slot: content,
+                layout: ContentBoxItemLayout::default(),
+            }],
+            clipping: false,
+            layout_direction: None,
+            transform,
+            constraints: vec![],
+        }
+    }}}
+}
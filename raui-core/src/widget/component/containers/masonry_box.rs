@@ -0,0 +1,99 @@
+use crate::{
+    pre_hooks, widget,
+    widget::{
+        component::interactive::navigation::{
+            use_nav_container_active, use_nav_item, use_nav_jump_direction_active,
+            NavContainerActive, NavItemActive, NavJumpActive,
+        },
+        context::WidgetContext,
+        node::WidgetNode,
+        unit::masonry::{MasonryBoxItemLayout, MasonryBoxItemNode, MasonryBoxNode},
+        utils::{LayoutDirection, Transform},
+    },
+    PropsData, Scalar,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct MasonryBoxProps {
+    /// Number of columns, when zero it is computed from minimal column width
+    #[serde(default)]
+    pub columns: usize,
+    #[serde(default)]
+    pub min_column_width: Scalar,
+    #[serde(default)]
+    pub column_gap: Scalar,
+    #[serde(default)]
+    pub row_gap: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+#[pre_hooks(use_nav_container_active, use_nav_jump_direction_active, use_nav_item)]
+pub fn nav_masonry_box(mut context: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        key,
+        props,
+        listed_slots,
+        ..
+    } = context;
+
+    let props = props
+        .clone()
+        .without::<NavContainerActive>()
+        .without::<NavJumpActive>()
+        .without::<NavItemActive>();
+
+    widget! {
+        (#{key} masonry_box: {props} |[listed_slots]|)
+    }
+}
+
+pub fn masonry_box(context: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        id,
+        props,
+        listed_slots,
+        ..
+    } = context;
+
+    let MasonryBoxProps {
+        columns,
+        min_column_width,
+        column_gap,
+        row_gap,
+        layout_direction,
+        transform,
+    } = props.read_cloned_or_default();
+
+    let items = listed_slots
+        .into_iter()
+        .filter_map(|slot| {
+            if let Some(props) = slot.props() {
+                let layout = props.read_cloned_or_default::<MasonryBoxItemLayout>();
+                Some(MasonryBoxItemNode { slot, layout })
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    widget! {{{
+        MasonryBoxNode {
+            id: id.to_owned(),
+            props: props.clone(),
+            items,
+            columns,
+            min_column_width,
+            column_gap,
+            row_gap,
+            layout_direction,
+            transform,
+        }
+    }}}
+}
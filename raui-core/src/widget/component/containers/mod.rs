@@ -7,7 +7,10 @@ pub mod flex_box;
 pub mod grid_box;
 pub mod hidden_box;
 pub mod horizontal_box;
+pub mod layout_transition_box;
+pub mod masonry_box;
 pub mod portal_box;
+pub mod safe_area_box;
 pub mod scroll_box;
 pub mod size_box;
 pub mod switch_box;
@@ -15,4 +18,5 @@ pub mod tabs_box;
 pub mod tooltip_box;
 pub mod variant_box;
 pub mod vertical_box;
+pub mod virtual_list_box;
 pub mod wrap_box;
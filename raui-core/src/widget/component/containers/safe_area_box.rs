@@ -0,0 +1,44 @@
+//! A container that keeps its content inside of the safe area of the screen
+
+use crate::{
+    pre_hooks, unpack_named_slots, widget,
+    widget::{
+        component::containers::anchor_box::{safe_area_margin, use_anchor_box, AnchorProps},
+        context::WidgetContext,
+        node::WidgetNode,
+        unit::content::{ContentBoxItemLayout, ContentBoxItemNode, ContentBoxNode},
+    },
+};
+
+/// Lays out its content inside the part of the box not covered by display notches or overscan
+///
+/// Safe area gets reported relative to widget set in [`RelativeLayoutProps`], which usually
+/// should be the root of the UI.
+///
+/// [`RelativeLayoutProps`]: crate::widget::component::RelativeLayoutProps
+#[pre_hooks(use_anchor_box)]
+pub fn safe_area_box(mut context: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        id,
+        state,
+        named_slots,
+        ..
+    } = context;
+    unpack_named_slots!(named_slots => content);
+
+    let anchor_props = state.read_cloned_or_default::<AnchorProps>();
+
+    widget! {{{
+        ContentBoxNode {
+            id: id.to_owned(),
+            items: vec![ContentBoxItemNode {
+                slot: content,
+                layout: ContentBoxItemLayout {
+                    margin: safe_area_margin(&anchor_props),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        }
+    }}}
+}
@@ -4,7 +4,7 @@ use crate::{
         context::WidgetContext,
         node::WidgetNode,
         unit::size::{SizeBoxNode, SizeBoxSizeValue},
-        utils::{Rect, Transform},
+        utils::{Rect, SizeConstraints, Transform},
     },
     PropsData,
 };
@@ -19,6 +19,8 @@ pub struct SizeBoxProps {
     #[serde(default)]
     pub height: SizeBoxSizeValue,
     #[serde(default)]
+    pub constraints: SizeConstraints,
+    #[serde(default)]
     pub margin: Rect,
     #[serde(default)]
     pub transform: Transform,
@@ -36,6 +38,7 @@ pub fn size_box(context: WidgetContext) -> WidgetNode {
     let SizeBoxProps {
         width,
         height,
+        constraints,
         margin,
         transform,
     } = props.read_cloned_or_default();
@@ -47,6 +50,7 @@ pub fn size_box(context: WidgetContext) -> WidgetNode {
             slot: Box::new(content),
             width,
             height,
+            constraints,
             margin,
             transform,
         }
@@ -80,7 +80,9 @@ pub fn switch_box(context: WidgetContext) -> WidgetNode {
             props: props.clone(),
             items,
             clipping,
+            layout_direction: None,
             transform,
+            constraints: vec![],
         }
     }}}
 }
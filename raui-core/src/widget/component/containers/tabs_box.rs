@@ -84,6 +84,7 @@ impl TabsBoxProps {
             separation: self.tabs_and_content_separation,
             wrap: false,
             transform: self.transform.to_owned(),
+            ..Default::default()
         }
     }
 
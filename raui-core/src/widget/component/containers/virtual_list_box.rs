@@ -0,0 +1,468 @@
+//! A scroll box that creates widgets only for its visible items
+
+use crate::{
+    pre_hooks,
+    props::Props,
+    unpack_named_slots, widget,
+    widget::{
+        component::{
+            containers::{
+                content_box::content_box,
+                scroll_box::nav_scroll_box,
+                size_box::{size_box, SizeBoxProps},
+            },
+            interactive::{
+                navigation::{
+                    use_nav_container_active, NavContainerActive, NavJump, NavScroll, NavSignal,
+                },
+                scroll_view::ScrollViewNotifyMessage,
+                scroll_view::ScrollViewNotifyProps,
+            },
+            use_resize_listener, ResizeListenerSignal,
+        },
+        context::WidgetContext,
+        node::WidgetNode,
+        unit::{area::AreaBoxNode, content::ContentBoxItemLayout, size::SizeBoxSizeValue},
+        utils::{Rect, Vec2},
+        WidgetId,
+    },
+    MessageData, PropsData, Scalar,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+};
+
+/// Size of virtual list items along the scrolling axis
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum VirtualListBoxItemSize {
+    /// All items have the same size
+    Fixed(Scalar),
+    /// Items size themselves and get measured once shown, given size is used for items that were
+    /// not measured yet
+    Estimated(Scalar),
+}
+
+impl Default for VirtualListBoxItemSize {
+    fn default() -> Self {
+        Self::Fixed(32.0)
+    }
+}
+
+impl VirtualListBoxItemSize {
+    pub fn size(&self) -> Scalar {
+        match self {
+            Self::Fixed(size) | Self::Estimated(size) => *size,
+        }
+    }
+
+    pub fn is_estimated(&self) -> bool {
+        matches!(self, Self::Estimated(_))
+    }
+}
+
+/// The properties of a [`nav_virtual_list_box`] component
+#[derive(PropsData, Debug, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct VirtualListBoxProps {
+    /// Total number of items
+    #[serde(default)]
+    pub count: usize,
+    #[serde(default)]
+    pub item_size: VirtualListBoxItemSize,
+    /// Number of items in a row, greater than one makes it a grid
+    #[serde(default = "VirtualListBoxProps::default_columns")]
+    pub columns: usize,
+    /// Space between rows
+    #[serde(default)]
+    pub separation: Scalar,
+    /// Number of rows created before and after visible rows
+    #[serde(default = "VirtualListBoxProps::default_overscan")]
+    pub overscan: usize,
+}
+
+impl Default for VirtualListBoxProps {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            item_size: Default::default(),
+            columns: Self::default_columns(),
+            separation: 0.0,
+            overscan: Self::default_overscan(),
+        }
+    }
+}
+
+impl VirtualListBoxProps {
+    fn default_columns() -> usize {
+        1
+    }
+
+    fn default_overscan() -> usize {
+        2
+    }
+}
+
+/// Properties given to widget created for virtual list item
+#[derive(PropsData, Debug, Default, Copy, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct VirtualListBoxItemProps {
+    pub index: usize,
+}
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct VirtualListBoxOwner(
+    #[serde(default)]
+    #[serde(skip_serializing_if = "WidgetId::is_none")]
+    pub WidgetId,
+);
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct VirtualListBoxState {
+    /// Scroll box used by the list
+    #[serde(default)]
+    pub scroll_view: WidgetId,
+    #[serde(default)]
+    pub viewport_size: Vec2,
+    /// Scroll position in local space units
+    #[serde(default)]
+    pub offset: Scalar,
+    /// Measured sizes of items with estimated size
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub measured: HashMap<usize, Scalar>,
+    /// Scroll box position waits to be synced with offset once its content gets resized
+    #[serde(default)]
+    pub sync_scroll: bool,
+}
+
+/// Message that scrolls virtual list to the row of item with given index
+#[derive(MessageData, Debug, Copy, Clone, PartialEq, Eq)]
+#[message_data(crate::messenger::MessageData)]
+pub struct VirtualListBoxJumpToIndex(pub usize);
+
+#[derive(MessageData, Debug, Copy, Clone, PartialEq)]
+#[message_data(crate::messenger::MessageData)]
+pub struct VirtualListBoxItemMeasured {
+    pub index: usize,
+    pub size: Vec2,
+}
+
+/// Positions of virtual list rows along the scrolling axis
+#[derive(Debug, Default, Clone)]
+pub struct VirtualListBoxMetrics {
+    rows: usize,
+    size: Scalar,
+    separation: Scalar,
+    /// Sizes of rows with measured items
+    measured_rows: BTreeMap<usize, Scalar>,
+}
+
+impl VirtualListBoxMetrics {
+    pub fn new(props: &VirtualListBoxProps, measured: &HashMap<usize, Scalar>) -> Self {
+        let columns = props.columns.max(1);
+        let mut measured_rows = BTreeMap::<usize, Scalar>::new();
+        if props.item_size.is_estimated() {
+            for (index, size) in measured {
+                if *index < props.count {
+                    let row = measured_rows.entry(index / columns).or_default();
+                    *row = row.max(*size);
+                }
+            }
+        }
+        let rows = props.count.saturating_add(columns - 1) / columns;
+        Self {
+            rows,
+            size: props.item_size.size(),
+            separation: props.separation,
+            measured_rows,
+        }
+    }
+
+    pub fn rows_count(&self) -> usize {
+        self.rows
+    }
+
+    pub fn row_size(&self, row: usize) -> Scalar {
+        self.measured_rows.get(&row).copied().unwrap_or(self.size)
+    }
+
+    pub fn row_offset(&self, row: usize) -> Scalar {
+        let row = row.min(self.rows);
+        let measured = self
+            .measured_rows
+            .range(..row)
+            .map(|(_, size)| size - self.size)
+            .sum::<Scalar>();
+        row as Scalar * (self.size + self.separation) + measured
+    }
+
+    pub fn content_size(&self) -> Scalar {
+        if self.rows > 0 {
+            self.row_offset(self.rows) - self.separation
+        } else {
+            0.0
+        }
+    }
+
+    /// Index of row at given offset.
+    pub fn row_at(&self, offset: Scalar) -> usize {
+        let mut from = 0;
+        let mut to = self.rows;
+        while from < to {
+            let row = (from + to) / 2;
+            if self.row_offset(row + 1) <= offset {
+                from = row + 1;
+            } else {
+                to = row;
+            }
+        }
+        from.min(self.rows.saturating_sub(1))
+    }
+
+    /// Range of rows visible in viewport at given offset, extended by overscan rows.
+    pub fn visible_rows(&self, offset: Scalar, viewport: Scalar, overscan: usize) -> Range<usize> {
+        if self.rows == 0 {
+            return 0..0;
+        }
+        let first = self.row_at(offset);
+        let last = self.row_at(offset + viewport.max(0.0));
+        first.saturating_sub(overscan)..(last + overscan + 1).min(self.rows)
+    }
+
+    /// Largest scroll offset for viewport of given size.
+    pub fn max_offset(&self, viewport: Scalar) -> Scalar {
+        (self.content_size() - viewport).max(0.0)
+    }
+}
+
+pub fn use_virtual_list_box_item(context: &mut WidgetContext) {
+    context.life_cycle.change(|context| {
+        for msg in context.messenger.messages {
+            if let Some(ResizeListenerSignal::Change(size)) = msg.as_any().downcast_ref() {
+                if let (Ok(owner), Ok(item)) = (
+                    context.props.read::<VirtualListBoxOwner>(),
+                    context.props.read::<VirtualListBoxItemProps>(),
+                ) {
+                    context.messenger.write(
+                        owner.0.to_owned(),
+                        VirtualListBoxItemMeasured {
+                            index: item.index,
+                            size: *size,
+                        },
+                    );
+                }
+            }
+        }
+    });
+}
+
+#[pre_hooks(use_resize_listener, use_virtual_list_box_item)]
+pub fn virtual_list_box_item(mut context: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        id, named_slots, ..
+    } = context;
+    unpack_named_slots!(named_slots => content);
+
+    widget! {{{
+        AreaBoxNode {
+            id: id.to_owned(),
+            slot: Box::new(content),
+            renderer_effect: None,
+        }
+    }}}
+}
+
+pub fn use_nav_virtual_list_box(context: &mut WidgetContext) {
+    context.life_cycle.mount(|context| {
+        let _ = context.state.write_with(VirtualListBoxState::default());
+    });
+
+    context.life_cycle.change(|context| {
+        let props = context
+            .props
+            .read_cloned_or_default::<VirtualListBoxProps>();
+        let mut data = context
+            .state
+            .read_cloned_or_default::<VirtualListBoxState>();
+        let metrics = VirtualListBoxMetrics::new(&props, &data.measured);
+        // scroll box reports position relative to content size that is not in sync with offset.
+        let syncing = data.sync_scroll;
+        let mut dirty = false;
+        let mut measured = vec![];
+        let mut jump_to = None;
+        for msg in context.messenger.messages {
+            if let Some(ResizeListenerSignal::Change(size)) = msg.as_any().downcast_ref() {
+                data.viewport_size = *size;
+                dirty = true;
+            } else if let Some(msg) = msg.as_any().downcast_ref::<ScrollViewNotifyMessage>() {
+                data.scroll_view = msg.sender.to_owned();
+                if !syncing {
+                    data.offset = msg.state.value.y * metrics.max_offset(data.viewport_size.y);
+                }
+                dirty = true;
+            } else if let Some(msg) = msg.as_any().downcast_ref::<VirtualListBoxItemMeasured>() {
+                measured.push(*msg);
+            } else if let Some(VirtualListBoxJumpToIndex(index)) = msg.as_any().downcast_ref() {
+                jump_to = Some(*index);
+            }
+        }
+        let mut resized = false;
+        if props.item_size.is_estimated() && !measured.is_empty() {
+            // keep the first visible row in place when rows above it change their size.
+            let row = metrics.row_at(data.offset);
+            let delta = data.offset - metrics.row_offset(row);
+            for VirtualListBoxItemMeasured { index, size } in measured {
+                resized = match data.measured.insert(index, size.y) {
+                    Some(prev) => resized || (prev - size.y).abs() >= 1.0e-6,
+                    None => true,
+                };
+            }
+            if resized {
+                let metrics = VirtualListBoxMetrics::new(&props, &data.measured);
+                data.offset =
+                    (metrics.row_offset(row) + delta).min(metrics.max_offset(data.viewport_size.y));
+                dirty = true;
+            }
+        }
+        let mut sync = syncing;
+        if let Some(index) = jump_to {
+            let metrics = VirtualListBoxMetrics::new(&props, &data.measured);
+            let row = index.min(props.count.saturating_sub(1)) / props.columns.max(1);
+            data.offset = metrics
+                .row_offset(row)
+                .min(metrics.max_offset(data.viewport_size.y));
+            sync = true;
+            dirty = true;
+        }
+        if resized {
+            // scroll box converts units with current layout, so wait for resized content first.
+            data.sync_scroll = true;
+        } else if sync {
+            data.sync_scroll = false;
+            dirty = true;
+            if data.scroll_view.is_valid() {
+                context
+                    .signals
+                    .write(NavSignal::Jump(NavJump::Scroll(NavScroll::DirectUnits(
+                        data.scroll_view.to_owned().into(),
+                        Vec2 {
+                            x: 0.0,
+                            y: data.offset,
+                        },
+                        false,
+                    ))));
+            }
+        }
+        if dirty {
+            let _ = context.state.write_with(data);
+        }
+    });
+}
+
+/// A vertical scroll box that creates widgets only for its visible items
+///
+/// Item widgets are made from `item` named slot, which gets cloned with
+/// [`VirtualListBoxItemProps`] for every visible item (plus [`VirtualListBoxProps::overscan`]
+/// rows around them), so the list can hold a huge number of items. Items of
+/// [`VirtualListBoxItemSize::Estimated`] size should size themselves, as they get laid out with
+/// no height and measured once shown. Send [`VirtualListBoxJumpToIndex`] message to the list to
+/// scroll to given item.
+#[pre_hooks(
+    use_resize_listener,
+    use_nav_container_active,
+    use_nav_virtual_list_box
+)]
+pub fn nav_virtual_list_box(mut context: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        id,
+        key,
+        props,
+        state,
+        named_slots,
+        ..
+    } = context;
+    unpack_named_slots!(named_slots => {item, scrollbars});
+
+    let list_props = props.read_cloned_or_default::<VirtualListBoxProps>();
+    let data = state.read_cloned_or_default::<VirtualListBoxState>();
+    let metrics = VirtualListBoxMetrics::new(&list_props, &data.measured);
+    let columns = list_props.columns.max(1);
+    let rows = metrics.visible_rows(data.offset, data.viewport_size.y, list_props.overscan);
+
+    let mut items = Vec::with_capacity(rows.len() * columns);
+    if item.is_some() {
+        for row in rows {
+            let top = metrics.row_offset(row);
+            let bottom = if list_props.item_size.is_estimated() {
+                0.0
+            } else {
+                -metrics.row_size(row)
+            };
+            for column in 0..columns {
+                let index = row * columns + column;
+                if index >= list_props.count {
+                    break;
+                }
+                let mut content = item.clone();
+                content.remap_props(|props| props.with(VirtualListBoxItemProps { index }));
+                let item_props = Props::new(ContentBoxItemLayout {
+                    anchors: Rect {
+                        left: column as Scalar / columns as Scalar,
+                        right: (column + 1) as Scalar / columns as Scalar,
+                        top: 0.0,
+                        bottom: 0.0,
+                    },
+                    margin: Rect {
+                        bottom,
+                        ..Default::default()
+                    },
+                    offset: Vec2 { x: 0.0, y: top },
+                    ..Default::default()
+                })
+                .with(VirtualListBoxItemProps { index })
+                .with(VirtualListBoxOwner(id.to_owned()));
+                items.push(widget! {
+                    (#{index} virtual_list_box_item: {item_props} {
+                        content = {content}
+                    })
+                });
+            }
+        }
+    }
+
+    let scroll_props = props
+        .clone()
+        .without::<VirtualListBoxProps>()
+        .without::<NavContainerActive>()
+        .with(ScrollViewNotifyProps(id.to_owned().into()));
+    let size_props = SizeBoxProps {
+        width: SizeBoxSizeValue::Fill,
+        height: SizeBoxSizeValue::Exact(metrics.content_size()),
+        ..Default::default()
+    };
+
+    let content = widget! {
+        (#{key} nav_scroll_box: {scroll_props} {
+            content = (#{"content"} size_box: {size_props} {
+                content = (#{"items"} content_box |[ items ]|)
+            })
+            scrollbars = {scrollbars}
+        })
+    };
+
+    widget! {{{
+        AreaBoxNode {
+            id: id.to_owned(),
+            slot: Box::new(content),
+            renderer_effect: None,
+        }
+    }}}
+}
@@ -0,0 +1,38 @@
+use crate::{
+    widget,
+    widget::{
+        context::WidgetContext, node::WidgetNode, unit::custom::CustomBoxNode, utils::Transform,
+    },
+    PropsData,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct CustomBoxProps {
+    /// Type name under which layout engine and renderer hooks of custom unit are registered
+    #[serde(default)]
+    pub type_name: String,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+/// Custom unit with all props of this widget passed to it as its data.
+pub fn custom_box(context: WidgetContext) -> WidgetNode {
+    let WidgetContext { id, props, .. } = context;
+
+    let CustomBoxProps {
+        type_name,
+        transform,
+    } = props.read_cloned_or_default();
+
+    widget! {{{
+        CustomBoxNode {
+            id: id.to_owned(),
+            props: props.clone(),
+            type_name,
+            transform,
+        }
+    }}}
+}
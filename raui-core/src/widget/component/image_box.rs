@@ -1,15 +1,16 @@
 use crate::{
-    widget,
+    animator::{AnimatedValue, Animation},
+    pre_hooks, widget,
     widget::{
         component::WidgetAlpha,
-        context::WidgetContext,
+        context::{WidgetContext, WidgetMountOrChangeContext},
         node::WidgetNode,
         unit::image::{
             ImageBoxAspectRatio, ImageBoxColor, ImageBoxMaterial, ImageBoxNode, ImageBoxSizeValue,
         },
-        utils::{Color, Transform},
+        utils::{lerp, Color, Rect, Transform},
     },
-    PropsData,
+    PropsData, Scalar,
 };
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +27,9 @@ pub struct ImageBoxProps {
     pub content_keep_aspect_ratio: Option<ImageBoxAspectRatio>,
     #[serde(default)]
     pub material: ImageBoxMaterial,
+    /// Distance of text baseline from image bottom edge, used by baseline alignment
+    #[serde(default)]
+    pub baseline_offset: Scalar,
     #[serde(default)]
     pub transform: Transform,
 }
@@ -55,6 +59,7 @@ pub fn image_box(context: WidgetContext) -> WidgetNode {
         height,
         content_keep_aspect_ratio,
         mut material,
+        baseline_offset,
         transform,
     } = props.read_cloned_or_default();
 
@@ -66,6 +71,15 @@ pub fn image_box(context: WidgetContext) -> WidgetNode {
         ImageBoxMaterial::Image(image) => {
             image.tint.a *= alpha;
         }
+        ImageBoxMaterial::Shape(shape) => {
+            shape.color.a *= alpha;
+            shape.border.color.a *= alpha;
+        }
+        ImageBoxMaterial::Gradient(gradient) => {
+            for stop in &mut gradient.stops {
+                stop.color.a *= alpha;
+            }
+        }
         _ => {}
     }
 
@@ -77,7 +91,238 @@ pub fn image_box(context: WidgetContext) -> WidgetNode {
             height,
             content_keep_aspect_ratio,
             material,
+            baseline_offset,
             transform,
         }
     }}}
 }
+
+/// Layout of frames on a sprite sheet, in image space fractions (`<0;1>`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpriteSheetLayout {
+    /// Frames of equal size read row by row
+    Grid {
+        columns: usize,
+        rows: usize,
+        /// Number of used frames, all grid cells are used if not set
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        count: Option<usize>,
+    },
+    /// Explicit frame rectangles
+    Frames(Vec<Rect>),
+}
+
+impl Default for SpriteSheetLayout {
+    fn default() -> Self {
+        Self::Frames(vec![])
+    }
+}
+
+impl SpriteSheetLayout {
+    pub fn frames_count(&self) -> usize {
+        match self {
+            Self::Grid {
+                columns,
+                rows,
+                count,
+            } => {
+                let cells = columns * rows;
+                count.map(|count| count.min(cells)).unwrap_or(cells)
+            }
+            Self::Frames(frames) => frames.len(),
+        }
+    }
+
+    pub fn frame_rect(&self, index: usize) -> Option<Rect> {
+        if index >= self.frames_count() {
+            return None;
+        }
+        match self {
+            Self::Grid { columns, rows, .. } => {
+                let column = (index % columns) as Scalar;
+                let row = (index / columns) as Scalar;
+                let width = 1.0 / *columns as Scalar;
+                let height = 1.0 / *rows as Scalar;
+                Some(Rect {
+                    left: column * width,
+                    right: (column + 1.0) * width,
+                    top: row * height,
+                    bottom: (row + 1.0) * height,
+                })
+            }
+            Self::Frames(frames) => frames.get(index).copied(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpriteAnimationLooping {
+    /// Play once and stay on the last frame
+    Once,
+    /// Restart from first frame after the last one
+    Loop,
+    /// Play back and forth
+    PingPong,
+}
+
+impl Default for SpriteAnimationLooping {
+    fn default() -> Self {
+        Self::Loop
+    }
+}
+
+/// Sprite sheet animation played by [`sprite_image_box`]
+#[derive(PropsData, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct SpriteAnimationProps {
+    #[serde(default)]
+    pub layout: SpriteSheetLayout,
+    /// Duration of each frame in seconds
+    #[serde(default = "SpriteAnimationProps::default_frame_duration")]
+    pub frame_duration: Scalar,
+    /// Per-frame durations overriding `frame_duration` for frames at given indices
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub frame_durations: Vec<Scalar>,
+    #[serde(default)]
+    pub looping: SpriteAnimationLooping,
+}
+
+impl Default for SpriteAnimationProps {
+    fn default() -> Self {
+        Self {
+            layout: Default::default(),
+            frame_duration: Self::default_frame_duration(),
+            frame_durations: Default::default(),
+            looping: Default::default(),
+        }
+    }
+}
+
+impl SpriteAnimationProps {
+    fn default_frame_duration() -> Scalar {
+        0.1
+    }
+
+    pub fn frame_duration(&self, index: usize) -> Scalar {
+        self.frame_durations
+            .get(index)
+            .copied()
+            .unwrap_or(self.frame_duration)
+            .max(0.0)
+    }
+
+    /// Total duration of all frames
+    pub fn duration(&self) -> Scalar {
+        (0..self.layout.frames_count())
+            .map(|index| self.frame_duration(index))
+            .sum()
+    }
+
+    /// Index of frame shown at given time since animation start
+    pub fn frame_at(&self, mut time: Scalar) -> usize {
+        let count = self.layout.frames_count();
+        for index in 0..count {
+            let duration = self.frame_duration(index);
+            if time < duration {
+                return index;
+            }
+            time -= duration;
+        }
+        count.saturating_sub(1)
+    }
+
+    fn animation(&self) -> Animation {
+        let animation = Animation::Value(AnimatedValue {
+            name: SPRITE_ANIMATION_VALUE.to_owned(),
+            duration: self.duration(),
+        });
+        match self.looping {
+            SpriteAnimationLooping::Once => animation,
+            SpriteAnimationLooping::Loop => Animation::Looped(Box::new(animation)),
+            SpriteAnimationLooping::PingPong => Animation::PingPong(Box::new(animation)),
+        }
+    }
+}
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct SpriteAnimationState {
+    /// Sprite animation that was started last, `None` until widget gets mounted
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation: Option<SpriteAnimationProps>,
+}
+
+const SPRITE_ANIMATION: &str = "sprite";
+const SPRITE_ANIMATION_VALUE: &str = "time";
+
+pub fn use_sprite_animation(context: &mut WidgetContext) {
+    fn restart(context: WidgetMountOrChangeContext) {
+        let props = context
+            .props
+            .read_cloned_or_default::<SpriteAnimationProps>();
+        let state = context
+            .state
+            .read_cloned_or_default::<SpriteAnimationState>();
+        if state.animation.as_ref() == Some(&props) {
+            return;
+        }
+        let _ = context
+            .animator
+            .change(SPRITE_ANIMATION, Some(props.animation()));
+        let _ = context.state.write(SpriteAnimationState {
+            animation: Some(props),
+        });
+    }
+
+    context.life_cycle.mount(restart);
+    context.life_cycle.change(restart);
+}
+
+/// Image box that shows frames of a sprite sheet
+///
+/// Image material is taken from [`ImageBoxProps`] and its `source_rect` (if any) is treated as the
+/// region of the sprite sheet, frames of [`SpriteAnimationProps`] are placed relative to it.
+/// Animation starts when widget gets mounted and restarts whenever its props change.
+#[pre_hooks(use_sprite_animation)]
+pub fn sprite_image_box(mut context: WidgetContext) -> WidgetNode {
+    let sprite = context
+        .props
+        .read_cloned_or_default::<SpriteAnimationProps>();
+    let state = context
+        .state
+        .read_cloned_or_default::<SpriteAnimationState>();
+    // animation of changed props starts right after this render, so show its first frame.
+    let time = if state.animation.as_ref() != Some(&sprite) {
+        0.0
+    } else {
+        match context
+            .animator
+            .value_progress(SPRITE_ANIMATION, SPRITE_ANIMATION_VALUE)
+        {
+            Some(progress) => progress.time,
+            None if sprite.looping == SpriteAnimationLooping::Once => sprite.duration(),
+            None => 0.0,
+        }
+    };
+    let mut props = context.props.read_cloned_or_default::<ImageBoxProps>();
+    if let ImageBoxMaterial::Image(image) = &mut props.material {
+        if let Some(frame) = sprite.layout.frame_rect(sprite.frame_at(time)) {
+            image.source_rect = Some(match image.source_rect {
+                Some(region) => Rect {
+                    left: lerp(region.left, region.right, frame.left),
+                    right: lerp(region.left, region.right, frame.right),
+                    top: lerp(region.top, region.bottom, frame.top),
+                    bottom: lerp(region.top, region.bottom, frame.bottom),
+                },
+                None => frame,
+            });
+        }
+    }
+    context.props.write(props);
+    image_box(context)
+}
@@ -1,6 +1,8 @@
 pub mod containers;
+pub mod custom_box;
 pub mod image_box;
 pub mod interactive;
+pub mod rich_text_box;
 pub mod space_box;
 pub mod text_box;
 
@@ -115,6 +117,8 @@ pub enum RelativeLayoutListenerSignal {
     Unregister,
     /// (outer box size, inner box rect)
     Change(Vec2, Rect),
+    /// (safe area rect relative to outer box)
+    SafeAreaChange(Rect),
 }
 
 pub fn use_relative_layout_listener(context: &mut WidgetContext) {
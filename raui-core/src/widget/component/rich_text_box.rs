@@ -0,0 +1,326 @@
+use crate::{
+    pre_hooks, widget,
+    widget::{
+        component::WidgetAlpha,
+        context::WidgetContext,
+        node::WidgetNode,
+        unit::{
+            rich_text::{
+                RichTextBoxImage, RichTextBoxNode, RichTextBoxSpan, RichTextBoxSpanContent,
+            },
+            text::{TextBoxFont, TextBoxHorizontalAlign, TextBoxSizeValue, TextBoxVerticalAlign},
+        },
+        utils::{Color, Transform},
+        WidgetId, WidgetIdOrRef,
+    },
+    MessageData, PropsData, Scalar,
+};
+use serde::{Deserialize, Serialize};
+
+/// Styles used by rich text markup tags
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RichTextMarkupStyle {
+    #[serde(default)]
+    pub font: TextBoxFont,
+    /// Font of bold text, regular font is used when empty
+    #[serde(default)]
+    pub bold_font: String,
+    /// Font of italic text, regular font is used when empty
+    #[serde(default)]
+    pub italic_font: String,
+    /// Font of bold italic text, bold font is used when empty
+    #[serde(default)]
+    pub bold_italic_font: String,
+    #[serde(default)]
+    pub color: Color,
+    /// Color of links, when not set links keep color of surrounding text
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_color: Option<Color>,
+}
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct RichTextBoxProps {
+    /// Text with markup tags (see [`parse_rich_text_markup`])
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub width: TextBoxSizeValue,
+    #[serde(default)]
+    pub height: TextBoxSizeValue,
+    #[serde(default)]
+    pub horizontal_align: TextBoxHorizontalAlign,
+    #[serde(default)]
+    pub vertical_align: TextBoxVerticalAlign,
+    #[serde(default)]
+    pub style: RichTextMarkupStyle,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct RichTextBoxNotifyProps(
+    #[serde(default)]
+    #[serde(skip_serializing_if = "WidgetIdOrRef::is_none")]
+    pub WidgetIdOrRef,
+);
+
+/// Link of rich text box clicked, sent as signal and to widget set in [`RichTextBoxNotifyProps`]
+#[derive(MessageData, Debug, Clone)]
+#[message_data(crate::messenger::MessageData)]
+pub struct RichTextLinkMessage {
+    pub sender: WidgetId,
+    pub link: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RichTextMarkupError {
+    /// Tag starting at given byte position has no closing `>`
+    UnterminatedTag(usize),
+    UnknownTag(String),
+    InvalidTagValue(String, String),
+    UnexpectedClosingTag(String),
+    UnclosedTag(String),
+}
+
+enum RichTextMarkupTag {
+    Bold,
+    Italic,
+    Color(Color),
+    Size(Scalar),
+    Font(String),
+    Link(String),
+}
+
+fn parse_markup_color(value: &str) -> Option<Color> {
+    let value = value.strip_prefix('#')?;
+    if (value.len() != 6 && value.len() != 8) || !value.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| {
+        value
+            .get((index * 2)..(index * 2 + 2))
+            .map(|v| u8::from_str_radix(v, 16).map(|v| v as Scalar / 255.0))
+            .unwrap_or(Ok(1.0))
+            .ok()
+    };
+    Some(Color {
+        r: channel(0)?,
+        g: channel(1)?,
+        b: channel(2)?,
+        a: channel(3)?,
+    })
+}
+
+fn markup_span(
+    style: &RichTextMarkupStyle,
+    tags: &[(String, RichTextMarkupTag)],
+) -> RichTextBoxSpan {
+    let mut bold = false;
+    let mut italic = false;
+    let mut font = None;
+    let mut result = RichTextBoxSpan {
+        content: Default::default(),
+        font: style.font.to_owned(),
+        color: style.color,
+        link: None,
+    };
+    for (_, tag) in tags {
+        match tag {
+            RichTextMarkupTag::Bold => bold = true,
+            RichTextMarkupTag::Italic => italic = true,
+            RichTextMarkupTag::Color(color) => result.color = *color,
+            RichTextMarkupTag::Size(size) => result.font.size = *size,
+            RichTextMarkupTag::Font(name) => font = Some(name),
+            RichTextMarkupTag::Link(link) => {
+                result.link = Some(link.to_owned());
+                if let Some(color) = style.link_color {
+                    result.color = color;
+                }
+            }
+        }
+    }
+    let variant = match (bold, italic) {
+        (true, true) if !style.bold_italic_font.is_empty() => &style.bold_italic_font,
+        (true, _) => &style.bold_font,
+        (_, true) => &style.italic_font,
+        _ => &style.font.name,
+    };
+    if let Some(name) = font {
+        result.font.name = name.to_owned();
+    } else if !variant.is_empty() {
+        result.font.name = variant.to_owned();
+    }
+    result
+}
+
+/// Parses text with markup tags into rich text spans. Supported tags:
+/// - `<b>bold</b>` and `<i>italic</i>`
+/// - `<color=#ff8800>colored</color>` (`#rrggbb` or `#rrggbbaa`)
+/// - `<size=24>resized</size>`
+/// - `<font=name>other font</font>`
+/// - `<link=target>link</link>`
+/// - `<img=id>` inline image sized to current font size
+///
+/// Tags can be nested, characters preceded by `\` are not treated as markup (`\<` gives `<`).
+pub fn parse_rich_text_markup(
+    markup: &str,
+    style: &RichTextMarkupStyle,
+) -> Result<Vec<RichTextBoxSpan>, RichTextMarkupError> {
+    let mut result = vec![];
+    let mut tags: Vec<(String, RichTextMarkupTag)> = vec![];
+    let mut text = String::new();
+    let flush = |text: &mut String, tags: &[_], result: &mut Vec<_>| {
+        if !text.is_empty() {
+            let mut span = markup_span(style, tags);
+            span.content = RichTextBoxSpanContent::Text(std::mem::take(text));
+            result.push(span);
+        }
+    };
+    let mut chars = markup.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => text.push(chars.next().map(|(_, c)| c).unwrap_or('\\')),
+            '<' => {
+                let rest = &markup[(index + 1)..];
+                let end = rest
+                    .find('>')
+                    .ok_or(RichTextMarkupError::UnterminatedTag(index))?;
+                let tag = rest[..end].trim();
+                for _ in 0..=rest[..end].chars().count() {
+                    chars.next();
+                }
+                flush(&mut text, &tags, &mut result);
+                if let Some(name) = tag.strip_prefix('/') {
+                    let name = name.trim();
+                    match tags.pop() {
+                        Some((opened, _)) if opened == name => {}
+                        _ => {
+                            return Err(RichTextMarkupError::UnexpectedClosingTag(name.to_owned()))
+                        }
+                    }
+                    continue;
+                }
+                let mut parts = tag.splitn(2, '=');
+                let name = parts.next().unwrap_or_default().trim();
+                let value = parts.next().map(|value| value.trim());
+                let invalid = || {
+                    RichTextMarkupError::InvalidTagValue(
+                        name.to_owned(),
+                        value.unwrap_or_default().to_owned(),
+                    )
+                };
+                let tag = match (name, value) {
+                    ("b", None) => RichTextMarkupTag::Bold,
+                    ("i", None) => RichTextMarkupTag::Italic,
+                    ("color", Some(value)) => {
+                        RichTextMarkupTag::Color(parse_markup_color(value).ok_or_else(invalid)?)
+                    }
+                    ("size", Some(value)) => {
+                        RichTextMarkupTag::Size(value.parse().map_err(|_| invalid())?)
+                    }
+                    ("font", Some(value)) if !value.is_empty() => {
+                        RichTextMarkupTag::Font(value.to_owned())
+                    }
+                    ("link", Some(value)) => RichTextMarkupTag::Link(value.to_owned()),
+                    ("img", Some(value)) if !value.is_empty() => {
+                        let mut span = markup_span(style, &tags);
+                        span.content = RichTextBoxSpanContent::Image(RichTextBoxImage {
+                            id: value.to_owned(),
+                            width: span.font.size,
+                            height: span.font.size,
+                            tint: Default::default(),
+                        });
+                        result.push(span);
+                        continue;
+                    }
+                    ("b", _)
+                    | ("i", _)
+                    | ("color", _)
+                    | ("size", _)
+                    | ("font", _)
+                    | ("link", _)
+                    | ("img", _) => return Err(invalid()),
+                    _ => return Err(RichTextMarkupError::UnknownTag(name.to_owned())),
+                };
+                tags.push((name.to_owned(), tag));
+            }
+            _ => text.push(c),
+        }
+    }
+    flush(&mut text, &tags, &mut result);
+    match tags.pop() {
+        Some((name, _)) => Err(RichTextMarkupError::UnclosedTag(name)),
+        None => Ok(result),
+    }
+}
+
+pub fn use_rich_text_box(context: &mut WidgetContext) {
+    context.life_cycle.change(|context| {
+        for msg in context.messenger.messages {
+            if let Some(msg) = msg.as_any().downcast_ref::<RichTextLinkMessage>() {
+                if let Ok(RichTextBoxNotifyProps(notify)) = context.props.read() {
+                    if let Some(to) = notify.read() {
+                        context.messenger.write(to, msg.to_owned());
+                    }
+                }
+                context.signals.write(msg.to_owned());
+            }
+        }
+    });
+}
+
+/// Text with styled spans, inline images and links described with markup (see
+/// [`parse_rich_text_markup`]). Invalid markup is shown as plain text.
+#[pre_hooks(use_rich_text_box)]
+pub fn rich_text_box(mut context: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        id,
+        props,
+        shared_props,
+        ..
+    } = context;
+
+    let RichTextBoxProps {
+        text,
+        width,
+        height,
+        horizontal_align,
+        vertical_align,
+        style,
+        transform,
+    } = props.read_cloned_or_default();
+
+    let mut spans = parse_rich_text_markup(&text, &style).unwrap_or_else(|_| {
+        vec![RichTextBoxSpan {
+            content: RichTextBoxSpanContent::Text(text),
+            font: style.font,
+            color: style.color,
+            link: None,
+        }]
+    });
+    let alpha = shared_props.read_cloned_or_default::<WidgetAlpha>().0;
+    for span in &mut spans {
+        span.color.a *= alpha;
+        if let RichTextBoxSpanContent::Image(image) = &mut span.content {
+            image.tint.a *= alpha;
+        }
+    }
+
+    widget! {{{
+        RichTextBoxNode {
+            id: id.to_owned(),
+            props: props.clone(),
+            spans,
+            width,
+            height,
+            horizontal_align,
+            vertical_align,
+            transform,
+        }
+    }}}
+}
@@ -5,12 +5,12 @@ use crate::{
         context::WidgetContext,
         node::WidgetNode,
         unit::text::{
-            TextBoxDirection, TextBoxFont, TextBoxHorizontalAlign, TextBoxNode, TextBoxSizeValue,
-            TextBoxVerticalAlign,
+            TextBoxDirection, TextBoxFont, TextBoxHorizontalAlign, TextBoxNode, TextBoxOverflow,
+            TextBoxSizeValue, TextBoxVerticalAlign, TextBoxWrap,
         },
         utils::{Color, Transform},
     },
-    PropsData,
+    PropsData, Scalar,
 };
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +31,16 @@ pub struct TextBoxProps {
     #[serde(default)]
     pub direction: TextBoxDirection,
     #[serde(default)]
+    pub wrap: TextBoxWrap,
+    #[serde(default)]
+    pub overflow: TextBoxOverflow,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height: Option<Scalar>,
+    #[serde(default)]
     pub font: TextBoxFont,
     #[serde(default)]
     pub color: Color,
@@ -53,6 +63,10 @@ pub fn text_box(context: WidgetContext) -> WidgetNode {
         horizontal_align,
         vertical_align,
         direction,
+        wrap,
+        overflow,
+        max_lines,
+        line_height,
         font,
         mut color,
         transform,
@@ -71,6 +85,10 @@ pub fn text_box(context: WidgetContext) -> WidgetNode {
             horizontal_align,
             vertical_align,
             direction,
+            wrap,
+            overflow,
+            max_lines,
+            line_height,
             font,
             color,
             transform,
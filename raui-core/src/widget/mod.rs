@@ -7,9 +7,11 @@ pub mod unit;
 pub mod utils;
 
 use crate::{
+    animator::Animation,
     application::Application,
     props::PropsData,
     widget::{
+        component::containers::layout_transition_box::LayoutTransitionProps,
         context::{WidgetContext, WidgetMountOrChangeContext, WidgetUnmountContext},
         node::WidgetNode,
     },
@@ -285,6 +287,8 @@ pub struct WidgetLifeCycle {
     mount: Vec<Box<dyn FnMut(WidgetMountOrChangeContext) + Send + Sync>>,
     change: Vec<Box<dyn FnMut(WidgetMountOrChangeContext) + Send + Sync>>,
     unmount: Vec<Box<dyn FnMut(WidgetUnmountContext) + Send + Sync>>,
+    exit: Option<(String, Animation)>,
+    layout_transition: Option<LayoutTransitionProps>,
 }
 
 impl WidgetLifeCycle {
@@ -309,6 +313,36 @@ impl WidgetLifeCycle {
         self.unmount.push(Box::new(f));
     }
 
+    /// Declare exit transition animation of this widget.
+    ///
+    /// When widget gets removed from its parent content, flex or grid box, application keeps
+    /// rendering it (non-interactive) at its last position, plays `animation` under `anim_id` on
+    /// its animator and unmounts it only after that animation completes. Widget should have an
+    /// explicit key so it does not get mixed up with its siblings while leaving.
+    pub fn exit(&mut self, anim_id: &str, animation: Animation) {
+        self.exit = Some((anim_id.to_owned(), animation));
+    }
+
+    pub(crate) fn take_exit(&mut self) -> Option<(String, Animation)> {
+        self.exit.take()
+    }
+
+    /// Declare layout transition of this widget.
+    ///
+    /// Whenever application computes layout in which this widget occupies different rectangle
+    /// than before, it starts transition from old to new rectangle right away, so the first frame
+    /// rendered with new layout already shows widget at its old place. See
+    /// [`layout_transition_box`] for more information.
+    ///
+    /// [`layout_transition_box`]: crate::widget::component::containers::layout_transition_box
+    pub fn layout_transition(&mut self, props: LayoutTransitionProps) {
+        self.layout_transition = Some(props);
+    }
+
+    pub(crate) fn take_layout_transition(&mut self) -> Option<LayoutTransitionProps> {
+        self.layout_transition.take()
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn unwrap(
         self,
@@ -321,6 +355,7 @@ impl WidgetLifeCycle {
             mount,
             change,
             unmount,
+            ..
         } = self;
         (mount, change, unmount)
     }
@@ -342,6 +377,8 @@ pub fn setup(app: &mut Application) {
     app.register_props::<f64>("f64");
     app.register_props::<bool>("bool");
     app.register_props::<String>("String");
+    app.register_props::<crate::animator::AnimationClip>("AnimationClip");
+    app.register_props::<crate::animator::AnimationTimeScale>("AnimationTimeScale");
     app.register_props::<component::containers::anchor_box::AnchorProps>("AnchorProps");
     app.register_props::<component::containers::anchor_box::PivotBoxProps>("PivotBoxProps");
     app.register_props::<component::containers::content_box::ContentBoxProps>("ContentBoxProps");
@@ -351,6 +388,13 @@ pub fn setup(app: &mut Application) {
         "HorizontalBoxProps",
     );
     app.register_props::<component::containers::hidden_box::HiddenBoxProps>("HiddenBoxProps");
+    app.register_props::<component::containers::layout_transition_box::LayoutTransitionProps>(
+        "LayoutTransitionProps",
+    );
+    app.register_props::<component::containers::layout_transition_box::LayoutTransitionState>(
+        "LayoutTransitionState",
+    );
+    app.register_props::<component::containers::masonry_box::MasonryBoxProps>("MasonryBoxProps");
     app.register_props::<component::containers::scroll_box::ScrollBoxOwner>("ScrollBoxOwner");
     app.register_props::<component::containers::scroll_box::SideScrollbarsProps>(
         "SideScrollbarsProps",
@@ -366,8 +410,23 @@ pub fn setup(app: &mut Application) {
     app.register_props::<component::containers::tooltip_box::TooltipState>("TooltipState");
     app.register_props::<component::containers::variant_box::VariantBoxProps>("VariantBoxProps");
     app.register_props::<component::containers::vertical_box::VerticalBoxProps>("VerticalBoxProps");
+    app.register_props::<component::containers::virtual_list_box::VirtualListBoxProps>(
+        "VirtualListBoxProps",
+    );
+    app.register_props::<component::containers::virtual_list_box::VirtualListBoxItemProps>(
+        "VirtualListBoxItemProps",
+    );
+    app.register_props::<component::containers::virtual_list_box::VirtualListBoxOwner>(
+        "VirtualListBoxOwner",
+    );
+    app.register_props::<component::containers::virtual_list_box::VirtualListBoxState>(
+        "VirtualListBoxState",
+    );
     app.register_props::<component::containers::wrap_box::WrapBoxProps>("WrapBoxProps");
+    app.register_props::<component::custom_box::CustomBoxProps>("CustomBoxProps");
     app.register_props::<component::image_box::ImageBoxProps>("ImageBoxProps");
+    app.register_props::<component::image_box::SpriteAnimationProps>("SpriteAnimationProps");
+    app.register_props::<component::image_box::SpriteAnimationState>("SpriteAnimationState");
     app.register_props::<component::interactive::button::ButtonProps>("ButtonProps");
     app.register_props::<component::interactive::button::ButtonNotifyProps>("ButtonNotifyProps");
     app.register_props::<component::interactive::input_field::TextInputMode>("TextInputMode");
@@ -391,11 +450,16 @@ pub fn setup(app: &mut Application) {
     );
     app.register_props::<component::MessageForwardProps>("MessageForwardProps");
     app.register_props::<component::WidgetAlpha>("WidgetAlpha");
+    app.register_props::<component::rich_text_box::RichTextBoxProps>("RichTextBoxProps");
+    app.register_props::<component::rich_text_box::RichTextBoxNotifyProps>(
+        "RichTextBoxNotifyProps",
+    );
     app.register_props::<component::space_box::SpaceBoxProps>("SpaceBoxProps");
     app.register_props::<component::text_box::TextBoxProps>("TextBoxProps");
     app.register_props::<unit::content::ContentBoxItemLayout>("ContentBoxItemLayout");
     app.register_props::<unit::flex::FlexBoxItemLayout>("FlexBoxItemLayout");
     app.register_props::<unit::grid::GridBoxItemLayout>("GridBoxItemLayout");
+    app.register_props::<unit::masonry::MasonryBoxItemLayout>("MasonryBoxItemLayout");
 
     app.register_component("anchor_box", component::containers::anchor_box::anchor_box);
     app.register_component("pivot_box", component::containers::anchor_box::pivot_box);
@@ -417,6 +481,14 @@ pub fn setup(app: &mut Application) {
         component::containers::grid_box::nav_grid_box,
     );
     app.register_component("grid_box", component::containers::grid_box::grid_box);
+    app.register_component(
+        "nav_masonry_box",
+        component::containers::masonry_box::nav_masonry_box,
+    );
+    app.register_component(
+        "masonry_box",
+        component::containers::masonry_box::masonry_box,
+    );
     app.register_component(
         "nav_horizontal_box",
         component::containers::horizontal_box::nav_horizontal_box,
@@ -425,6 +497,10 @@ pub fn setup(app: &mut Application) {
         "horizontal_box",
         component::containers::horizontal_box::horizontal_box,
     );
+    app.register_component(
+        "layout_transition_box",
+        component::containers::layout_transition_box::layout_transition_box,
+    );
     app.register_component(
         "nav_scroll_box",
         component::containers::scroll_box::nav_scroll_box,
@@ -434,6 +510,10 @@ pub fn setup(app: &mut Application) {
         component::containers::scroll_box::nav_scroll_box_side_scrollbars,
     );
     app.register_component("portal_box", component::containers::portal_box::portal_box);
+    app.register_component(
+        "safe_area_box",
+        component::containers::safe_area_box::safe_area_box,
+    );
     app.register_component("size_box", component::containers::size_box::size_box);
     app.register_component(
         "nav_switch_box",
@@ -464,8 +544,18 @@ pub fn setup(app: &mut Application) {
         "vertical_box",
         component::containers::vertical_box::vertical_box,
     );
+    app.register_component(
+        "nav_virtual_list_box",
+        component::containers::virtual_list_box::nav_virtual_list_box,
+    );
+    app.register_component(
+        "virtual_list_box_item",
+        component::containers::virtual_list_box::virtual_list_box_item,
+    );
     app.register_component("wrap_box", component::containers::wrap_box::wrap_box);
+    app.register_component("custom_box", component::custom_box::custom_box);
     app.register_component("image_box", component::image_box::image_box);
+    app.register_component("sprite_image_box", component::image_box::sprite_image_box);
     app.register_component("button", component::interactive::button::button);
     app.register_component(
         "text_input",
@@ -475,6 +565,7 @@ pub fn setup(app: &mut Application) {
         "input_field",
         component::interactive::input_field::input_field,
     );
+    app.register_component("rich_text_box", component::rich_text_box::rich_text_box);
     app.register_component("space_box", component::space_box::space_box);
     app.register_component("text_box", component::text_box::text_box);
 }
@@ -3,10 +3,10 @@ use crate::{
     widget::{
         node::{WidgetNode, WidgetNodePrefab},
         unit::{WidgetUnit, WidgetUnitData},
-        utils::{Rect, Transform, Vec2},
+        utils::{LayoutDirection, Rect, SizeConstraints, Transform, Vec2},
         WidgetId,
     },
-    PrefabValue, PropsData, Scalar,
+    Integer, PrefabValue, PropsData, Scalar,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -28,9 +28,16 @@ pub struct ContentBoxItemLayout {
     /// The amount to offset the item from where it would otherwise be laid out
     #[serde(default)]
     pub offset: Vec2,
-    /// The "Z" depth of the item
+    /// The "Z" depth of the item, used to order items of the same layer within content box
     #[serde(default)]
     pub depth: Scalar,
+    /// Minimal and maximal size of the item (excluding margins)
+    #[serde(default)]
+    pub constraints: SizeConstraints,
+    /// Render layer of the item relative to its container, items (with their content) on higher
+    /// layers are rendered above and receive pointer input before items on lower layers
+    #[serde(default)]
+    pub z_index: Integer,
 }
 
 impl ContentBoxItemLayout {
@@ -52,8 +59,171 @@ impl Default for ContentBoxItemLayout {
             align: Default::default(),
             offset: Default::default(),
             depth: 0.0,
+            constraints: Default::default(),
+            z_index: 0,
+        }
+    }
+}
+
+/// Edge or dimension of content box item (or of content box itself) used by layout constraints
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayoutConstraintVariable {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Width,
+    Height,
+    CenterX,
+    CenterY,
+}
+
+impl Default for LayoutConstraintVariable {
+    fn default() -> Self {
+        Self::Left
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayoutConstraintRelation {
+    Equal,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+impl Default for LayoutConstraintRelation {
+    fn default() -> Self {
+        Self::Equal
+    }
+}
+
+/// Priority of layout constraint, non-required constraints can be broken when they conflict with
+/// stronger ones
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LayoutConstraintStrength {
+    Required,
+    Strong,
+    Medium,
+    Weak,
+    /// (strong, medium, weak) weights
+    Custom(Scalar, Scalar, Scalar),
+}
+
+impl Default for LayoutConstraintStrength {
+    fn default() -> Self {
+        Self::Required
+    }
+}
+
+/// Variable of content box item multiplied by coefficient
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutConstraintTerm {
+    /// Key of content box item widget, empty key refers to content box itself
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub item: String,
+    #[serde(default)]
+    pub variable: LayoutConstraintVariable,
+    #[serde(default = "LayoutConstraintTerm::default_coefficient")]
+    pub coefficient: Scalar,
+}
+
+impl Default for LayoutConstraintTerm {
+    fn default() -> Self {
+        Self {
+            item: Default::default(),
+            variable: Default::default(),
+            coefficient: Self::default_coefficient(),
+        }
+    }
+}
+
+impl LayoutConstraintTerm {
+    fn default_coefficient() -> Scalar {
+        1.0
+    }
+
+    pub fn new(item: impl ToString, variable: LayoutConstraintVariable) -> Self {
+        Self {
+            item: item.to_string(),
+            variable,
+            coefficient: Self::default_coefficient(),
         }
     }
+
+    pub fn container(variable: LayoutConstraintVariable) -> Self {
+        Self {
+            item: Default::default(),
+            variable,
+            coefficient: Self::default_coefficient(),
+        }
+    }
+
+    pub fn scaled(mut self, coefficient: Scalar) -> Self {
+        self.coefficient *= coefficient;
+        self
+    }
+}
+
+/// Linear constraint between content box items: `sum(lhs) <relation> sum(rhs) + constant`
+///
+/// Constraints referencing keys of items missing from content box are skipped, so are required
+/// constraints conflicting with the ones added before them (these get reported by
+/// [`DefaultLayoutEngine::rejected_constraints`]). Minimal size of content box is calculated from
+/// items anchors only, ignoring constraints.
+///
+/// [`DefaultLayoutEngine::rejected_constraints`]: crate::layout::default_layout_engine::DefaultLayoutEngine::rejected_constraints
+///
+/// ```
+/// # use raui_core::prelude::*;
+/// // "a.right == b.left - 8"
+/// let constraint = LayoutConstraint::new(
+///     vec![LayoutConstraintTerm::new("a", LayoutConstraintVariable::Right)],
+///     LayoutConstraintRelation::Equal,
+///     vec![LayoutConstraintTerm::new("b", LayoutConstraintVariable::Left)],
+/// )
+/// .with_constant(-8.0);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutConstraint {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lhs: Vec<LayoutConstraintTerm>,
+    #[serde(default)]
+    pub relation: LayoutConstraintRelation,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rhs: Vec<LayoutConstraintTerm>,
+    #[serde(default)]
+    pub constant: Scalar,
+    #[serde(default)]
+    pub strength: LayoutConstraintStrength,
+}
+
+impl LayoutConstraint {
+    pub fn new(
+        lhs: Vec<LayoutConstraintTerm>,
+        relation: LayoutConstraintRelation,
+        rhs: Vec<LayoutConstraintTerm>,
+    ) -> Self {
+        Self {
+            lhs,
+            relation,
+            rhs,
+            constant: 0.0,
+            strength: Default::default(),
+        }
+    }
+
+    pub fn with_constant(mut self, constant: Scalar) -> Self {
+        self.constant = constant;
+        self
+    }
+
+    pub fn with_strength(mut self, strength: LayoutConstraintStrength) -> Self {
+        self.strength = strength;
+        self
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -92,7 +262,14 @@ pub struct ContentBox {
     #[serde(default)]
     pub clipping: bool,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
     pub transform: Transform,
+    /// When not empty, items get laid out by solving these constraints instead of their anchors
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<LayoutConstraint>,
 }
 
 impl WidgetUnitData for ContentBox {
@@ -113,7 +290,9 @@ impl TryFrom<ContentBoxNode> for ContentBox {
             id,
             items,
             clipping,
+            layout_direction,
             transform,
+            constraints,
             ..
         } = node;
         let items = items
@@ -124,7 +303,9 @@ impl TryFrom<ContentBoxNode> for ContentBox {
             id,
             items,
             clipping,
+            layout_direction,
             transform,
+            constraints,
         })
     }
 }
@@ -135,7 +316,9 @@ pub struct ContentBoxNode {
     pub props: Props,
     pub items: Vec<ContentBoxItemNode>,
     pub clipping: bool,
+    pub layout_direction: Option<LayoutDirection>,
     pub transform: Transform,
+    pub constraints: Vec<LayoutConstraint>,
 }
 
 impl ContentBoxNode {
@@ -166,7 +349,13 @@ pub(crate) struct ContentBoxNodePrefab {
     #[serde(default)]
     pub clipping: bool,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
     pub transform: Transform,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<LayoutConstraint>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -0,0 +1,86 @@
+use crate::{
+    props::Props,
+    widget::{node::WidgetNode, unit::WidgetUnitData, utils::Transform, WidgetId},
+    PrefabValue,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// User-defined unit identified by its type name, with its data stored in props.
+///
+/// Layout engines measure it with measurement registered for its type name, and renderers
+/// render it with their custom unit hooks.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CustomBox {
+    #[serde(default)]
+    pub id: WidgetId,
+    #[serde(default)]
+    pub type_name: String,
+    /// Data of custom unit, not serialized since props can be serialized only with props
+    /// registry (prefabs of custom box nodes keep them)
+    #[serde(skip)]
+    pub props: Props,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+impl WidgetUnitData for CustomBox {
+    fn id(&self) -> &WidgetId {
+        &self.id
+    }
+}
+
+impl TryFrom<CustomBoxNode> for CustomBox {
+    type Error = ();
+
+    fn try_from(node: CustomBoxNode) -> Result<Self, Self::Error> {
+        let CustomBoxNode {
+            id,
+            props,
+            type_name,
+            transform,
+        } = node;
+        Ok(Self {
+            id,
+            type_name,
+            props,
+            transform,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CustomBoxNode {
+    pub id: WidgetId,
+    pub props: Props,
+    pub type_name: String,
+    pub transform: Transform,
+}
+
+impl CustomBoxNode {
+    pub fn remap_props<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Props) -> Props,
+    {
+        let props = std::mem::take(&mut self.props);
+        self.props = (f)(props);
+    }
+}
+
+impl From<CustomBoxNode> for WidgetNode {
+    fn from(data: CustomBoxNode) -> Self {
+        Self::Unit(data.into())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct CustomBoxNodePrefab {
+    #[serde(default)]
+    pub id: WidgetId,
+    #[serde(default)]
+    pub props: PrefabValue,
+    #[serde(default)]
+    pub type_name: String,
+    #[serde(default)]
+    pub transform: Transform,
+}
@@ -3,10 +3,10 @@ use crate::{
     widget::{
         node::{WidgetNode, WidgetNodePrefab},
         unit::{WidgetUnit, WidgetUnitData},
-        utils::{Rect, Transform},
+        utils::{LayoutDirection, Rect, SizeConstraints, Transform},
         WidgetId,
     },
-    PrefabValue, PropsData, Scalar,
+    Integer, PrefabValue, PropsData, Scalar,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -28,6 +28,17 @@ pub struct FlexBoxItemLayout {
     pub margin: Rect,
     #[serde(default)]
     pub align: Scalar,
+    /// Aligns item baseline with baselines of other baseline aligned items in the same line of
+    /// horizontal flex box instead of using `align`
+    #[serde(default)]
+    pub align_baseline: bool,
+    /// Minimal and maximal size of the item (excluding margins)
+    #[serde(default)]
+    pub constraints: SizeConstraints,
+    /// Render layer of the item relative to its container, items (with their content) on higher
+    /// layers are rendered above and receive pointer input before items on lower layers
+    #[serde(default)]
+    pub z_index: Integer,
 }
 
 impl FlexBoxItemLayout {
@@ -62,6 +73,9 @@ impl Default for FlexBoxItemLayout {
             shrink: Self::default_shrink(),
             margin: Default::default(),
             align: 0.0,
+            align_baseline: false,
+            constraints: Default::default(),
+            z_index: 0,
         }
     }
 }
@@ -124,6 +138,98 @@ impl FlexBoxDirection {
     }
 }
 
+/// Distribution of space left along main axis between items
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlexBoxJustifyContent {
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+impl Default for FlexBoxJustifyContent {
+    fn default() -> Self {
+        Self::Start
+    }
+}
+
+impl FlexBoxJustifyContent {
+    /// Returns offset of first item and extra space put between items, for given space left
+    /// and number of items.
+    pub fn spacing(&self, space_left: Scalar, count: usize) -> (Scalar, Scalar) {
+        let space_left = space_left.max(0.0);
+        match self {
+            Self::Start => (0.0, 0.0),
+            Self::Center => (space_left * 0.5, 0.0),
+            Self::End => (space_left, 0.0),
+            Self::SpaceBetween => {
+                if count > 1 {
+                    (0.0, space_left / (count - 1) as Scalar)
+                } else {
+                    (0.0, 0.0)
+                }
+            }
+            Self::SpaceAround => {
+                if count > 0 {
+                    let space = space_left / count as Scalar;
+                    (space * 0.5, space)
+                } else {
+                    (0.0, 0.0)
+                }
+            }
+            Self::SpaceEvenly => {
+                let space = space_left / (count + 1) as Scalar;
+                (space, space)
+            }
+        }
+    }
+}
+
+/// Distribution of space left along cross axis between lines of wrapping flex box
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlexBoxAlignContent {
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+    /// Lines grow evenly to fill space left
+    Stretch,
+}
+
+impl Default for FlexBoxAlignContent {
+    fn default() -> Self {
+        Self::Start
+    }
+}
+
+impl FlexBoxAlignContent {
+    /// Returns offset of first line, extra space put between lines and extra size added to each
+    /// line, for given space left and number of lines.
+    pub fn spacing(&self, space_left: Scalar, count: usize) -> (Scalar, Scalar, Scalar) {
+        let justify = match self {
+            Self::Start => FlexBoxJustifyContent::Start,
+            Self::Center => FlexBoxJustifyContent::Center,
+            Self::End => FlexBoxJustifyContent::End,
+            Self::SpaceBetween => FlexBoxJustifyContent::SpaceBetween,
+            Self::SpaceAround => FlexBoxJustifyContent::SpaceAround,
+            Self::SpaceEvenly => FlexBoxJustifyContent::SpaceEvenly,
+            Self::Stretch => {
+                return if count > 0 {
+                    (0.0, 0.0, space_left.max(0.0) / count as Scalar)
+                } else {
+                    (0.0, 0.0, 0.0)
+                };
+            }
+        };
+        let (offset, between) = justify.spacing(space_left, count);
+        (offset, between, 0.0)
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FlexBox {
     #[serde(default)]
@@ -137,6 +243,20 @@ pub struct FlexBox {
     pub separation: Scalar,
     #[serde(default)]
     pub wrap: bool,
+    /// Lines of wrapping flex box go from cross axis end to its start
+    #[serde(default)]
+    pub wrap_reverse: bool,
+    /// Separation between lines of wrapping flex box, `separation` is used when not set
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cross_separation: Option<Scalar>,
+    #[serde(default)]
+    pub justify_content: FlexBoxJustifyContent,
+    #[serde(default)]
+    pub align_content: FlexBoxAlignContent,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
     #[serde(default)]
     pub transform: Transform,
 }
@@ -161,6 +281,11 @@ impl TryFrom<FlexBoxNode> for FlexBox {
             direction,
             separation,
             wrap,
+            wrap_reverse,
+            cross_separation,
+            justify_content,
+            align_content,
+            layout_direction,
             transform,
             ..
         } = node;
@@ -174,6 +299,11 @@ impl TryFrom<FlexBoxNode> for FlexBox {
             direction,
             separation,
             wrap,
+            wrap_reverse,
+            cross_separation,
+            justify_content,
+            align_content,
+            layout_direction,
             transform,
         })
     }
@@ -187,6 +317,11 @@ pub struct FlexBoxNode {
     pub direction: FlexBoxDirection,
     pub separation: Scalar,
     pub wrap: bool,
+    pub wrap_reverse: bool,
+    pub cross_separation: Option<Scalar>,
+    pub justify_content: FlexBoxJustifyContent,
+    pub align_content: FlexBoxAlignContent,
+    pub layout_direction: Option<LayoutDirection>,
     pub transform: Transform,
 }
 
@@ -222,6 +357,18 @@ pub(crate) struct FlexBoxNodePrefab {
     #[serde(default)]
     pub wrap: bool,
     #[serde(default)]
+    pub wrap_reverse: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cross_separation: Option<Scalar>,
+    #[serde(default)]
+    pub justify_content: FlexBoxJustifyContent,
+    #[serde(default)]
+    pub align_content: FlexBoxAlignContent,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
     pub transform: Transform,
 }
 
@@ -3,18 +3,53 @@ use crate::{
     widget::{
         node::{WidgetNode, WidgetNodePrefab},
         unit::{WidgetUnit, WidgetUnitData},
-        utils::{IntRect, Rect, Transform},
+        utils::{IntRect, LayoutDirection, Rect, SizeConstraints, Transform},
         WidgetId,
     },
-    PrefabValue, PropsData, Scalar,
+    Integer, PrefabValue, PropsData, Scalar,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 
+/// Size of grid column or row track
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GridBoxTrackSize {
+    /// Exact size in layout units
+    Exact(Scalar),
+    /// Share of space left after exact and content tracks got sized
+    Fraction(Scalar),
+    /// Largest minimal size of items placed in this track
+    Content,
+    /// Single fraction track clamped between minimal and maximal size
+    MinMax(Scalar, Scalar),
+}
+
+impl Default for GridBoxTrackSize {
+    fn default() -> Self {
+        Self::Fraction(1.0)
+    }
+}
+
+impl GridBoxTrackSize {
+    /// Weight of track in distribution of space left, zero for non-flexible tracks
+    pub fn fraction(&self) -> Scalar {
+        match self {
+            Self::Fraction(v) => v.max(0.0),
+            Self::MinMax(_, _) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    pub fn is_flexible(&self) -> bool {
+        self.fraction() > 0.0
+    }
+}
+
 #[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
 #[props_data(crate::props::PropsData)]
 #[prefab(crate::Prefab)]
 pub struct GridBoxItemLayout {
+    /// Cells range occupied by item, item gets auto placed when this range is empty
     #[serde(default)]
     pub space_occupancy: IntRect,
     #[serde(default)]
@@ -23,6 +58,25 @@ pub struct GridBoxItemLayout {
     pub horizontal_align: Scalar,
     #[serde(default)]
     pub vertical_align: Scalar,
+    /// Number of columns spanned by auto placed item (one when zero)
+    #[serde(default)]
+    pub column_span: usize,
+    /// Number of rows spanned by auto placed item (one when zero)
+    #[serde(default)]
+    pub row_span: usize,
+    /// Minimal and maximal size of the item (excluding margins)
+    #[serde(default)]
+    pub constraints: SizeConstraints,
+    /// Render layer of the item relative to its container, items (with their content) on higher
+    /// layers are rendered above and receive pointer input before items on lower layers
+    #[serde(default)]
+    pub z_index: Integer,
+}
+
+impl GridBoxItemLayout {
+    pub fn is_auto_placed(&self) -> bool {
+        self.space_occupancy.width() <= 0 || self.space_occupancy.height() <= 0
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -58,11 +112,27 @@ pub struct GridBox {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<GridBoxItem>,
+    /// Number of uniform columns, used when there are no column tracks
     #[serde(default)]
     pub cols: usize,
+    /// Number of uniform rows, used when there are no row tracks
     #[serde(default)]
     pub rows: usize,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub column_tracks: Vec<GridBoxTrackSize>,
+    /// Rows tracks, rows added for auto placed items beyond them are content sized
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub row_tracks: Vec<GridBoxTrackSize>,
+    #[serde(default)]
+    pub column_gap: Scalar,
+    #[serde(default)]
+    pub row_gap: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
     pub transform: Transform,
 }
 
@@ -85,6 +155,11 @@ impl TryFrom<GridBoxNode> for GridBox {
             items,
             cols,
             rows,
+            column_tracks,
+            row_tracks,
+            column_gap,
+            row_gap,
+            layout_direction,
             transform,
             ..
         } = node;
@@ -97,6 +172,11 @@ impl TryFrom<GridBoxNode> for GridBox {
             items,
             cols,
             rows,
+            column_tracks,
+            row_tracks,
+            column_gap,
+            row_gap,
+            layout_direction,
             transform,
         })
     }
@@ -109,6 +189,11 @@ pub struct GridBoxNode {
     pub items: Vec<GridBoxItemNode>,
     pub cols: usize,
     pub rows: usize,
+    pub column_tracks: Vec<GridBoxTrackSize>,
+    pub row_tracks: Vec<GridBoxTrackSize>,
+    pub column_gap: Scalar,
+    pub row_gap: Scalar,
+    pub layout_direction: Option<LayoutDirection>,
     pub transform: Transform,
 }
 
@@ -142,6 +227,19 @@ pub(crate) struct GridBoxNodePrefab {
     #[serde(default)]
     pub rows: usize,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub column_tracks: Vec<GridBoxTrackSize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub row_tracks: Vec<GridBoxTrackSize>,
+    #[serde(default)]
+    pub column_gap: Scalar,
+    #[serde(default)]
+    pub row_gap: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
     pub transform: Transform,
 }
 
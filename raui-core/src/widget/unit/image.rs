@@ -3,7 +3,7 @@ use crate::{
     widget::{
         node::WidgetNode,
         unit::WidgetUnitData,
-        utils::{Color, Rect, Transform},
+        utils::{lerp, Color, Rect, Transform, Vec2},
         WidgetId,
     },
     PrefabValue, Scalar,
@@ -109,11 +109,197 @@ pub struct ImageBoxProcedural {
     pub parameters: HashMap<String, Scalar>,
 }
 
+/// Radii of rectangle corners
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageBoxCornerRadius {
+    #[serde(default)]
+    pub top_left: Scalar,
+    #[serde(default)]
+    pub top_right: Scalar,
+    #[serde(default)]
+    pub bottom_right: Scalar,
+    #[serde(default)]
+    pub bottom_left: Scalar,
+}
+
+impl From<Scalar> for ImageBoxCornerRadius {
+    fn from(v: Scalar) -> Self {
+        Self {
+            top_left: v,
+            top_right: v,
+            bottom_right: v,
+            bottom_left: v,
+        }
+    }
+}
+
+/// Geometry of vector shape, fitted into image box
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ImageBoxShapeKind {
+    /// Rectangle filling image box, with optionally rounded corners
+    Rectangle {
+        #[serde(default)]
+        corner_radius: ImageBoxCornerRadius,
+    },
+    /// Ellipse inscribed in image box
+    Ellipse,
+    /// Closed polygon with points given in image box space fractions (`<0;1>`)
+    Polygon(Vec<Vec2>),
+    /// Open line with points given in image box space fractions (`<0;1>`)
+    Polyline(Vec<Vec2>),
+    /// Part of ellipse inscribed in image box between angles (in radians, clockwise from right)
+    Arc {
+        #[serde(default)]
+        start_angle: Scalar,
+        #[serde(default)]
+        end_angle: Scalar,
+    },
+}
+
+impl Default for ImageBoxShapeKind {
+    fn default() -> Self {
+        Self::Rectangle {
+            corner_radius: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub struct ImageBoxShapeBorder {
+    #[serde(default)]
+    pub width: Scalar,
+    #[serde(default)]
+    pub color: Color,
+}
+
+/// Vector shape filled with color and outlined with border. Borders of rectangles, ellipses and
+/// arcs are placed inside their edges, borders of polygons are centered on their edges. Polylines
+/// and arcs are lines drawn only with border width and color.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ImageBoxShape {
+    #[serde(default)]
+    pub kind: ImageBoxShapeKind,
+    #[serde(default)]
+    pub color: Color,
+    #[serde(default)]
+    pub border: ImageBoxShapeBorder,
+}
+
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub struct ImageBoxGradientStop {
+    /// Position along gradient (`<0;1>`)
+    #[serde(default)]
+    pub position: Scalar,
+    #[serde(default)]
+    pub color: Color,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum ImageBoxGradientKind {
+    /// Gradient spanning whole image box along direction of angle (in radians, clockwise from
+    /// right)
+    Linear {
+        #[serde(default)]
+        angle: Scalar,
+    },
+    /// Gradient going from center (in image box space fractions) to radius (fraction of image box
+    /// size)
+    Radial {
+        #[serde(default = "ImageBoxGradientKind::default_center")]
+        center: Vec2,
+        #[serde(default = "ImageBoxGradientKind::default_radius")]
+        radius: Scalar,
+    },
+}
+
+impl Default for ImageBoxGradientKind {
+    fn default() -> Self {
+        Self::Linear {
+            angle: Default::default(),
+        }
+    }
+}
+
+impl ImageBoxGradientKind {
+    fn default_center() -> Vec2 {
+        Vec2 { x: 0.5, y: 0.5 }
+    }
+
+    fn default_radius() -> Scalar {
+        0.5
+    }
+}
+
+/// Gradient fill, with stops sorted by their positions
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ImageBoxGradient {
+    #[serde(default)]
+    pub kind: ImageBoxGradientKind,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stops: Vec<ImageBoxGradientStop>,
+    #[serde(default)]
+    pub scaling: ImageBoxImageScaling,
+}
+
+impl ImageBoxGradient {
+    /// Color at given position along gradient.
+    pub fn sample(&self, position: Scalar) -> Color {
+        let index = self
+            .stops
+            .iter()
+            .position(|stop| stop.position >= position)
+            .unwrap_or(self.stops.len());
+        match (self.stops.get(index.wrapping_sub(1)), self.stops.get(index)) {
+            (Some(from), Some(to)) if to.position > from.position => {
+                let factor = (position - from.position) / (to.position - from.position);
+                Color {
+                    r: lerp(from.color.r, to.color.r, factor),
+                    g: lerp(from.color.g, to.color.g, factor),
+                    b: lerp(from.color.b, to.color.b, factor),
+                    a: lerp(from.color.a, to.color.a, factor),
+                }
+            }
+            (_, Some(stop)) | (Some(stop), None) => stop.color,
+            (None, None) => Default::default(),
+        }
+    }
+
+    /// Color at point in local space of image box of given size.
+    pub fn color_at(&self, point: Vec2, size: Vec2) -> Color {
+        let position = match self.kind {
+            ImageBoxGradientKind::Linear { angle } => {
+                let (y, x) = angle.sin_cos();
+                let length = (size.x * x).abs() + (size.y * y).abs();
+                if length > 0.0 {
+                    let dx = point.x - size.x * 0.5;
+                    let dy = point.y - size.y * 0.5;
+                    (dx * x + dy * y) / length + 0.5
+                } else {
+                    0.0
+                }
+            }
+            ImageBoxGradientKind::Radial { center, radius } => {
+                if radius > 0.0 && size.x > 0.0 && size.y > 0.0 {
+                    let dx = point.x / size.x - center.x;
+                    let dy = point.y / size.y - center.y;
+                    (dx * dx + dy * dy).sqrt() / radius
+                } else {
+                    0.0
+                }
+            }
+        };
+        self.sample(position)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ImageBoxMaterial {
     Color(ImageBoxColor),
     Image(ImageBoxImage),
     Procedural(ImageBoxProcedural),
+    Shape(ImageBoxShape),
+    Gradient(ImageBoxGradient),
 }
 
 impl Default for ImageBoxMaterial {
@@ -124,6 +310,10 @@ impl Default for ImageBoxMaterial {
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum ImageBoxSizeValue {
+    /// Native size of image (see [`crate::layout::ImageMeasurementEngine`])
+    Content,
+    /// Native size of image multiplied by given scale
+    ContentScaled(Scalar),
     Fill,
     Exact(Scalar),
 }
@@ -134,6 +324,15 @@ impl Default for ImageBoxSizeValue {
     }
 }
 
+impl ImageBoxSizeValue {
+    pub fn content_scale(&self) -> Scalar {
+        match self {
+            Self::ContentScaled(v) => *v,
+            _ => 1.0,
+        }
+    }
+}
+
 #[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
 pub struct ImageBoxAspectRatio {
     #[serde(default)]
@@ -157,6 +356,9 @@ pub struct ImageBox {
     pub content_keep_aspect_ratio: Option<ImageBoxAspectRatio>,
     #[serde(default)]
     pub material: ImageBoxMaterial,
+    /// Distance of text baseline from image bottom edge, used by baseline alignment
+    #[serde(default)]
+    pub baseline_offset: Scalar,
     #[serde(default)]
     pub transform: Transform,
 }
@@ -177,6 +379,7 @@ impl TryFrom<ImageBoxNode> for ImageBox {
             height,
             content_keep_aspect_ratio,
             material,
+            baseline_offset,
             transform,
             ..
         } = node;
@@ -186,6 +389,7 @@ impl TryFrom<ImageBoxNode> for ImageBox {
             height,
             content_keep_aspect_ratio,
             material,
+            baseline_offset,
             transform,
         })
     }
@@ -199,6 +403,7 @@ pub struct ImageBoxNode {
     pub height: ImageBoxSizeValue,
     pub content_keep_aspect_ratio: Option<ImageBoxAspectRatio>,
     pub material: ImageBoxMaterial,
+    pub baseline_offset: Scalar,
     pub transform: Transform,
 }
 
@@ -233,6 +438,9 @@ pub(crate) struct ImageBoxNodePrefab {
     pub content_keep_aspect_ratio: Option<ImageBoxAspectRatio>,
     #[serde(default)]
     pub material: ImageBoxMaterial,
+    /// Distance of text baseline from image bottom edge, used by baseline alignment
+    #[serde(default)]
+    pub baseline_offset: Scalar,
     #[serde(default)]
     pub transform: Transform,
 }
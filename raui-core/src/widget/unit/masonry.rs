@@ -0,0 +1,195 @@
+use crate::{
+    props::Props,
+    widget::{
+        node::{WidgetNode, WidgetNodePrefab},
+        unit::{WidgetUnit, WidgetUnitData},
+        utils::{LayoutDirection, Rect, SizeConstraints, Transform},
+        WidgetId,
+    },
+    Integer, PrefabValue, PropsData, Scalar,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct MasonryBoxItemLayout {
+    #[serde(default)]
+    pub margin: Rect,
+    /// Minimal and maximal size of the item (excluding margins)
+    #[serde(default)]
+    pub constraints: SizeConstraints,
+    /// Render layer of the item relative to its container, items (with their content) on higher
+    /// layers are rendered above and receive pointer input before items on lower layers
+    #[serde(default)]
+    pub z_index: Integer,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MasonryBoxItem {
+    #[serde(default)]
+    pub slot: WidgetUnit,
+    #[serde(default)]
+    pub layout: MasonryBoxItemLayout,
+}
+
+impl TryFrom<MasonryBoxItemNode> for MasonryBoxItem {
+    type Error = ();
+
+    fn try_from(node: MasonryBoxItemNode) -> Result<Self, Self::Error> {
+        let MasonryBoxItemNode { slot, layout } = node;
+        Ok(Self {
+            slot: WidgetUnit::try_from(slot)?,
+            layout,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MasonryBoxItemNode {
+    pub slot: WidgetNode,
+    pub layout: MasonryBoxItemLayout,
+}
+
+/// Items are put one by one into currently shortest column.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MasonryBox {
+    #[serde(default)]
+    pub id: WidgetId,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<MasonryBoxItem>,
+    /// Number of columns, when zero it is computed from minimal column width
+    #[serde(default)]
+    pub columns: usize,
+    /// Minimal width of column, used when columns count is zero
+    #[serde(default)]
+    pub min_column_width: Scalar,
+    #[serde(default)]
+    pub column_gap: Scalar,
+    #[serde(default)]
+    pub row_gap: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+impl MasonryBox {
+    /// Number of columns fitting in given width (at least one).
+    pub fn columns_count(&self, width: Scalar) -> usize {
+        if self.columns > 0 {
+            self.columns
+        } else if self.min_column_width > 0.0 {
+            let count = (width + self.column_gap) / (self.min_column_width + self.column_gap);
+            (count.max(0.0) as usize).max(1)
+        } else {
+            1
+        }
+    }
+}
+
+impl WidgetUnitData for MasonryBox {
+    fn id(&self) -> &WidgetId {
+        &self.id
+    }
+
+    fn get_children(&self) -> Vec<&WidgetUnit> {
+        self.items.iter().map(|item| &item.slot).collect()
+    }
+}
+
+impl TryFrom<MasonryBoxNode> for MasonryBox {
+    type Error = ();
+
+    fn try_from(node: MasonryBoxNode) -> Result<Self, Self::Error> {
+        let MasonryBoxNode {
+            id,
+            items,
+            columns,
+            min_column_width,
+            column_gap,
+            row_gap,
+            layout_direction,
+            transform,
+            ..
+        } = node;
+        let items = items
+            .into_iter()
+            .map(MasonryBoxItem::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            id,
+            items,
+            columns,
+            min_column_width,
+            column_gap,
+            row_gap,
+            layout_direction,
+            transform,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MasonryBoxNode {
+    pub id: WidgetId,
+    pub props: Props,
+    pub items: Vec<MasonryBoxItemNode>,
+    pub columns: usize,
+    pub min_column_width: Scalar,
+    pub column_gap: Scalar,
+    pub row_gap: Scalar,
+    pub layout_direction: Option<LayoutDirection>,
+    pub transform: Transform,
+}
+
+impl MasonryBoxNode {
+    pub fn remap_props<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Props) -> Props,
+    {
+        let props = std::mem::take(&mut self.props);
+        self.props = (f)(props);
+    }
+}
+
+impl From<MasonryBoxNode> for WidgetNode {
+    fn from(data: MasonryBoxNode) -> Self {
+        Self::Unit(data.into())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct MasonryBoxNodePrefab {
+    #[serde(default)]
+    pub id: WidgetId,
+    #[serde(default)]
+    pub props: PrefabValue,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<MasonryBoxItemNodePrefab>,
+    #[serde(default)]
+    pub columns: usize,
+    #[serde(default)]
+    pub min_column_width: Scalar,
+    #[serde(default)]
+    pub column_gap: Scalar,
+    #[serde(default)]
+    pub row_gap: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_direction: Option<LayoutDirection>,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct MasonryBoxItemNodePrefab {
+    #[serde(default)]
+    pub slot: WidgetNodePrefab,
+    #[serde(default)]
+    pub layout: MasonryBoxItemLayout,
+}
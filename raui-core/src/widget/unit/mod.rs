@@ -1,9 +1,12 @@
 pub mod area;
 pub mod content;
+pub mod custom;
 pub mod flex;
 pub mod grid;
 pub mod image;
+pub mod masonry;
 pub mod portal;
+pub mod rich_text;
 pub mod size;
 pub mod text;
 
@@ -14,10 +17,13 @@ use crate::{
         unit::{
             area::{AreaBox, AreaBoxNode, AreaBoxNodePrefab},
             content::{ContentBox, ContentBoxNode, ContentBoxNodePrefab},
+            custom::{CustomBox, CustomBoxNode, CustomBoxNodePrefab},
             flex::{FlexBox, FlexBoxNode, FlexBoxNodePrefab},
             grid::{GridBox, GridBoxNode, GridBoxNodePrefab},
             image::{ImageBox, ImageBoxNode, ImageBoxNodePrefab},
+            masonry::{MasonryBox, MasonryBoxNode, MasonryBoxNodePrefab},
             portal::{PortalBox, PortalBoxNode, PortalBoxNodePrefab},
+            rich_text::{RichTextBox, RichTextBoxNode, RichTextBoxNodePrefab},
             size::{SizeBox, SizeBoxNode, SizeBoxNodePrefab},
             text::{TextBox, TextBoxNode, TextBoxNodePrefab},
         },
@@ -44,6 +50,11 @@ pub trait WidgetUnitData {
     }
 }
 
+/// Unit of rendered widget tree.
+///
+/// Serialization keeps everything but props of [`CustomBox`] units, since props can be
+/// serialized only through application props registry - use `Application::serialize_node` on
+/// unit nodes to keep them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WidgetUnit {
     None,
@@ -52,9 +63,12 @@ pub enum WidgetUnit {
     ContentBox(ContentBox),
     FlexBox(FlexBox),
     GridBox(GridBox),
+    MasonryBox(MasonryBox),
     SizeBox(SizeBox),
     ImageBox(ImageBox),
     TextBox(TextBox),
+    RichTextBox(RichTextBox),
+    CustomBox(CustomBox),
 }
 
 impl Default for WidgetUnit {
@@ -80,9 +94,24 @@ impl WidgetUnit {
             Self::ContentBox(v) => Some(v as &dyn WidgetUnitData),
             Self::FlexBox(v) => Some(v as &dyn WidgetUnitData),
             Self::GridBox(v) => Some(v as &dyn WidgetUnitData),
+            Self::MasonryBox(v) => Some(v as &dyn WidgetUnitData),
             Self::SizeBox(v) => Some(v as &dyn WidgetUnitData),
             Self::ImageBox(v) => Some(v as &dyn WidgetUnitData),
             Self::TextBox(v) => Some(v as &dyn WidgetUnitData),
+            Self::RichTextBox(v) => Some(v as &dyn WidgetUnitData),
+            Self::CustomBox(v) => Some(v as &dyn WidgetUnitData),
+        }
+    }
+
+    /// Finds unit with given id in this unit subtree.
+    pub fn find(&self, id: &WidgetId) -> Option<&WidgetUnit> {
+        let data = self.as_data()?;
+        if data.id() == id {
+            Some(self)
+        } else {
+            data.get_children()
+                .into_iter()
+                .find_map(|child| child.find(id))
         }
     }
 
@@ -109,9 +138,14 @@ impl TryFrom<WidgetUnitNode> for WidgetUnit {
             WidgetUnitNode::ContentBox(n) => Ok(WidgetUnit::ContentBox(ContentBox::try_from(n)?)),
             WidgetUnitNode::FlexBox(n) => Ok(WidgetUnit::FlexBox(FlexBox::try_from(n)?)),
             WidgetUnitNode::GridBox(n) => Ok(WidgetUnit::GridBox(GridBox::try_from(n)?)),
+            WidgetUnitNode::MasonryBox(n) => Ok(WidgetUnit::MasonryBox(MasonryBox::try_from(n)?)),
             WidgetUnitNode::SizeBox(n) => Ok(WidgetUnit::SizeBox(SizeBox::try_from(n)?)),
             WidgetUnitNode::ImageBox(n) => Ok(WidgetUnit::ImageBox(ImageBox::try_from(n)?)),
             WidgetUnitNode::TextBox(n) => Ok(WidgetUnit::TextBox(TextBox::try_from(n)?)),
+            WidgetUnitNode::RichTextBox(n) => {
+                Ok(WidgetUnit::RichTextBox(RichTextBox::try_from(n)?))
+            }
+            WidgetUnitNode::CustomBox(n) => Ok(WidgetUnit::CustomBox(CustomBox::try_from(n)?)),
         }
     }
 }
@@ -136,9 +170,12 @@ pub enum WidgetUnitNode {
     ContentBox(ContentBoxNode),
     FlexBox(FlexBoxNode),
     GridBox(GridBoxNode),
+    MasonryBox(MasonryBoxNode),
     SizeBox(SizeBoxNode),
     ImageBox(ImageBoxNode),
     TextBox(TextBoxNode),
+    RichTextBox(RichTextBoxNode),
+    CustomBox(CustomBoxNode),
 }
 
 impl Default for WidgetUnitNode {
@@ -162,9 +199,12 @@ impl WidgetUnitNode {
             Self::ContentBox(v) => Some(&v.props),
             Self::FlexBox(v) => Some(&v.props),
             Self::GridBox(v) => Some(&v.props),
+            Self::MasonryBox(v) => Some(&v.props),
             Self::SizeBox(v) => Some(&v.props),
             Self::ImageBox(v) => Some(&v.props),
             Self::TextBox(v) => Some(&v.props),
+            Self::RichTextBox(v) => Some(&v.props),
+            Self::CustomBox(v) => Some(&v.props),
         }
     }
 
@@ -174,9 +214,12 @@ impl WidgetUnitNode {
             Self::ContentBox(v) => Some(&mut v.props),
             Self::FlexBox(v) => Some(&mut v.props),
             Self::GridBox(v) => Some(&mut v.props),
+            Self::MasonryBox(v) => Some(&mut v.props),
             Self::SizeBox(v) => Some(&mut v.props),
             Self::ImageBox(v) => Some(&mut v.props),
             Self::TextBox(v) => Some(&mut v.props),
+            Self::RichTextBox(v) => Some(&mut v.props),
+            Self::CustomBox(v) => Some(&mut v.props),
         }
     }
 
@@ -189,9 +232,12 @@ impl WidgetUnitNode {
             Self::ContentBox(v) => v.remap_props(f),
             Self::FlexBox(v) => v.remap_props(f),
             Self::GridBox(v) => v.remap_props(f),
+            Self::MasonryBox(v) => v.remap_props(f),
             Self::SizeBox(v) => v.remap_props(f),
             Self::ImageBox(v) => v.remap_props(f),
             Self::TextBox(v) => v.remap_props(f),
+            Self::RichTextBox(v) => v.remap_props(f),
+            Self::CustomBox(v) => v.remap_props(f),
         }
     }
 }
@@ -232,9 +278,12 @@ implement_from_unit! {
     ContentBoxNode => ContentBox,
     FlexBoxNode => FlexBox,
     GridBoxNode => GridBox,
+    MasonryBoxNode => MasonryBox,
     SizeBoxNode => SizeBox,
     ImageBoxNode => ImageBox,
     TextBoxNode => TextBox,
+    RichTextBoxNode => RichTextBox,
+    CustomBoxNode => CustomBox,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -245,9 +294,12 @@ pub(crate) enum WidgetUnitNodePrefab {
     ContentBox(ContentBoxNodePrefab),
     FlexBox(FlexBoxNodePrefab),
     GridBox(GridBoxNodePrefab),
+    MasonryBox(MasonryBoxNodePrefab),
     SizeBox(SizeBoxNodePrefab),
     ImageBox(ImageBoxNodePrefab),
     TextBox(TextBoxNodePrefab),
+    RichTextBox(RichTextBoxNodePrefab),
+    CustomBox(CustomBoxNodePrefab),
 }
 
 impl Default for WidgetUnitNodePrefab {
@@ -0,0 +1,181 @@
+use crate::{
+    layout::LayoutItem,
+    props::Props,
+    widget::{
+        node::WidgetNode,
+        unit::{
+            text::{TextBoxFont, TextBoxHorizontalAlign, TextBoxSizeValue, TextBoxVerticalAlign},
+            WidgetUnitData,
+        },
+        utils::{Color, Transform, Vec2},
+        WidgetId,
+    },
+    PrefabValue, Scalar,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// Inline image sitting on text baseline
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RichTextBoxImage {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub width: Scalar,
+    #[serde(default)]
+    pub height: Scalar,
+    #[serde(default)]
+    pub tint: Color,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RichTextBoxSpanContent {
+    Text(String),
+    Image(RichTextBoxImage),
+}
+
+impl Default for RichTextBoxSpanContent {
+    fn default() -> Self {
+        Self::Text(Default::default())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RichTextBoxSpan {
+    #[serde(default)]
+    pub content: RichTextBoxSpanContent,
+    #[serde(default)]
+    pub font: TextBoxFont,
+    #[serde(default)]
+    pub color: Color,
+    /// Link target reported when span gets clicked
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+impl RichTextBoxSpan {
+    pub fn text(&self) -> Option<&str> {
+        match &self.content {
+            RichTextBoxSpanContent::Text(text) => Some(text),
+            RichTextBoxSpanContent::Image(_) => None,
+        }
+    }
+}
+
+/// Text made of differently styled spans, wrapped into lines at whitespaces and new lines.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RichTextBox {
+    #[serde(default)]
+    pub id: WidgetId,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub spans: Vec<RichTextBoxSpan>,
+    #[serde(default)]
+    pub width: TextBoxSizeValue,
+    #[serde(default)]
+    pub height: TextBoxSizeValue,
+    #[serde(default)]
+    pub horizontal_align: TextBoxHorizontalAlign,
+    #[serde(default)]
+    pub vertical_align: TextBoxVerticalAlign,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+impl RichTextBox {
+    /// Link of span laid out under point given in local space of this unit layout item.
+    pub fn link_at(&self, layout: &LayoutItem, point: Vec2) -> Option<&str> {
+        layout
+            .fragments
+            .iter()
+            .filter(|fragment| {
+                point.x >= fragment.rect.left
+                    && point.x <= fragment.rect.right
+                    && point.y >= fragment.rect.top
+                    && point.y <= fragment.rect.bottom
+            })
+            .find_map(|fragment| self.spans.get(fragment.span)?.link.as_deref())
+    }
+}
+
+impl WidgetUnitData for RichTextBox {
+    fn id(&self) -> &WidgetId {
+        &self.id
+    }
+}
+
+impl TryFrom<RichTextBoxNode> for RichTextBox {
+    type Error = ();
+
+    fn try_from(node: RichTextBoxNode) -> Result<Self, Self::Error> {
+        let RichTextBoxNode {
+            id,
+            spans,
+            width,
+            height,
+            horizontal_align,
+            vertical_align,
+            transform,
+            ..
+        } = node;
+        Ok(Self {
+            id,
+            spans,
+            width,
+            height,
+            horizontal_align,
+            vertical_align,
+            transform,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RichTextBoxNode {
+    pub id: WidgetId,
+    pub props: Props,
+    pub spans: Vec<RichTextBoxSpan>,
+    pub width: TextBoxSizeValue,
+    pub height: TextBoxSizeValue,
+    pub horizontal_align: TextBoxHorizontalAlign,
+    pub vertical_align: TextBoxVerticalAlign,
+    pub transform: Transform,
+}
+
+impl RichTextBoxNode {
+    pub fn remap_props<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Props) -> Props,
+    {
+        let props = std::mem::take(&mut self.props);
+        self.props = (f)(props);
+    }
+}
+
+impl From<RichTextBoxNode> for WidgetNode {
+    fn from(data: RichTextBoxNode) -> Self {
+        Self::Unit(data.into())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct RichTextBoxNodePrefab {
+    #[serde(default)]
+    pub id: WidgetId,
+    #[serde(default)]
+    pub props: PrefabValue,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub spans: Vec<RichTextBoxSpan>,
+    #[serde(default)]
+    pub width: TextBoxSizeValue,
+    #[serde(default)]
+    pub height: TextBoxSizeValue,
+    #[serde(default)]
+    pub horizontal_align: TextBoxHorizontalAlign,
+    #[serde(default)]
+    pub vertical_align: TextBoxVerticalAlign,
+    #[serde(default)]
+    pub transform: Transform,
+}
@@ -3,7 +3,7 @@ use crate::{
     widget::{
         node::{WidgetNode, WidgetNodePrefab},
         unit::{WidgetUnit, WidgetUnitData},
-        utils::{Rect, Transform},
+        utils::{Rect, SizeConstraints, Transform},
         WidgetId,
     },
     PrefabValue, Scalar,
@@ -35,6 +35,8 @@ pub struct SizeBox {
     #[serde(default)]
     pub height: SizeBoxSizeValue,
     #[serde(default)]
+    pub constraints: SizeConstraints,
+    #[serde(default)]
     pub margin: Rect,
     #[serde(default)]
     pub transform: Transform,
@@ -59,6 +61,7 @@ impl TryFrom<SizeBoxNode> for SizeBox {
             slot,
             width,
             height,
+            constraints,
             margin,
             transform,
             ..
@@ -68,6 +71,7 @@ impl TryFrom<SizeBoxNode> for SizeBox {
             slot: Box::new(WidgetUnit::try_from(*slot)?),
             width,
             height,
+            constraints,
             margin,
             transform,
         })
@@ -81,6 +85,7 @@ pub struct SizeBoxNode {
     pub slot: Box<WidgetNode>,
     pub width: SizeBoxSizeValue,
     pub height: SizeBoxSizeValue,
+    pub constraints: SizeConstraints,
     pub margin: Rect,
     pub transform: Transform,
 }
@@ -114,6 +119,8 @@ pub(crate) struct SizeBoxNodePrefab {
     #[serde(default)]
     pub height: SizeBoxSizeValue,
     #[serde(default)]
+    pub constraints: SizeConstraints,
+    #[serde(default)]
     pub margin: Rect,
     #[serde(default)]
     pub transform: Transform,
@@ -69,6 +69,51 @@ impl TextBoxDirection {
     }
 }
 
+/// Places where text lines can be broken to fit text box width. Text boxes with default wrapping
+/// and overflow settings are wrapped by renderers, lines of other ones are laid out by layout
+/// engine (see [`crate::layout::LayoutTextFragment`]).
+///
+/// Layout engine lays out lines of horizontal text only, vertical text directions ignore
+/// wrapping, overflow, `max_lines` and `line_height` settings and are wrapped by renderers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextBoxWrap {
+    /// Lines are broken only at new line characters
+    None,
+    /// Lines are broken between words, words wider than text box are broken between characters
+    Word,
+    /// Lines are broken between any characters
+    Character,
+}
+
+impl Default for TextBoxWrap {
+    fn default() -> Self {
+        Self::Word
+    }
+}
+
+/// What happens with text that does not fit text box
+///
+/// Applies to horizontal text directions only, see [`TextBoxWrap`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextBoxOverflow {
+    /// Text is shown outside of text box
+    Visible,
+    /// Lines and characters that do not fit text box are not shown
+    Clip,
+    /// Text is cut at its end and replaced with ellipsis
+    EllipsisEnd,
+    /// Text is cut in its middle and replaced with ellipsis, keeping start and end of text
+    EllipsisMiddle,
+    /// Font size gets scaled down until text fits text box
+    ShrinkToFit,
+}
+
+impl Default for TextBoxOverflow {
+    fn default() -> Self {
+        Self::Visible
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TextBoxFont {
     #[serde(default)]
@@ -79,6 +124,8 @@ pub struct TextBoxFont {
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TextBoxSizeValue {
+    /// Size of measured text (see [`crate::layout::TextMeasurementEngine`])
+    Content,
     Fill,
     Exact(Scalar),
 }
@@ -106,6 +153,18 @@ pub struct TextBox {
     #[serde(default)]
     pub direction: TextBoxDirection,
     #[serde(default)]
+    pub wrap: TextBoxWrap,
+    #[serde(default)]
+    pub overflow: TextBoxOverflow,
+    /// Maximum number of shown lines, unlimited when not set
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    /// Distance between baselines of consecutive lines, font line height when not set
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height: Option<Scalar>,
+    #[serde(default)]
     pub font: TextBoxFont,
     #[serde(default)]
     pub color: Color,
@@ -131,6 +190,10 @@ impl TryFrom<TextBoxNode> for TextBox {
             horizontal_align,
             vertical_align,
             direction,
+            wrap,
+            overflow,
+            max_lines,
+            line_height,
             font,
             color,
             transform,
@@ -144,6 +207,10 @@ impl TryFrom<TextBoxNode> for TextBox {
             horizontal_align,
             vertical_align,
             direction,
+            wrap,
+            overflow,
+            max_lines,
+            line_height,
             font,
             color,
             transform,
@@ -161,6 +228,10 @@ pub struct TextBoxNode {
     pub horizontal_align: TextBoxHorizontalAlign,
     pub vertical_align: TextBoxVerticalAlign,
     pub direction: TextBoxDirection,
+    pub wrap: TextBoxWrap,
+    pub overflow: TextBoxOverflow,
+    pub max_lines: Option<usize>,
+    pub line_height: Option<Scalar>,
     pub font: TextBoxFont,
     pub color: Color,
     pub transform: Transform,
@@ -201,6 +272,18 @@ pub(crate) struct TextBoxNodePrefab {
     #[serde(default)]
     pub direction: TextBoxDirection,
     #[serde(default)]
+    pub wrap: TextBoxWrap,
+    #[serde(default)]
+    pub overflow: TextBoxOverflow,
+    /// Maximum number of shown lines, unlimited when not set
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    /// Distance between baselines of consecutive lines, font line height when not set
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height: Option<Scalar>,
+    #[serde(default)]
     pub font: TextBoxFont,
     #[serde(default)]
     pub color: Color,
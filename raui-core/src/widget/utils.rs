@@ -210,6 +210,82 @@ impl From<(Integer, Integer)> for IntRect {
     }
 }
 
+/// Horizontal layout direction of the widgets tree
+///
+/// Right-to-left direction mirrors horizontal placement of container children, so flex order,
+/// anchors, margins and alignment are all resolved from the right edge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayoutDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Default for LayoutDirection {
+    fn default() -> Self {
+        Self::LeftToRight
+    }
+}
+
+impl LayoutDirection {
+    #[inline]
+    pub fn is_right_to_left(&self) -> bool {
+        *self == Self::RightToLeft
+    }
+}
+
+/// Minimal and maximal size limits of a layout item
+///
+/// Maximal sizes are unbounded when not set.
+#[derive(PropsData, Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[props_data(crate::props::PropsData)]
+#[prefab(crate::Prefab)]
+pub struct SizeConstraints {
+    #[serde(default)]
+    pub min_width: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<Scalar>,
+    #[serde(default)]
+    pub min_height: Scalar,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<Scalar>,
+}
+
+impl SizeConstraints {
+    #[inline]
+    pub fn max_width(&self) -> Scalar {
+        self.max_width
+            .unwrap_or(Scalar::INFINITY)
+            .max(self.min_width)
+    }
+
+    #[inline]
+    pub fn max_height(&self) -> Scalar {
+        self.max_height
+            .unwrap_or(Scalar::INFINITY)
+            .max(self.min_height)
+    }
+
+    #[inline]
+    pub fn clamp_width(&self, value: Scalar) -> Scalar {
+        value.max(self.min_width).min(self.max_width())
+    }
+
+    #[inline]
+    pub fn clamp_height(&self, value: Scalar) -> Scalar {
+        value.max(self.min_height).min(self.max_height())
+    }
+
+    #[inline]
+    pub fn clamp(&self, value: Vec2) -> Vec2 {
+        Vec2 {
+            x: self.clamp_width(value.x),
+            y: self.clamp_height(value.y),
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(PropsData, Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[props_data(crate::props::PropsData)]
@@ -289,6 +365,33 @@ impl Transform {
     fn default_scale() -> Vec2 {
         Vec2 { x: 1.0, y: 1.0 }
     }
+
+    /// Maps point from space of rectangle of given size (placed at origin) into its local space
+    /// with this transform undone, or returns `None` when transform cannot be inverted.
+    pub fn unproject_point(&self, point: Vec2, size: Vec2) -> Option<Vec2> {
+        let pivot = Vec2 {
+            x: lerp(0.0, size.x, self.pivot.x),
+            y: lerp(0.0, size.y, self.pivot.y),
+        };
+        let x = point.x - lerp(0.0, size.x, self.align.x) - pivot.x - self.translation.x;
+        let y = point.y - lerp(0.0, size.y, self.align.y) - pivot.y - self.translation.y;
+        let (sin, cos) = (-self.rotation).sin_cos();
+        let (x, y) = (x * cos - y * sin, x * sin + y * cos);
+        if self.scale.x == 0.0 || self.scale.y == 0.0 {
+            return None;
+        }
+        let (x, y) = (x / self.scale.x, y / self.scale.y);
+        let (skew_x, skew_y) = (self.skew.x.tan(), self.skew.y.tan());
+        let det = 1.0 - skew_x * skew_y;
+        if det == 0.0 {
+            return None;
+        }
+        let (x, y) = ((x - skew_y * y) / det, (y - skew_x * x) / det);
+        Some(Vec2 {
+            x: x + pivot.x,
+            y: y + pivot.y,
+        })
+    }
 }
 
 #[derive(Debug, Default, Copy, Clone)]
@@ -14,7 +14,7 @@ use raui_core::{
         },
         utils::{lerp, Rect, Transform, Vec2},
     },
-    Scalar,
+    Integer, Scalar,
 };
 
 pub struct GgezRenderer<'a> {
@@ -41,15 +41,18 @@ impl<'a> GgezRenderer<'a> {
         (offset, transform.rotation, transform.scale, rect)
     }
 
-    fn render_node(
-        &mut self,
-        unit: &WidgetUnit,
-        mapping: &CoordsMapping,
+    /// Collects units drawn by this renderer in tree order, together with their render layers.
+    fn collect_draw_units<'b>(
+        unit: &'b WidgetUnit,
         layout: &Layout,
-    ) -> Result<(), Error> {
+        result: &mut Vec<(Integer, &'b WidgetUnit)>,
+    ) {
         match unit {
-            WidgetUnit::None | WidgetUnit::PortalBox(_) => Ok(()),
-            WidgetUnit::AreaBox(unit) => self.render_node(&unit.slot, mapping, layout),
+            WidgetUnit::None
+            | WidgetUnit::PortalBox(_)
+            | WidgetUnit::CustomBox(_)
+            | WidgetUnit::RichTextBox(_) => {}
+            WidgetUnit::AreaBox(unit) => Self::collect_draw_units(&unit.slot, layout, result),
             WidgetUnit::ContentBox(unit) => {
                 let mut items = unit
                     .items
@@ -58,23 +61,43 @@ impl<'a> GgezRenderer<'a> {
                     .collect::<Vec<_>>();
                 items.sort_by(|(a, _), (b, _)| a.partial_cmp(&b).unwrap());
                 for (_, item) in items {
-                    self.render_node(&item.slot, mapping, layout)?;
+                    Self::collect_draw_units(&item.slot, layout, result);
                 }
-                Ok(())
             }
             WidgetUnit::FlexBox(unit) => {
                 for item in &unit.items {
-                    self.render_node(&item.slot, mapping, layout)?;
+                    Self::collect_draw_units(&item.slot, layout, result);
                 }
-                Ok(())
             }
             WidgetUnit::GridBox(unit) => {
                 for item in &unit.items {
-                    self.render_node(&item.slot, mapping, layout)?;
+                    Self::collect_draw_units(&item.slot, layout, result);
+                }
+            }
+            WidgetUnit::MasonryBox(unit) => {
+                for item in &unit.items {
+                    Self::collect_draw_units(&item.slot, layout, result);
                 }
-                Ok(())
             }
-            WidgetUnit::SizeBox(unit) => self.render_node(&unit.slot, mapping, layout),
+            WidgetUnit::SizeBox(unit) => Self::collect_draw_units(&unit.slot, layout, result),
+            WidgetUnit::ImageBox(_) | WidgetUnit::TextBox(_) => {
+                let layer = unit
+                    .as_data()
+                    .and_then(|data| layout.items.get(data.id()))
+                    .map(|item| item.layer)
+                    .unwrap_or_default();
+                result.push((layer, unit));
+            }
+        }
+    }
+
+    fn render_node(
+        &mut self,
+        unit: &WidgetUnit,
+        mapping: &CoordsMapping,
+        layout: &Layout,
+    ) -> Result<(), Error> {
+        match unit {
             WidgetUnit::ImageBox(unit) => match &unit.material {
                 ImageBoxMaterial::Color(image) => {
                     if let Some(item) = layout.items.get(&unit.id) {
@@ -448,30 +471,49 @@ impl<'a> GgezRenderer<'a> {
                         let rect = mapping.virtual_to_real_rect(item.ui_space, false);
                         let (offset, rotation, scaling, rect) =
                             Self::transform_rect(rect, &unit.transform);
-                        let mut text = Text::new(TextFragment::new(unit.text.as_str()).color(
-                            graphics::Color::new(
-                                unit.color.r,
-                                unit.color.g,
-                                unit.color.b,
-                                unit.color.a,
-                            ),
-                        ));
-                        text.set_font(
-                            *resource,
-                            Scale::uniform(unit.font.size * mapping.scale().x),
-                        );
-                        text.set_bounds(
-                            [rect.width(), rect.height()],
-                            match unit.horizontal_align {
-                                TextBoxHorizontalAlign::Left => Align::Left,
-                                TextBoxHorizontalAlign::Center => Align::Center,
-                                TextBoxHorizontalAlign::Right => Align::Right,
-                            },
+                        let color = graphics::Color::new(
+                            unit.color.r,
+                            unit.color.g,
+                            unit.color.b,
+                            unit.color.a,
                         );
+                        let align = match unit.horizontal_align {
+                            TextBoxHorizontalAlign::Left => Align::Left,
+                            TextBoxHorizontalAlign::Center => Align::Center,
+                            TextBoxHorizontalAlign::Right => Align::Right,
+                        };
                         // NOTE:
                         // this is a solution for a bug that when passing position to DrawParam,
                         // next item after text is positioned relative to this text offset.
-                        graphics::queue_text(self.context, &text, [rect.left, rect.top], None);
+                        if item.fragments.is_empty() {
+                            let mut text =
+                                Text::new(TextFragment::new(unit.text.as_str()).color(color));
+                            text.set_font(
+                                *resource,
+                                Scale::uniform(unit.font.size * mapping.scale().x),
+                            );
+                            text.set_bounds([rect.width(), rect.height()], align);
+                            graphics::queue_text(self.context, &text, [rect.left, rect.top], None);
+                        } else {
+                            // lines laid out by layout engine are drawn without further wrapping.
+                            for fragment in &item.fragments {
+                                let fragment_rect =
+                                    mapping.virtual_to_real_rect(fragment.rect, true);
+                                let mut text = Text::new(
+                                    TextFragment::new(fragment.text.as_str()).color(color),
+                                );
+                                text.set_font(
+                                    *resource,
+                                    Scale::uniform(fragment.font_size * mapping.scale().x),
+                                );
+                                graphics::queue_text(
+                                    self.context,
+                                    &text,
+                                    [rect.left + fragment_rect.left, rect.top + fragment_rect.top],
+                                    None,
+                                );
+                            }
+                        }
                         let params = graphics::DrawParam::default()
                             .rotation(rotation)
                             .scale([scaling.x, scaling.y])
@@ -505,6 +547,7 @@ impl<'a> GgezRenderer<'a> {
                     Err(Error::WidgetHasNoLayout(unit.id.to_owned()))
                 }
             }
+            _ => Ok(()),
         }
     }
 }
@@ -516,6 +559,13 @@ impl<'a> Renderer<(), Error> for GgezRenderer<'a> {
         mapping: &CoordsMapping,
         layout: &Layout,
     ) -> Result<(), Error> {
-        self.render_node(tree, mapping, layout)
+        let mut units = vec![];
+        Self::collect_draw_units(tree, layout, &mut units);
+        // stable sort keeps tree order of units within the same layer.
+        units.sort_by_key(|(layer, _)| *layer);
+        for (_, unit) in units {
+            self.render_node(unit, mapping, layout)?;
+        }
+        Ok(())
     }
 }
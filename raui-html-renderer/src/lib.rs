@@ -291,6 +291,13 @@ impl HtmlRenderer {
                     }
                 } (writer, level));
             }
+            WidgetUnit::MasonryBox(MasonryBox { items, .. }) => {
+                node!(self: div [writer] level={level} {
+                    for item in items {
+                        self.write_node(writer, &item.slot, level)?;
+                    }
+                } (writer, level));
+            }
             WidgetUnit::SizeBox(SizeBox { slot, .. }) => {
                 node!(self: div [writer] level={level} {
                     self.write_node(writer, slot, level)?;
@@ -305,6 +312,19 @@ impl HtmlRenderer {
                     self.write_line(text, writer, level)?;
                 } (writer, level));
             }
+            WidgetUnit::RichTextBox(RichTextBox { spans, .. }) => {
+                node!(self: span [writer] level={level} {
+                    for span in spans {
+                        if let Some(text) = span.text() {
+                            self.write_line(text, writer, level)?;
+                        }
+                    }
+                } (writer, level));
+            }
+            WidgetUnit::CustomBox(_) => {
+                node!(self: div [writer] level={level} {
+                } (writer, level));
+            }
         }
         Ok(())
     }
@@ -78,6 +78,10 @@ pub fn paper(context: WidgetContext) -> WidgetNode {
                     material: ImageBoxMaterial::Procedural(material),
                     ..Default::default()
                 },
+                ThemedImageMaterial::Gradient(material) => ImageBoxProps {
+                    material: ImageBoxMaterial::Gradient(material),
+                    ..Default::default()
+                },
             };
             let props = Props::new(ContentBoxItemLayout {
                 depth: Scalar::NEG_INFINITY,
@@ -86,6 +86,7 @@ pub fn scroll_paper_side_scrollbars(context: WidgetContext) -> WidgetNode {
             }
             ThemedImageMaterial::Image(material) => ImageBoxMaterial::Image(material),
             ThemedImageMaterial::Procedural(material) => ImageBoxMaterial::Procedural(material),
+            ThemedImageMaterial::Gradient(material) => ImageBoxMaterial::Gradient(material),
         })
     } else {
         None
@@ -114,6 +115,7 @@ pub fn scroll_paper_side_scrollbars(context: WidgetContext) -> WidgetNode {
             }
             ThemedImageMaterial::Image(material) => ImageBoxMaterial::Image(material),
             ThemedImageMaterial::Procedural(material) => ImageBoxMaterial::Procedural(material),
+            ThemedImageMaterial::Gradient(material) => ImageBoxMaterial::Gradient(material),
         }
     };
 
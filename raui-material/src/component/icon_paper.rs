@@ -72,6 +72,7 @@ pub fn icon_paper(context: WidgetContext) -> WidgetNode {
             outside: false,
         }),
         material: ImageBoxMaterial::Image(image),
+        baseline_offset: 0.0,
         transform: icon_props.transform,
     };
 
@@ -94,6 +94,10 @@ fn button_paper_content(context: WidgetContext) -> WidgetNode {
                     material: ImageBoxMaterial::Procedural(material),
                     ..Default::default()
                 },
+                ThemedImageMaterial::Gradient(material) => ImageBoxProps {
+                    material: ImageBoxMaterial::Gradient(material),
+                    ..Default::default()
+                },
             };
             let props = Props::new(ContentBoxItemLayout {
                 depth: Scalar::NEG_INFINITY,
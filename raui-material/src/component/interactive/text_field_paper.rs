@@ -132,6 +132,7 @@ fn text_field_paper_content(context: WidgetContext) -> WidgetNode {
             horizontal_align_override,
             vertical_align_override,
             transform,
+            ..Default::default()
         })
         .with(ContentBoxItemLayout {
             margin: padding,
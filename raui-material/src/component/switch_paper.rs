@@ -86,6 +86,12 @@ pub fn switch_paper(context: WidgetContext) -> WidgetNode {
             height: ImageBoxSizeValue::Exact(size),
             ..Default::default()
         },
+        ThemedImageMaterial::Gradient(data) => ImageBoxProps {
+            material: ImageBoxMaterial::Gradient(data),
+            width: ImageBoxSizeValue::Exact(size),
+            height: ImageBoxSizeValue::Exact(size),
+            ..Default::default()
+        },
     };
     widget! {
         (#{key} | {idref.cloned()} image_box: {image})
@@ -23,6 +23,16 @@ pub struct TextPaperProps {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vertical_align_override: Option<TextBoxVerticalAlign>,
     #[serde(default)]
+    pub wrap: TextBoxWrap,
+    #[serde(default)]
+    pub overflow: TextBoxOverflow,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height: Option<Scalar>,
+    #[serde(default)]
     pub transform: Transform,
 }
 
@@ -43,6 +53,10 @@ pub fn text_paper(context: WidgetContext) -> WidgetNode {
         use_main_color,
         horizontal_align_override,
         vertical_align_override,
+        wrap,
+        overflow,
+        max_lines,
+        line_height,
         transform,
     } = props.read_cloned_or_default();
     let themed_props = props.read_cloned_or_default::<ThemedWidgetProps>();
@@ -90,6 +104,10 @@ pub fn text_paper(context: WidgetContext) -> WidgetNode {
         horizontal_align,
         vertical_align,
         direction,
+        wrap,
+        overflow,
+        max_lines,
+        line_height,
         font,
         color,
         transform,
@@ -75,6 +75,7 @@ pub enum ThemedImageMaterial {
     Color,
     Image(ImageBoxImage),
     Procedural(ImageBoxProcedural),
+    Gradient(ImageBoxGradient),
 }
 
 impl Default for ThemedImageMaterial {
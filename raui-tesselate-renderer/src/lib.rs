@@ -1,4 +1,5 @@
 pub mod renderer;
+pub mod shape;
 pub mod tesselation;
 
 use raui_core::widget::{unit::image::ImageBoxMaterial, WidgetId};
@@ -13,8 +14,9 @@ pub enum Error {
     WidgetHasNoLayout(WidgetId),
     UnsupportedImageMaterial(ImageBoxMaterial),
     CouldNotTesselateText(WidgetId),
+    CouldNotTesselateCustomUnit(WidgetId),
 }
 
 pub mod prelude {
-    pub use crate::{renderer::*, tesselation::*};
+    pub use crate::{renderer::*, shape::*, tesselation::*};
 }
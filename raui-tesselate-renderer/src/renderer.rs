@@ -1,25 +1,38 @@
 use crate::{
+    shape::ShapeMesh,
     tesselation::{
-        Batch, BatchClipRect, BatchExternalText, Tesselation, TesselationVerticeInterleaved,
-        TesselationVertices, TesselationVerticesFormat, TesselationVerticesSeparated,
-        TesselationVerticesSeparatedSliceMut, TesselationVerticesSliceMut,
+        Batch, BatchClipRect, BatchExternalCustom, BatchExternalText, Tesselation,
+        TesselationVerticeInterleaved, TesselationVertices, TesselationVerticesFormat,
+        TesselationVerticesSeparated, TesselationVerticesSeparatedSliceMut,
+        TesselationVerticesSliceMut,
     },
     Error, Index,
 };
 use raui_core::{
-    layout::{CoordsMapping, Layout, LayoutItem},
+    layout::{CoordsMapping, Layout, LayoutItem, LayoutTextFragment},
     renderer::Renderer,
     widget::{
         unit::{
-            image::{ImageBoxColor, ImageBoxImage, ImageBoxImageScaling, ImageBoxMaterial},
-            text::TextBox,
+            custom::CustomBox,
+            image::{
+                ImageBoxColor, ImageBoxGradient, ImageBoxGradientKind, ImageBoxImage,
+                ImageBoxImageScaling, ImageBoxMaterial, ImageBoxShape,
+            },
+            rich_text::{RichTextBox, RichTextBoxSpanContent},
+            text::{TextBox, TextBoxFont, TextBoxSizeValue, TextBoxWrap},
             WidgetUnit,
         },
-        utils::{lerp, Rect, Transform, Vec2},
+        utils::{lerp, Color, Rect, Transform, Vec2},
+        WidgetId,
     },
-    Scalar,
+    Integer, Scalar,
 };
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Maximal size of gradient grid cells (in pixels), gradient colors are interpolated between them.
+const GRADIENT_CELL_SIZE: Scalar = 16.0;
+/// Maximal number of gradient grid cells along single axis, cells of bigger boxes get bigger.
+const GRADIENT_MAX_SUBDIVISIONS: usize = 128;
 
 fn raui_to_vec2(v: Vec2) -> vek::Vec2<Scalar> {
     vek::Vec2::new(v.x, v.y)
@@ -66,6 +79,7 @@ impl TextTesselationEngine for () {
                 horizontal_align: text.horizontal_align,
                 vertical_align: text.vertical_align,
                 direction: text.direction,
+                wrap: text.wrap,
                 color: text.color,
                 box_size: layout.local_space.size(),
                 matrix,
@@ -75,6 +89,31 @@ impl TextTesselationEngine for () {
     }
 }
 
+/// Tesselation of custom units, registered in renderer under custom unit type name
+pub trait CustomTesselationEngine {
+    fn count(&self, unit: &CustomBox, layout: &LayoutItem) -> (usize, usize, usize);
+
+    fn tesselate(
+        &mut self,
+        unit: &CustomBox,
+        layout: &LayoutItem,
+        matrix: [Scalar; 16],
+        output_vertices: TesselationVerticesSliceMut,
+        output_indices: &mut [Index],
+        output_batches: &mut [Batch],
+    ) -> Result<(), Error>;
+}
+
+/// Custom units tesselations by custom unit type name
+#[derive(Default)]
+struct CustomTesselationEngines<'a>(HashMap<String, Box<dyn CustomTesselationEngine + 'a>>);
+
+impl<'a> std::fmt::Debug for CustomTesselationEngines<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct TesselateRenderer<'a, TTE = ()>
 where
@@ -85,7 +124,15 @@ where
     /// {image id: (atlas image id, inner rectangle)}
     atlas_mapping: &'a HashMap<String, (String, Rect)>,
     image_sizes: &'a HashMap<String, Vec2>,
+    custom_tesselation_engines: CustomTesselationEngines<'a>,
     transform_stack: VecDeque<vek::Mat4<Scalar>>,
+    /// Clip rectangles of content boxes being rendered, with their unique ids
+    clip_stack: Vec<(usize, BatchClipRect)>,
+    clips_count: usize,
+    /// Batches of render layers, with ids of clip rectangles opened in them
+    layers: BTreeMap<Integer, (Vec<Batch>, Vec<usize>)>,
+    /// Layout in real coords snapped to physical pixel grid, when mapping uses pixel snapping
+    snapped_layout: Option<Layout>,
 }
 
 impl<'a, TTE> TesselateRenderer<'a, TTE>
@@ -103,7 +150,12 @@ where
             text_tesselation_engine,
             atlas_mapping,
             image_sizes,
+            custom_tesselation_engines: Default::default(),
             transform_stack: Default::default(),
+            clip_stack: Default::default(),
+            clips_count: 0,
+            layers: Default::default(),
+            snapped_layout: None,
         }
     }
 
@@ -119,10 +171,38 @@ where
             text_tesselation_engine,
             atlas_mapping,
             image_sizes,
+            custom_tesselation_engines: Default::default(),
             transform_stack: VecDeque::with_capacity(transform_stack),
+            clip_stack: Default::default(),
+            clips_count: 0,
+            layers: Default::default(),
+            snapped_layout: None,
         }
     }
 
+    /// Registers tesselation of custom units with given type name. Custom units without
+    /// registered tesselation produce [`Batch::ExternalCustom`] batch.
+    pub fn register_custom_unit<T>(&mut self, type_name: &str, engine: T)
+    where
+        T: CustomTesselationEngine + 'a,
+    {
+        self.custom_tesselation_engines
+            .0
+            .insert(type_name.to_owned(), Box::new(engine));
+    }
+
+    pub fn unregister_custom_unit(&mut self, type_name: &str) {
+        self.custom_tesselation_engines.0.remove(type_name);
+    }
+
+    pub fn with_custom_unit<T>(mut self, type_name: &str, engine: T) -> Self
+    where
+        T: CustomTesselationEngine + 'a,
+    {
+        self.register_custom_unit(type_name, engine);
+        self
+    }
+
     fn push_transform(&mut self, transform: &Transform, rect: Rect) {
         let size = rect.size();
         let offset = vek::Vec2::new(rect.left, rect.top);
@@ -170,6 +250,151 @@ where
         self.transform_stack.back().cloned().unwrap_or_default()
     }
 
+    /// Reserves space for tesselation of given size (vertices, indices and batches counts), lets
+    /// `f` fill it (with indices and batches ranges relative to reserved space) and then offsets
+    /// them to reserved space.
+    fn append_tesselation<F>(
+        result: &mut Tesselation,
+        (vertices, indices, batches): (usize, usize, usize),
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(TesselationVerticesSliceMut, &mut [Index], &mut [Batch]) -> Result<(), Error>,
+    {
+        let batches_start = result.batches.len();
+        let indices_start = result.indices.len();
+        result
+            .batches
+            .resize(batches_start + batches, Default::default());
+        result.indices.resize(indices_start + indices, 0);
+        let vertices_start = match &mut result.vertices {
+            TesselationVertices::Separated(TesselationVerticesSeparated {
+                position,
+                tex_coord,
+                color,
+            }) => {
+                let vertices_start = position.len();
+                position.resize(vertices_start + vertices, Default::default());
+                tex_coord.resize(vertices_start + vertices, Default::default());
+                color.resize(vertices_start + vertices, Default::default());
+                (f)(
+                    TesselationVerticesSliceMut::Separated(TesselationVerticesSeparatedSliceMut {
+                        position: &mut position[vertices_start..(vertices_start + vertices)],
+                        tex_coord: &mut tex_coord[vertices_start..(vertices_start + vertices)],
+                        color: &mut color[vertices_start..(vertices_start + vertices)],
+                    }),
+                    &mut result.indices[indices_start..(indices_start + indices)],
+                    &mut result.batches[batches_start..(batches_start + batches)],
+                )?;
+                vertices_start as Index
+            }
+            TesselationVertices::Interleaved(data) => {
+                let vertices_start = data.len();
+                data.resize(vertices_start + vertices, Default::default());
+                (f)(
+                    TesselationVerticesSliceMut::Interleaved(
+                        &mut data[vertices_start..(vertices_start + vertices)],
+                    ),
+                    &mut result.indices[indices_start..(indices_start + indices)],
+                    &mut result.batches[batches_start..(batches_start + batches)],
+                )?;
+                vertices_start as Index
+            }
+        };
+        for item in &mut result.indices[indices_start..(indices_start + indices)] {
+            *item += vertices_start;
+        }
+        for item in &mut result.batches[batches_start..(batches_start + batches)] {
+            match item {
+                Batch::ColoredTriangles(range) => {
+                    range.start += indices_start;
+                    range.end += indices_start;
+                }
+                Batch::ImageTriangles(_, range) => {
+                    range.start += indices_start;
+                    range.end += indices_start;
+                }
+                Batch::FontTriangles(_, _, range) => {
+                    range.start += indices_start;
+                    range.end += indices_start;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Text box drawing single line text fragment laid out by layout engine, together with its
+    /// layout. Whitespace fragments and image fragments produce nothing.
+    fn fragment_text_box(
+        id: &WidgetId,
+        font: &str,
+        color: Color,
+        layout: &LayoutItem,
+        fragment: &LayoutTextFragment,
+    ) -> Option<(TextBox, LayoutItem)> {
+        if fragment.text.trim().is_empty() {
+            return None;
+        }
+        let text = TextBox {
+            id: id.to_owned(),
+            text: fragment.text.to_owned(),
+            width: TextBoxSizeValue::Exact(fragment.rect.width()),
+            height: TextBoxSizeValue::Exact(fragment.rect.height()),
+            wrap: TextBoxWrap::None,
+            font: TextBoxFont {
+                name: font.to_owned(),
+                size: fragment.font_size,
+            },
+            color,
+            ..Default::default()
+        };
+        let layout = LayoutItem {
+            local_space: fragment.rect,
+            ui_space: Rect {
+                left: layout.ui_space.left + fragment.rect.left,
+                right: layout.ui_space.left + fragment.rect.right,
+                top: layout.ui_space.top + fragment.rect.top,
+                bottom: layout.ui_space.top + fragment.rect.bottom,
+            },
+            parent: Some(id.to_owned()),
+            layer: layout.layer,
+            direction: layout.direction,
+            fragments: vec![],
+        };
+        Some((text, layout))
+    }
+
+    /// Text box drawing text fragment of rich text box, together with its layout.
+    fn rich_text_fragment_text(
+        unit: &RichTextBox,
+        layout: &LayoutItem,
+        fragment: &LayoutTextFragment,
+    ) -> Option<(TextBox, LayoutItem)> {
+        let span = unit.spans.get(fragment.span)?;
+        span.text()?;
+        Self::fragment_text_box(&unit.id, &span.font.name, span.color, layout, fragment)
+    }
+
+    /// Tesselates single line text fragments of text box or rich text box with text engine.
+    fn produce_fragment_text(
+        &mut self,
+        text: &TextBox,
+        layout: &LayoutItem,
+        rect: Rect,
+        result: &mut Tesselation,
+    ) -> Result<(), Error> {
+        self.push_transform_simple(rect);
+        let matrix = self.top_transform().into_col_array();
+        let counts = self.text_tesselation_engine.count(text, layout);
+        let engine = &mut self.text_tesselation_engine;
+        Self::append_tesselation(result, counts, |vertices, indices, batches| {
+            engine.tesselate(text, layout, matrix, vertices, indices, batches)
+        })?;
+        self.pop_transform();
+        Ok(())
+    }
+
     fn push_tiled_indices(indices: &mut Vec<Index>, start: Index) {
         indices.push(start);
         indices.push(start + 1);
@@ -443,6 +668,203 @@ where
         }
     }
 
+    fn produce_shape_triangles(
+        &self,
+        size: Vec2,
+        scale: Vec2,
+        data: &ImageBoxShape,
+        result: &mut Tesselation,
+    ) {
+        let mesh = ShapeMesh::new(data, size, scale.x.min(scale.y));
+        if mesh.indices.is_empty() {
+            return;
+        }
+        let matrix = self.top_transform();
+        let positions = mesh
+            .positions
+            .iter()
+            .map(|p| vec2_to_raui(matrix.mul_point(raui_to_vec2(*p))));
+        let vertices_start =
+            match &mut result.vertices {
+                TesselationVertices::Separated(TesselationVerticesSeparated {
+                    position,
+                    tex_coord,
+                    color,
+                }) => {
+                    let vertices_start = position.len();
+                    position.extend(positions);
+                    tex_coord.resize(position.len(), Default::default());
+                    color.extend(mesh.colors.iter().copied());
+                    vertices_start as Index
+                }
+                TesselationVertices::Interleaved(data) => {
+                    let vertices_start = data.len();
+                    data.extend(positions.zip(mesh.colors.iter()).map(|(p, c)| {
+                        TesselationVerticeInterleaved::new(p, Default::default(), *c)
+                    }));
+                    vertices_start as Index
+                }
+            };
+        let indices_start = result.indices.len();
+        result
+            .indices
+            .extend(mesh.indices.iter().map(|i| vertices_start + i));
+        result
+            .batches
+            .push(Batch::ColoredTriangles(indices_start..result.indices.len()));
+    }
+
+    /// Lines of gradient grid along both axes and optionally skipped inner rectangle of frame.
+    fn gradient_grid(
+        size: Vec2,
+        scale: Vec2,
+        data: &ImageBoxGradient,
+    ) -> (Vec<Scalar>, Vec<Scalar>, Option<Rect>) {
+        let (xs, ys, hole) = match &data.scaling {
+            ImageBoxImageScaling::Stretch => (vec![0.0, size.x], vec![0.0, size.y], None),
+            ImageBoxImageScaling::Frame(frame) => {
+                let mut d = frame.destination;
+                d.left *= scale.x;
+                d.right *= scale.x;
+                d.top *= scale.y;
+                d.bottom *= scale.y;
+                if d.left + d.right > size.x {
+                    let m = d.left + d.right;
+                    d.left = size.x * d.left / m;
+                    d.right = size.x * d.right / m;
+                }
+                if d.top + d.bottom > size.y {
+                    let m = d.top + d.bottom;
+                    d.top = size.y * d.top / m;
+                    d.bottom = size.y * d.bottom / m;
+                }
+                let inner = Rect {
+                    left: d.left,
+                    right: size.x - d.right,
+                    top: d.top,
+                    bottom: size.y - d.bottom,
+                };
+                (
+                    vec![0.0, inner.left, inner.right, size.x],
+                    vec![0.0, inner.top, inner.bottom, size.y],
+                    if frame.frame_only { Some(inner) } else { None },
+                )
+            }
+        };
+        if let ImageBoxGradientKind::Linear { angle } = data.kind {
+            let (sin, cos) = angle.sin_cos();
+            let horizontal = sin.abs() < 1.0e-4;
+            if horizontal || cos.abs() < 1.0e-4 {
+                // colors of axis aligned gradients change linearly between stops, so grid lines
+                // at stops make them exact.
+                let (mut xs, mut ys) = (xs, ys);
+                let (lines, length, flip) = if horizontal {
+                    (&mut xs, size.x, cos < 0.0)
+                } else {
+                    (&mut ys, size.y, sin < 0.0)
+                };
+                lines.extend(
+                    data.stops
+                        .iter()
+                        .filter(|stop| stop.position > 0.0 && stop.position < 1.0)
+                        .map(|stop| {
+                            if flip {
+                                (1.0 - stop.position) * length
+                            } else {
+                                stop.position * length
+                            }
+                        }),
+                );
+                for lines in [&mut xs, &mut ys].iter_mut() {
+                    lines.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                    lines.dedup();
+                }
+                return (xs, ys, hole);
+            }
+        }
+        let subdivide = |breaks: &[Scalar], length: Scalar| {
+            let cell_size = GRADIENT_CELL_SIZE.max(length / GRADIENT_MAX_SUBDIVISIONS as Scalar);
+            let mut result = vec![breaks[0]];
+            for pair in breaks.windows(2) {
+                if pair[1] <= pair[0] {
+                    continue;
+                }
+                let count = ((pair[1] - pair[0]) / cell_size).ceil().max(1.0) as usize;
+                for index in 1..=count {
+                    result.push(lerp(pair[0], pair[1], index as Scalar / count as Scalar));
+                }
+            }
+            result
+        };
+        (subdivide(&xs, size.x), subdivide(&ys, size.y), hole)
+    }
+
+    fn produce_gradient_triangles(
+        &self,
+        size: Vec2,
+        scale: Vec2,
+        data: &ImageBoxGradient,
+        result: &mut Tesselation,
+    ) {
+        let (xs, ys, hole) = Self::gradient_grid(size, scale, data);
+        if xs.len() < 2 || ys.len() < 2 {
+            return;
+        }
+        let matrix = self.top_transform();
+        let points = ys
+            .iter()
+            .flat_map(|y| xs.iter().map(move |x| Vec2 { x: *x, y: *y }));
+        let vertices_start = match &mut result.vertices {
+            TesselationVertices::Separated(TesselationVerticesSeparated {
+                position,
+                tex_coord,
+                color,
+            }) => {
+                let vertices_start = position.len();
+                position.extend(
+                    points
+                        .clone()
+                        .map(|p| vec2_to_raui(matrix.mul_point(raui_to_vec2(p)))),
+                );
+                tex_coord.resize(position.len(), Default::default());
+                color.extend(points.map(|p| data.color_at(p, size)));
+                vertices_start as Index
+            }
+            TesselationVertices::Interleaved(vertices) => {
+                let vertices_start = vertices.len();
+                vertices.extend(points.map(|p| {
+                    TesselationVerticeInterleaved::new(
+                        vec2_to_raui(matrix.mul_point(raui_to_vec2(p))),
+                        Default::default(),
+                        data.color_at(p, size),
+                    )
+                }));
+                vertices_start as Index
+            }
+        };
+        let columns = xs.len();
+        let indices_start = result.indices.len();
+        for row in 0..(ys.len() - 1) {
+            for column in 0..(columns - 1) {
+                if let Some(hole) = hole {
+                    let x = (xs[column] + xs[column + 1]) * 0.5;
+                    let y = (ys[row] + ys[row + 1]) * 0.5;
+                    if x > hole.left && x < hole.right && y > hole.top && y < hole.bottom {
+                        continue;
+                    }
+                }
+                let a = vertices_start + (row * columns + column) as Index;
+                let b = a + columns as Index;
+                result
+                    .indices
+                    .extend_from_slice(&[a, a + 1, b + 1, b + 1, b, a]);
+            }
+        }
+        result
+            .batches
+            .push(Batch::ColoredTriangles(indices_start..result.indices.len()));
+    }
+
     fn produce_image_triangles(
         &self,
         rect: Rect,
@@ -462,6 +884,16 @@ where
                 },
             ),
         };
+        // source rectangle is relative to image, so it gets mapped into its atlas region.
+        let srect = match data.source_rect {
+            Some(source) => Rect {
+                left: lerp(srect.left, srect.right, source.left),
+                right: lerp(srect.left, srect.right, source.right),
+                top: lerp(srect.top, srect.bottom, source.top),
+                bottom: lerp(srect.top, srect.bottom, source.bottom),
+            },
+            None => srect,
+        };
         let matrix = self.top_transform();
         let tl = vec2_to_raui(matrix.mul_point(vek::Vec2::new(rect.left, rect.top)));
         let tr = vec2_to_raui(matrix.mul_point(vek::Vec2::new(rect.right, rect.top)));
@@ -533,8 +965,15 @@ where
                     .image_sizes
                     .get(&id)
                     .map(|size| {
+                        let size = match data.source_rect {
+                            Some(source) => Vec2 {
+                                x: size.x * source.width(),
+                                y: size.y * source.height(),
+                            },
+                            None => *size,
+                        };
                         (
-                            *size,
+                            size,
                             Vec2 {
                                 x: 1.0 / size.x,
                                 y: 1.0 / size.y,
@@ -771,15 +1210,20 @@ where
         }
     }
 
-    fn count(&self, unit: &WidgetUnit, layout: &Layout) -> (usize, usize, usize) {
+    fn count(
+        &self,
+        unit: &WidgetUnit,
+        mapping: &CoordsMapping,
+        layout: &Layout,
+    ) -> (usize, usize, usize) {
         match unit {
             WidgetUnit::None | WidgetUnit::PortalBox(_) => (0, 0, 0),
-            WidgetUnit::AreaBox(unit) => self.count(&unit.slot, layout),
+            WidgetUnit::AreaBox(unit) => self.count(&unit.slot, mapping, layout),
             WidgetUnit::ContentBox(unit) => {
                 if layout.items.contains_key(&unit.id) {
                     let (vertices, indices, mut batches) =
                         unit.items.iter().fold((0, 0, 0), |a, v| {
-                            let v = self.count(&v.slot, layout);
+                            let v = self.count(&v.slot, mapping, layout);
                             (a.0 + v.0, a.1 + v.1, a.2 + v.2)
                         });
                     if unit.clipping {
@@ -793,7 +1237,7 @@ where
             WidgetUnit::FlexBox(unit) => {
                 if layout.items.contains_key(&unit.id) {
                     unit.items.iter().fold((0, 0, 0), |a, v| {
-                        let v = self.count(&v.slot, layout);
+                        let v = self.count(&v.slot, mapping, layout);
                         (a.0 + v.0, a.1 + v.1, a.2 + v.2)
                     })
                 } else {
@@ -803,14 +1247,24 @@ where
             WidgetUnit::GridBox(unit) => {
                 if layout.items.contains_key(&unit.id) {
                     unit.items.iter().fold((0, 0, 0), |a, v| {
-                        let v = self.count(&v.slot, layout);
+                        let v = self.count(&v.slot, mapping, layout);
                         (a.0 + v.0, a.1 + v.1, a.2 + v.2)
                     })
                 } else {
                     (0, 0, 0)
                 }
             }
-            WidgetUnit::SizeBox(unit) => self.count(&unit.slot, layout),
+            WidgetUnit::MasonryBox(unit) => {
+                if layout.items.contains_key(&unit.id) {
+                    unit.items.iter().fold((0, 0, 0), |a, v| {
+                        let v = self.count(&v.slot, mapping, layout);
+                        (a.0 + v.0, a.1 + v.1, a.2 + v.2)
+                    })
+                } else {
+                    (0, 0, 0)
+                }
+            }
+            WidgetUnit::SizeBox(unit) => self.count(&unit.slot, mapping, layout),
             WidgetUnit::ImageBox(unit) => match &unit.material {
                 ImageBoxMaterial::Color(color) => {
                     if layout.items.contains_key(&unit.id) {
@@ -844,11 +1298,78 @@ where
                         (0, 0, 0)
                     }
                 }
+                ImageBoxMaterial::Shape(shape) => {
+                    if let Some(item) = layout.items.get(&unit.id) {
+                        let size = self.real_space(&unit.id, item, mapping, true).size();
+                        let scale = mapping.scale();
+                        let (vertices, indices) =
+                            ShapeMesh::capacity(shape, size, scale.x.min(scale.y));
+                        (vertices, indices, 1)
+                    } else {
+                        (0, 0, 0)
+                    }
+                }
+                ImageBoxMaterial::Gradient(gradient) => {
+                    if let Some(item) = layout.items.get(&unit.id) {
+                        let size = self.real_space(&unit.id, item, mapping, true).size();
+                        let (xs, ys, _) = Self::gradient_grid(size, mapping.scale(), gradient);
+                        let cells = xs.len().saturating_sub(1) * ys.len().saturating_sub(1);
+                        (xs.len() * ys.len(), cells * 6, 1)
+                    } else {
+                        (0, 0, 0)
+                    }
+                }
                 _ => (0, 0, 0),
             },
             WidgetUnit::TextBox(unit) => {
                 if let Some(item) = layout.items.get(&unit.id) {
-                    self.text_tesselation_engine.count(unit, item)
+                    if item.fragments.is_empty() {
+                        self.text_tesselation_engine.count(unit, item)
+                    } else {
+                        item.fragments.iter().fold((0, 0, 0), |a, fragment| {
+                            let v = match Self::fragment_text_box(
+                                &unit.id,
+                                &unit.font.name,
+                                unit.color,
+                                item,
+                                fragment,
+                            ) {
+                                Some((text, item)) => {
+                                    self.text_tesselation_engine.count(&text, &item)
+                                }
+                                None => (0, 0, 0),
+                            };
+                            (a.0 + v.0, a.1 + v.1, a.2 + v.2)
+                        })
+                    }
+                } else {
+                    (0, 0, 0)
+                }
+            }
+            WidgetUnit::RichTextBox(unit) => {
+                if let Some(item) = layout.items.get(&unit.id) {
+                    item.fragments.iter().fold((0, 0, 0), |a, fragment| {
+                        let v = match unit.spans.get(fragment.span).map(|span| &span.content) {
+                            Some(RichTextBoxSpanContent::Image(_)) => (4, 6, 1),
+                            _ => match Self::rich_text_fragment_text(unit, item, fragment) {
+                                Some((text, item)) => {
+                                    self.text_tesselation_engine.count(&text, &item)
+                                }
+                                None => (0, 0, 0),
+                            },
+                        };
+                        (a.0 + v.0, a.1 + v.1, a.2 + v.2)
+                    })
+                } else {
+                    (0, 0, 0)
+                }
+            }
+            WidgetUnit::CustomBox(unit) => {
+                if let Some(item) = layout.items.get(&unit.id) {
+                    match self.custom_tesselation_engines.0.get(&unit.type_name) {
+                        Some(engine) => engine.count(unit, item),
+                        None => (0, 0, 1),
+                    }
                 } else {
                     (0, 0, 0)
                 }
@@ -856,6 +1377,47 @@ where
         }
     }
 
+    /// Moves batches produced since `start` to given render layer, closing and opening clip
+    /// rectangles of that layer to match clip rectangles of currently rendered node.
+    fn move_batches_to_layer(&mut self, result: &mut Tesselation, start: usize, layer: Integer) {
+        if result.batches.len() <= start {
+            return;
+        }
+        let (batches, clips) = self.layers.entry(layer).or_default();
+        let common = clips
+            .iter()
+            .zip(self.clip_stack.iter())
+            .take_while(|(a, (b, _))| *a == b)
+            .count();
+        batches.extend((common..clips.len()).map(|_| Batch::ClipPop));
+        clips.truncate(common);
+        for (id, rect) in &self.clip_stack[common..] {
+            batches.push(Batch::ClipPush(rect.to_owned()));
+            clips.push(*id);
+        }
+        batches.extend(result.batches.drain(start..));
+    }
+
+    /// Real space of item relative to its parent, or to screen when rendered as root.
+    fn real_space(
+        &self,
+        id: &WidgetId,
+        item: &LayoutItem,
+        mapping: &CoordsMapping,
+        local: bool,
+    ) -> Rect {
+        match self
+            .snapped_layout
+            .as_ref()
+            .and_then(|layout| layout.items.get(id))
+        {
+            Some(item) if local => item.local_space,
+            Some(item) => item.ui_space,
+            None => mapping.virtual_to_real_rect(item.local_space, local),
+        }
+    }
+
+    /// Renders unit with its descendants, batches of units get sorted into their render layers.
     fn render_node(
         &mut self,
         unit: &WidgetUnit,
@@ -863,12 +1425,28 @@ where
         layout: &Layout,
         result: &mut Tesselation,
         local: bool,
+    ) -> Result<(), Error> {
+        let batches_start = result.batches.len();
+        self.render_unit(unit, mapping, layout, result, local)?;
+        if let Some(item) = unit.as_data().and_then(|data| layout.items.get(data.id())) {
+            self.move_batches_to_layer(result, batches_start, item.layer);
+        }
+        Ok(())
+    }
+
+    fn render_unit(
+        &mut self,
+        unit: &WidgetUnit,
+        mapping: &CoordsMapping,
+        layout: &Layout,
+        result: &mut Tesselation,
+        local: bool,
     ) -> Result<(), Error> {
         match unit {
             WidgetUnit::None | WidgetUnit::PortalBox(_) => Ok(()),
             WidgetUnit::AreaBox(unit) => {
                 if let Some(item) = layout.items.get(&unit.id) {
-                    let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
                     self.push_transform_simple(local_space);
                     self.render_node(&unit.slot, mapping, layout, result, true)?;
                     self.pop_transform();
@@ -885,19 +1463,21 @@ where
                         .map(|item| (item.layout.depth, item))
                         .collect::<Vec<_>>();
                     items.sort_by(|(a, _), (b, _)| a.partial_cmp(&b).unwrap());
-                    let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
                     self.push_transform(&unit.transform, local_space);
                     if unit.clipping {
-                        result.batches.push(Batch::ClipPush(BatchClipRect {
+                        let rect = BatchClipRect {
                             box_size: local_space.size(),
                             matrix: self.top_transform().into_col_array(),
-                        }));
+                        };
+                        self.clip_stack.push((self.clips_count, rect));
+                        self.clips_count += 1;
                     }
                     for (_, item) in items {
                         self.render_node(&item.slot, mapping, layout, result, true)?;
                     }
                     if unit.clipping {
-                        result.batches.push(Batch::ClipPop);
+                        self.clip_stack.pop();
                     }
                     self.pop_transform();
                     Ok(())
@@ -907,7 +1487,7 @@ where
             }
             WidgetUnit::FlexBox(unit) => {
                 if let Some(item) = layout.items.get(&unit.id) {
-                    let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
                     self.push_transform(&unit.transform, local_space);
                     for item in &unit.items {
                         self.render_node(&item.slot, mapping, layout, result, true)?;
@@ -920,7 +1500,20 @@ where
             }
             WidgetUnit::GridBox(unit) => {
                 if let Some(item) = layout.items.get(&unit.id) {
-                    let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
+                    self.push_transform(&unit.transform, local_space);
+                    for item in &unit.items {
+                        self.render_node(&item.slot, mapping, layout, result, true)?;
+                    }
+                    self.pop_transform();
+                    Ok(())
+                } else {
+                    Err(Error::WidgetHasNoLayout(unit.id.to_owned()))
+                }
+            }
+            WidgetUnit::MasonryBox(unit) => {
+                if let Some(item) = layout.items.get(&unit.id) {
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
                     self.push_transform(&unit.transform, local_space);
                     for item in &unit.items {
                         self.render_node(&item.slot, mapping, layout, result, true)?;
@@ -933,7 +1526,7 @@ where
             }
             WidgetUnit::SizeBox(unit) => {
                 if let Some(item) = layout.items.get(&unit.id) {
-                    let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
                     self.push_transform(&unit.transform, local_space);
                     self.render_node(&unit.slot, mapping, layout, result, true)?;
                     self.pop_transform();
@@ -945,7 +1538,7 @@ where
             WidgetUnit::ImageBox(unit) => match &unit.material {
                 ImageBoxMaterial::Color(color) => {
                     if let Some(item) = layout.items.get(&unit.id) {
-                        let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                        let local_space = self.real_space(&unit.id, item, mapping, local);
                         self.push_transform(&unit.transform, local_space);
                         self.produce_color_triangles(
                             local_space.size(),
@@ -961,7 +1554,7 @@ where
                 }
                 ImageBoxMaterial::Image(image) => {
                     if let Some(item) = layout.items.get(&unit.id) {
-                        let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                        let local_space = self.real_space(&unit.id, item, mapping, local);
                         let rect = Rect {
                             left: 0.0,
                             right: local_space.width(),
@@ -1006,85 +1599,122 @@ where
                         Err(Error::WidgetHasNoLayout(unit.id.to_owned()))
                     }
                 }
+                ImageBoxMaterial::Shape(shape) => {
+                    if let Some(item) = layout.items.get(&unit.id) {
+                        let local_space = self.real_space(&unit.id, item, mapping, local);
+                        self.push_transform(&unit.transform, local_space);
+                        self.produce_shape_triangles(
+                            local_space.size(),
+                            mapping.scale(),
+                            shape,
+                            result,
+                        );
+                        self.pop_transform();
+                        Ok(())
+                    } else {
+                        Err(Error::WidgetHasNoLayout(unit.id.to_owned()))
+                    }
+                }
+                ImageBoxMaterial::Gradient(gradient) => {
+                    if let Some(item) = layout.items.get(&unit.id) {
+                        let local_space = self.real_space(&unit.id, item, mapping, local);
+                        self.push_transform(&unit.transform, local_space);
+                        self.produce_gradient_triangles(
+                            local_space.size(),
+                            mapping.scale(),
+                            gradient,
+                            result,
+                        );
+                        self.pop_transform();
+                        Ok(())
+                    } else {
+                        Err(Error::WidgetHasNoLayout(unit.id.to_owned()))
+                    }
+                }
                 _ => Err(Error::UnsupportedImageMaterial(unit.material.clone())),
             },
             WidgetUnit::TextBox(unit) => {
                 if let Some(item) = layout.items.get(&unit.id) {
-                    let local_space = mapping.virtual_to_real_rect(item.local_space, local);
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
                     self.push_transform(&unit.transform, local_space);
-                    let matrix = self.top_transform().into_col_array();
-                    let (vertices, indices, batches) =
-                        self.text_tesselation_engine.count(unit, item);
-                    let batches_start = result.batches.len();
-                    let indices_start = result.indices.len();
-                    result
-                        .batches
-                        .resize(batches_start + batches, Default::default());
-                    result.indices.resize(indices_start + indices, 0);
-                    let vertices_start = match &mut result.vertices {
-                        TesselationVertices::Separated(TesselationVerticesSeparated {
-                            position,
-                            tex_coord,
-                            color,
-                        }) => {
-                            let vertices_start = position.len();
-                            position.resize(vertices_start + vertices, Default::default());
-                            tex_coord.resize(vertices_start + vertices, Default::default());
-                            color.resize(vertices_start + vertices, Default::default());
-                            self.text_tesselation_engine.tesselate(
-                                unit,
+                    if item.fragments.is_empty() {
+                        let matrix = self.top_transform().into_col_array();
+                        let counts = self.text_tesselation_engine.count(unit, item);
+                        let engine = &mut self.text_tesselation_engine;
+                        Self::append_tesselation(result, counts, |vertices, indices, batches| {
+                            engine.tesselate(unit, item, matrix, vertices, indices, batches)
+                        })?;
+                    } else {
+                        for fragment in &item.fragments {
+                            if let Some((text, item)) = Self::fragment_text_box(
+                                &unit.id,
+                                &unit.font.name,
+                                unit.color,
                                 item,
-                                matrix,
-                                TesselationVerticesSliceMut::Separated(
-                                    TesselationVerticesSeparatedSliceMut {
-                                        position: &mut position
-                                            [vertices_start..(vertices_start + vertices)],
-                                        tex_coord: &mut tex_coord
-                                            [vertices_start..(vertices_start + vertices)],
-                                        color: &mut color
-                                            [vertices_start..(vertices_start + vertices)],
-                                    },
-                                ),
-                                &mut result.indices[indices_start..(indices_start + indices)],
-                                &mut result.batches[batches_start..(batches_start + batches)],
-                            )?;
-                            vertices_start as Index
+                                fragment,
+                            ) {
+                                let rect = mapping.virtual_to_real_rect(fragment.rect, true);
+                                self.produce_fragment_text(&text, &item, rect, result)?;
+                            }
                         }
-                        TesselationVertices::Interleaved(data) => {
-                            let vertices_start = data.len();
-                            data.resize(vertices_start + vertices, Default::default());
-                            self.text_tesselation_engine.tesselate(
-                                unit,
-                                item,
-                                matrix,
-                                TesselationVerticesSliceMut::Interleaved(
-                                    &mut data[vertices_start..(vertices_start + vertices)],
-                                ),
-                                &mut result.indices[indices_start..(indices_start + indices)],
-                                &mut result.batches[batches_start..(batches_start + batches)],
-                            )?;
-                            vertices_start as Index
+                    }
+                    self.pop_transform();
+                    Ok(())
+                } else {
+                    Err(Error::WidgetHasNoLayout(unit.id.to_owned()))
+                }
+            }
+            WidgetUnit::RichTextBox(unit) => {
+                if let Some(item) = layout.items.get(&unit.id) {
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
+                    self.push_transform(&unit.transform, local_space);
+                    for fragment in &item.fragments {
+                        let rect = mapping.virtual_to_real_rect(fragment.rect, true);
+                        if let Some(RichTextBoxSpanContent::Image(image)) =
+                            unit.spans.get(fragment.span).map(|span| &span.content)
+                        {
+                            let image = ImageBoxImage {
+                                id: image.id.to_owned(),
+                                tint: image.tint,
+                                ..Default::default()
+                            };
+                            self.produce_image_triangles(rect, mapping.scale(), &image, result);
+                        } else if let Some((text, item)) =
+                            Self::rich_text_fragment_text(unit, item, fragment)
+                        {
+                            self.produce_fragment_text(&text, &item, rect, result)?;
                         }
-                    };
-                    for item in &mut result.indices[indices_start..(indices_start + indices)] {
-                        *item += vertices_start;
                     }
-                    for item in &mut result.batches[batches_start..(batches_start + batches)] {
-                        match item {
-                            Batch::ColoredTriangles(range) => {
-                                range.start += indices_start;
-                                range.end += indices_start;
-                            }
-                            Batch::ImageTriangles(_, range) => {
-                                range.start += indices_start;
-                                range.end += indices_start;
-                            }
-                            Batch::FontTriangles(_, _, range) => {
-                                range.start += indices_start;
-                                range.end += indices_start;
-                            }
-                            _ => {}
+                    self.pop_transform();
+                    Ok(())
+                } else {
+                    Err(Error::WidgetHasNoLayout(unit.id.to_owned()))
+                }
+            }
+            WidgetUnit::CustomBox(unit) => {
+                if let Some(item) = layout.items.get(&unit.id) {
+                    let local_space = self.real_space(&unit.id, item, mapping, local);
+                    self.push_transform(&unit.transform, local_space);
+                    let matrix = self.top_transform().into_col_array();
+                    match self.custom_tesselation_engines.0.get_mut(&unit.type_name) {
+                        Some(engine) => {
+                            let counts = engine.count(unit, item);
+                            Self::append_tesselation(
+                                result,
+                                counts,
+                                |vertices, indices, batches| {
+                                    engine.tesselate(unit, item, matrix, vertices, indices, batches)
+                                },
+                            )?;
                         }
+                        None => result.batches.push(Batch::ExternalCustom(
+                            unit.id.to_owned(),
+                            BatchExternalCustom {
+                                type_name: unit.type_name.to_owned(),
+                                box_size: local_space.size(),
+                                matrix,
+                            },
+                        )),
                     }
                     self.pop_transform();
                     Ok(())
@@ -1107,7 +1737,15 @@ where
         layout: &Layout,
     ) -> Result<Tesselation, Error> {
         self.transform_stack.clear();
-        let (vertices, indices, batches) = self.count(tree, layout);
+        self.clip_stack.clear();
+        self.clips_count = 0;
+        self.layers.clear();
+        self.snapped_layout = if mapping.pixel_snapping() {
+            Some(layout.virtual_to_real(mapping))
+        } else {
+            None
+        };
+        let (vertices, indices, batches) = self.count(tree, mapping, layout);
         let mut result = Tesselation {
             vertices: match self.vertices_format {
                 TesselationVerticesFormat::Separated => {
@@ -1125,6 +1763,11 @@ where
             batches: Vec::with_capacity(batches),
         };
         self.render_node(tree, mapping, layout, &mut result, false)?;
+        for (_, (batches, clips)) in std::mem::take(&mut self.layers) {
+            result.batches.extend(batches);
+            result.batches.extend(clips.iter().map(|_| Batch::ClipPop));
+        }
+        self.snapped_layout = None;
         Ok(result)
     }
 }
@@ -0,0 +1,484 @@
+//! Triangulation of vector shapes (see [`ImageBoxShape`])
+
+use crate::Index;
+use raui_core::{
+    widget::{
+        unit::image::{ImageBoxCornerRadius, ImageBoxShape, ImageBoxShapeKind},
+        utils::{Color, Vec2},
+    },
+    Scalar,
+};
+#[cfg(not(feature = "scalar64"))]
+use std::f32::consts::PI;
+#[cfg(feature = "scalar64")]
+use std::f64::consts::PI;
+
+/// Maximal distance of curve segments from actual curve
+const CURVE_TOLERANCE: Scalar = 0.25;
+const CURVE_MAX_SEGMENTS: usize = 256;
+/// Maximal length of miter joins of lines, relative to half of line width
+const MITER_LIMIT: Scalar = 4.0;
+
+/// Colored triangles of shape in its local space
+#[derive(Debug, Default, Clone)]
+pub struct ShapeMesh {
+    pub positions: Vec<Vec2>,
+    pub colors: Vec<Color>,
+    pub indices: Vec<Index>,
+}
+
+impl ShapeMesh {
+    /// Triangulates shape fitted into rectangle of given size. Border width and corner radii are
+    /// multiplied by `scale`.
+    pub fn new(shape: &ImageBoxShape, size: Vec2, scale: Scalar) -> Self {
+        let mut result = Self::default();
+        let border = shape.border.width * scale;
+        match &shape.kind {
+            ImageBoxShapeKind::Rectangle { corner_radius } => {
+                let radius = scale_corner_radius(corner_radius, scale, size);
+                let outer = rounded_rect_path(size, radius, 0.0);
+                if border > 0.0 {
+                    let inner = rounded_rect_path(size, radius, border);
+                    result.fill_convex(&inner, shape.color);
+                    result.fill_strip(&outer, &inner, true, shape.border.color);
+                } else {
+                    result.fill_convex(&outer, shape.color);
+                }
+            }
+            ImageBoxShapeKind::Ellipse => {
+                let center = Vec2 {
+                    x: size.x * 0.5,
+                    y: size.y * 0.5,
+                };
+                let segments = curve_segments(center.x.max(center.y), PI * 2.0);
+                let mut outer = ellipse_points(center, center, 0.0, PI * 2.0, segments);
+                outer.pop();
+                if border > 0.0 {
+                    let radius = Vec2 {
+                        x: (center.x - border).max(0.0),
+                        y: (center.y - border).max(0.0),
+                    };
+                    let mut inner = ellipse_points(center, radius, 0.0, PI * 2.0, segments);
+                    inner.pop();
+                    result.fill_convex(&inner, shape.color);
+                    result.fill_strip(&outer, &inner, true, shape.border.color);
+                } else {
+                    result.fill_convex(&outer, shape.color);
+                }
+            }
+            ImageBoxShapeKind::Polygon(points) => {
+                let points = fit_points(points, size);
+                result.fill_polygon(&points, shape.color);
+                if border > 0.0 {
+                    result.stroke(&points, border, true, shape.border.color);
+                }
+            }
+            ImageBoxShapeKind::Polyline(points) => {
+                let points = fit_points(points, size);
+                result.stroke(&points, border, false, shape.border.color);
+            }
+            ImageBoxShapeKind::Arc {
+                start_angle,
+                end_angle,
+            } => {
+                if border > 0.0 {
+                    let center = Vec2 {
+                        x: size.x * 0.5,
+                        y: size.y * 0.5,
+                    };
+                    let radius = Vec2 {
+                        x: (center.x - border).max(0.0),
+                        y: (center.y - border).max(0.0),
+                    };
+                    let sweep = end_angle - start_angle;
+                    let segments = curve_segments(center.x.max(center.y), sweep);
+                    let outer = ellipse_points(center, center, *start_angle, *end_angle, segments);
+                    let inner = ellipse_points(center, radius, *start_angle, *end_angle, segments);
+                    result.fill_strip(&outer, &inner, false, shape.border.color);
+                }
+            }
+        }
+        result
+    }
+
+    /// Upper bound of vertices and indices count of shape mesh, calculated without triangulating
+    /// shape.
+    pub fn capacity(shape: &ImageBoxShape, size: Vec2, scale: Scalar) -> (usize, usize) {
+        // fills take n vertices and less than 3n indices, strips along paths take 2n vertices
+        // and up to 6n indices.
+        match &shape.kind {
+            ImageBoxShapeKind::Rectangle { corner_radius } => {
+                let count = rounded_rect_path_len(&scale_corner_radius(corner_radius, scale, size));
+                (count * 3, count * 9)
+            }
+            ImageBoxShapeKind::Ellipse => {
+                let count = curve_segments(size.x.max(size.y) * 0.5, PI * 2.0);
+                (count * 3, count * 9)
+            }
+            ImageBoxShapeKind::Polygon(points) => (points.len() * 3, points.len() * 9),
+            ImageBoxShapeKind::Polyline(points) => (points.len() * 2, points.len() * 6),
+            ImageBoxShapeKind::Arc {
+                start_angle,
+                end_angle,
+            } => {
+                let count = curve_segments(size.x.max(size.y) * 0.5, end_angle - start_angle) + 1;
+                (count * 2, count * 6)
+            }
+        }
+    }
+
+    fn push_vertex(&mut self, position: Vec2, color: Color) -> Index {
+        let index = self.positions.len() as Index;
+        self.positions.push(position);
+        self.colors.push(color);
+        index
+    }
+
+    fn fill_convex(&mut self, points: &[Vec2], color: Color) {
+        if points.len() < 3 || color.a <= 0.0 {
+            return;
+        }
+        let start = self.positions.len() as Index;
+        for point in points {
+            self.push_vertex(*point, color);
+        }
+        for index in 1..(points.len() as Index - 1) {
+            self.indices
+                .extend_from_slice(&[start, start + index, start + index + 1]);
+        }
+    }
+
+    fn fill_polygon(&mut self, points: &[Vec2], color: Color) {
+        if points.len() < 3 || color.a <= 0.0 {
+            return;
+        }
+        let start = self.positions.len() as Index;
+        for point in points {
+            self.push_vertex(*point, color);
+        }
+        for [a, b, c] in triangulate_polygon(points) {
+            self.indices.extend_from_slice(&[
+                start + a as Index,
+                start + b as Index,
+                start + c as Index,
+            ]);
+        }
+    }
+
+    /// Fills space between two paths with the same number of points.
+    fn fill_strip(&mut self, outer: &[Vec2], inner: &[Vec2], closed: bool, color: Color) {
+        if outer.len() < 2 || outer.len() != inner.len() || color.a <= 0.0 {
+            return;
+        }
+        let start = self.positions.len() as Index;
+        for (outer, inner) in outer.iter().zip(inner.iter()) {
+            self.push_vertex(*outer, color);
+            self.push_vertex(*inner, color);
+        }
+        let count = outer.len() as Index;
+        let segments = if closed { count } else { count - 1 };
+        for index in 0..segments {
+            let a = start + index * 2;
+            let b = start + ((index + 1) % count) * 2;
+            self.indices
+                .extend_from_slice(&[a, b, b + 1, b + 1, a + 1, a]);
+        }
+    }
+
+    /// Draws line of given width centered on path, with mitered joins.
+    fn stroke(&mut self, points: &[Vec2], width: Scalar, closed: bool, color: Color) {
+        let mut path = Vec::with_capacity(points.len());
+        for point in points {
+            if path.last().map(|last| *last != *point).unwrap_or(true) {
+                path.push(*point);
+            }
+        }
+        if closed && path.len() > 2 && path.first() == path.last() {
+            path.pop();
+        }
+        if path.len() < 2 || width <= 0.0 || color.a <= 0.0 {
+            return;
+        }
+        let half = width * 0.5;
+        let count = path.len();
+        let mut left = Vec::with_capacity(count);
+        let mut right = Vec::with_capacity(count);
+        for index in 0..count {
+            let point = path[index];
+            let prev = if index > 0 {
+                Some(path[index - 1])
+            } else if closed {
+                Some(path[count - 1])
+            } else {
+                None
+            };
+            let next = if index + 1 < count {
+                Some(path[index + 1])
+            } else if closed {
+                Some(path[0])
+            } else {
+                None
+            };
+            let normal_in = prev.map(|prev| segment_normal(prev, point));
+            let normal_out = next.map(|next| segment_normal(point, next));
+            let offset = match (normal_in, normal_out) {
+                (Some(a), Some(b)) => {
+                    let miter = normalize(Vec2 {
+                        x: a.x + b.x,
+                        y: a.y + b.y,
+                    });
+                    let dot = miter.x * b.x + miter.y * b.y;
+                    let length = if dot > 1.0e-4 {
+                        (half / dot).min(half * MITER_LIMIT)
+                    } else {
+                        half
+                    };
+                    Vec2 {
+                        x: miter.x * length,
+                        y: miter.y * length,
+                    }
+                }
+                (Some(n), None) | (None, Some(n)) => Vec2 {
+                    x: n.x * half,
+                    y: n.y * half,
+                },
+                (None, None) => Vec2::default(),
+            };
+            left.push(Vec2 {
+                x: point.x + offset.x,
+                y: point.y + offset.y,
+            });
+            right.push(Vec2 {
+                x: point.x - offset.x,
+                y: point.y - offset.y,
+            });
+        }
+        self.fill_strip(&left, &right, closed, color);
+    }
+}
+
+fn normalize(v: Vec2) -> Vec2 {
+    let length = (v.x * v.x + v.y * v.y).sqrt();
+    if length > 0.0 {
+        Vec2 {
+            x: v.x / length,
+            y: v.y / length,
+        }
+    } else {
+        Vec2::default()
+    }
+}
+
+fn segment_normal(from: Vec2, to: Vec2) -> Vec2 {
+    normalize(Vec2 {
+        x: from.y - to.y,
+        y: to.x - from.x,
+    })
+}
+
+fn cross(a: Vec2, b: Vec2, c: Vec2) -> Scalar {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Number of segments approximating arc of given radius and angle.
+fn curve_segments(radius: Scalar, angle: Scalar) -> usize {
+    let angle = angle.abs();
+    if radius <= CURVE_TOLERANCE || angle <= 0.0 {
+        return 1;
+    }
+    let step = 2.0 * (1.0 - CURVE_TOLERANCE / radius).acos();
+    ((angle / step).ceil() as usize).clamp(1, CURVE_MAX_SEGMENTS)
+}
+
+/// Points of ellipse arc, including both its ends.
+fn ellipse_points(
+    center: Vec2,
+    radius: Vec2,
+    start_angle: Scalar,
+    end_angle: Scalar,
+    segments: usize,
+) -> Vec<Vec2> {
+    (0..=segments)
+        .map(|index| {
+            let angle =
+                start_angle + (end_angle - start_angle) * index as Scalar / segments as Scalar;
+            Vec2 {
+                x: center.x + radius.x * angle.cos(),
+                y: center.y + radius.y * angle.sin(),
+            }
+        })
+        .collect()
+}
+
+fn fit_points(points: &[Vec2], size: Vec2) -> Vec<Vec2> {
+    points
+        .iter()
+        .map(|point| Vec2 {
+            x: point.x * size.x,
+            y: point.y * size.y,
+        })
+        .collect()
+}
+
+/// Scales corner radii and shrinks them proportionally when adjacent corners do not fit sides.
+fn scale_corner_radius(
+    radius: &ImageBoxCornerRadius,
+    scale: Scalar,
+    size: Vec2,
+) -> ImageBoxCornerRadius {
+    let radius = ImageBoxCornerRadius {
+        top_left: radius.top_left.max(0.0) * scale,
+        top_right: radius.top_right.max(0.0) * scale,
+        bottom_right: radius.bottom_right.max(0.0) * scale,
+        bottom_left: radius.bottom_left.max(0.0) * scale,
+    };
+    let fit = |side: Scalar, a: Scalar, b: Scalar| {
+        if a + b > side {
+            side.max(0.0) / (a + b)
+        } else {
+            1.0
+        }
+    };
+    let factor = fit(size.x, radius.top_left, radius.top_right)
+        .min(fit(size.x, radius.bottom_left, radius.bottom_right))
+        .min(fit(size.y, radius.top_left, radius.bottom_left))
+        .min(fit(size.y, radius.top_right, radius.bottom_right));
+    ImageBoxCornerRadius {
+        top_left: radius.top_left * factor,
+        top_right: radius.top_right * factor,
+        bottom_right: radius.bottom_right * factor,
+        bottom_left: radius.bottom_left * factor,
+    }
+}
+
+/// Number of points of rounded rectangle path with given radii.
+fn rounded_rect_path_len(radius: &ImageBoxCornerRadius) -> usize {
+    [
+        radius.top_left,
+        radius.top_right,
+        radius.bottom_right,
+        radius.bottom_left,
+    ]
+    .iter()
+    .map(|radius| {
+        if *radius > 0.0 {
+            curve_segments(*radius, PI * 0.5) + 1
+        } else {
+            1
+        }
+    })
+    .sum()
+}
+
+/// Clockwise path of rounded rectangle shrunk by `inset`. Paths with the same radii have the same
+/// number of points regardless of inset.
+fn rounded_rect_path(size: Vec2, radius: ImageBoxCornerRadius, inset: Scalar) -> Vec<Vec2> {
+    let inset = inset.min(size.x * 0.5).min(size.y * 0.5);
+    let corners = [
+        (radius.top_left, Vec2 { x: 0.0, y: 0.0 }, PI),
+        (radius.top_right, Vec2 { x: 1.0, y: 0.0 }, PI * 1.5),
+        (radius.bottom_right, Vec2 { x: 1.0, y: 1.0 }, 0.0),
+        (radius.bottom_left, Vec2 { x: 0.0, y: 1.0 }, PI * 0.5),
+    ];
+    let mut result = vec![];
+    for (radius, side, angle) in corners.iter().copied() {
+        let inner_radius = (radius - inset).max(0.0);
+        // corner arcs are centered at the same points for both outer and inset paths.
+        let center = Vec2 {
+            x: if side.x > 0.0 {
+                size.x - radius
+            } else {
+                radius
+            },
+            y: if side.y > 0.0 {
+                size.y - radius
+            } else {
+                radius
+            },
+        };
+        let offset = (inset - radius).max(0.0);
+        let center = Vec2 {
+            x: if side.x > 0.0 {
+                center.x - offset
+            } else {
+                center.x + offset
+            },
+            y: if side.y > 0.0 {
+                center.y - offset
+            } else {
+                center.y + offset
+            },
+        };
+        if radius > 0.0 {
+            let segments = curve_segments(radius, PI * 0.5);
+            result.extend(ellipse_points(
+                center,
+                Vec2 {
+                    x: inner_radius,
+                    y: inner_radius,
+                },
+                angle,
+                angle + PI * 0.5,
+                segments,
+            ));
+        } else {
+            result.push(center);
+        }
+    }
+    result
+}
+
+/// Triangulates simple polygon using ear clipping, returns triangles as indices of points.
+fn triangulate_polygon(points: &[Vec2]) -> Vec<[usize; 3]> {
+    let mut remaining = (0..points.len()).collect::<Vec<_>>();
+    let area = (0..points.len()).fold(0.0, |area, index| {
+        let a = points[index];
+        let b = points[(index + 1) % points.len()];
+        area + a.x * b.y - b.x * a.y
+    });
+    if area < 0.0 {
+        remaining.reverse();
+    }
+    let mut result = Vec::with_capacity(points.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|index| {
+            let a = points[remaining[(index + count - 1) % count]];
+            let b = points[remaining[*index]];
+            let c = points[remaining[(index + 1) % count]];
+            if cross(a, b, c) <= 0.0 {
+                return false;
+            }
+            !remaining.iter().any(|other| {
+                let p = points[*other];
+                p != a
+                    && p != b
+                    && p != c
+                    && cross(a, b, p) >= 0.0
+                    && cross(b, c, p) >= 0.0
+                    && cross(c, a, p) >= 0.0
+            })
+        });
+        match ear {
+            Some(index) => {
+                result.push([
+                    remaining[(index + count - 1) % count],
+                    remaining[index],
+                    remaining[(index + 1) % count],
+                ]);
+                remaining.remove(index);
+            }
+            None => {
+                // self intersecting or degenerated polygons get filled with triangle fan.
+                for index in 1..(count - 1) {
+                    result.push([remaining[0], remaining[index], remaining[index + 1]]);
+                }
+                return result;
+            }
+        }
+    }
+    if remaining.len() == 3 {
+        result.push([remaining[0], remaining[1], remaining[2]]);
+    }
+    result
+}
@@ -1,7 +1,7 @@
 use crate::Index;
 use raui_core::{
     widget::{
-        unit::text::{TextBoxDirection, TextBoxHorizontalAlign, TextBoxVerticalAlign},
+        unit::text::{TextBoxDirection, TextBoxHorizontalAlign, TextBoxVerticalAlign, TextBoxWrap},
         utils::{Color, Vec2},
         WidgetId,
     },
@@ -20,6 +20,16 @@ pub struct BatchExternalText {
     pub horizontal_align: TextBoxHorizontalAlign,
     pub vertical_align: TextBoxVerticalAlign,
     pub direction: TextBoxDirection,
+    /// Lines laid out by layout engine are not wrapped again
+    pub wrap: TextBoxWrap,
+    pub matrix: [Scalar; 16],
+}
+
+/// Custom unit without registered tesselation, to be rendered by host application
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BatchExternalCustom {
+    pub type_name: String,
+    pub box_size: Vec2,
     pub matrix: [Scalar; 16],
 }
 
@@ -36,6 +46,7 @@ pub enum Batch {
     ImageTriangles(String, Range<usize>),
     FontTriangles(String, Scalar, Range<usize>),
     ExternalText(WidgetId, BatchExternalText),
+    ExternalCustom(WidgetId, BatchExternalCustom),
     ClipPush(BatchClipRect),
     ClipPop,
 }
@@ -5,7 +5,11 @@ use raui_core::{
     widget::{
         unit::{
             image::ImageBoxMaterial,
-            text::{TextBoxDirection, TextBoxHorizontalAlign, TextBoxVerticalAlign},
+            rich_text::RichTextBoxSpanContent,
+            text::{
+                TextBoxDirection, TextBoxFont, TextBoxHorizontalAlign, TextBoxVerticalAlign,
+                TextBoxWrap,
+            },
             WidgetUnit,
         },
         utils::Vec2 as RauiVec2,
@@ -89,61 +93,98 @@ where
     }
 
     /// Loads any missing textures or fonts that have not been preloaded
-    fn try_load_missing_resources(&mut self, tree: &WidgetUnit) -> Result<(), Error> {
+    fn try_load_missing_resources(
+        &mut self,
+        tree: &WidgetUnit,
+        layout: &Layout,
+    ) -> Result<(), Error> {
         match tree {
             WidgetUnit::AreaBox(area_box) => {
-                self.try_load_missing_resources(&area_box.slot)?;
+                self.try_load_missing_resources(&area_box.slot, layout)?;
             }
             WidgetUnit::ContentBox(content_box) => {
                 for item in &content_box.items {
-                    self.try_load_missing_resources(&item.slot)?;
+                    self.try_load_missing_resources(&item.slot, layout)?;
                 }
             }
             WidgetUnit::FlexBox(flex_box) => {
                 for item in &flex_box.items {
-                    self.try_load_missing_resources(&item.slot)?;
+                    self.try_load_missing_resources(&item.slot, layout)?;
                 }
             }
             WidgetUnit::GridBox(grid_box) => {
                 for item in &grid_box.items {
-                    self.try_load_missing_resources(&item.slot)?;
+                    self.try_load_missing_resources(&item.slot, layout)?;
+                }
+            }
+            WidgetUnit::MasonryBox(masonry_box) => {
+                for item in &masonry_box.items {
+                    self.try_load_missing_resources(&item.slot, layout)?;
                 }
             }
             WidgetUnit::SizeBox(size_box) => {
-                self.try_load_missing_resources(&size_box.slot)?;
+                self.try_load_missing_resources(&size_box.slot, layout)?;
             }
             WidgetUnit::ImageBox(image_box) => match &image_box.material {
-                ImageBoxMaterial::Image(image) => {
-                    if !self.resources.textures.contains_key(&image.id) {
-                        self.resources.textures.insert(
-                            image.id.clone(),
-                            Texture::new(self.context, image.id.clone())
-                                .map_err(|e| Error::ImageResourceNotFound(e.to_string()))?,
-                        );
-                    }
-                }
-                ImageBoxMaterial::Color(_) | ImageBoxMaterial::Procedural(_) => {}
+                ImageBoxMaterial::Image(image) => self.try_load_missing_texture(&image.id)?,
+                ImageBoxMaterial::Color(_)
+                | ImageBoxMaterial::Procedural(_)
+                | ImageBoxMaterial::Shape(_)
+                | ImageBoxMaterial::Gradient(_) => {}
             },
             WidgetUnit::TextBox(text_box) => {
-                let font = &text_box.font;
-                if !self
-                    .resources
-                    .fonts
-                    .contains_key(&format!("{}:{}", font.name, font.size))
-                {
-                    self.resources.fonts.insert(
-                        format!("{}:{}", font.name, font.size),
-                        (
-                            1.0,
-                            Font::vector(self.context, font.name.clone(), font.size as Scalar)
-                                .map_err(|e| Error::FontResourceNotFound(e.to_string()))?,
-                        ),
-                    );
+                self.try_load_missing_font(&text_box.font)?;
+                // lines laid out by layout engine might use font shrunk to fit text box.
+                if let Some(item) = layout.items.get(&text_box.id) {
+                    for fragment in &item.fragments {
+                        self.try_load_missing_font(&TextBoxFont {
+                            name: text_box.font.name.to_owned(),
+                            size: fragment.font_size,
+                        })?;
+                    }
+                }
+            }
+            WidgetUnit::RichTextBox(rich_text_box) => {
+                for span in &rich_text_box.spans {
+                    match &span.content {
+                        RichTextBoxSpanContent::Text(_) => {
+                            self.try_load_missing_font(&span.font)?
+                        }
+                        RichTextBoxSpanContent::Image(image) => {
+                            self.try_load_missing_texture(&image.id)?
+                        }
+                    }
                 }
             }
-            WidgetUnit::PortalBox(_) | WidgetUnit::None => {}
+            WidgetUnit::PortalBox(_) | WidgetUnit::CustomBox(_) | WidgetUnit::None => {}
+        }
+
+        Ok(())
+    }
+
+    fn try_load_missing_texture(&mut self, id: &str) -> Result<(), Error> {
+        if !self.resources.textures.contains_key(id) {
+            self.resources.textures.insert(
+                id.to_owned(),
+                Texture::new(self.context, id)
+                    .map_err(|e| Error::ImageResourceNotFound(e.to_string()))?,
+            );
         }
+        Ok(())
+    }
 
+    fn try_load_missing_font(&mut self, font: &TextBoxFont) -> Result<(), Error> {
+        let id = format!("{}:{}", font.name, font.size as usize);
+        if !self.resources.fonts.contains_key(&id) {
+            self.resources.fonts.insert(
+                id,
+                (
+                    1.0,
+                    Font::vector(self.context, font.name.clone(), font.size as Scalar)
+                        .map_err(|e| Error::FontResourceNotFound(e.to_string()))?,
+                ),
+            );
+        }
         Ok(())
     }
 }
@@ -160,7 +201,7 @@ where
     ) -> Result<(), Error> {
         // Go through the widget tree and try to load any images and fonts that have not been loaded
         // yet.
-        self.try_load_missing_resources(tree)?;
+        self.try_load_missing_resources(tree, layout)?;
 
         self.clip_stack.clear();
         for (k, t) in &self.resources.textures {
@@ -204,7 +245,7 @@ where
         mesh.set_front_face_winding(VertexWinding::Clockwise);
         for batch in tesselation.batches {
             match batch {
-                Batch::None | Batch::FontTriangles(_, _, _) => {}
+                Batch::None | Batch::FontTriangles(_, _, _) | Batch::ExternalCustom(_, _) => {}
                 Batch::ColoredTriangles(range) => {
                     mesh.reset_texture();
                     mesh.set_draw_range(range.start, range.end - range.start);
@@ -258,14 +299,21 @@ where
                         let scale = mapping.scale();
                         let w = text.box_size.x * font_scale;
                         let h = text.box_size.y * font_scale;
+                        let max_width = match text.wrap {
+                            TextBoxWrap::None => None,
+                            TextBoxWrap::Word | TextBoxWrap::Character => Some(w),
+                        };
                         let mut renderable = match self.resources.texts.remove(&wid) {
                             Some(mut renderable) => {
                                 renderable.set_content(text.text.as_str());
                                 renderable.set_font(font);
-                                renderable.set_max_width(Some(w));
+                                renderable.set_max_width(max_width);
                                 renderable
                             }
-                            None => Text::wrapped(text.text.as_str(), font, w),
+                            None => match max_width {
+                                Some(w) => Text::wrapped(text.text.as_str(), font, w),
+                                None => Text::new(text.text.as_str(), font),
+                            },
                         };
                         let height = renderable
                             .get_bounds(self.context)
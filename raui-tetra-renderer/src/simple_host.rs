@@ -18,6 +18,8 @@ pub struct TetraSimpleHost {
     pub application: Application,
     pub resources: TetraResources,
     pub interactions: TetraInteractionsEngine,
+    /// Has no text measurement engine, text baselines are aligned by approximated font metrics
+    pub layout_engine: DefaultLayoutEngine,
     pub scaling: CoordsMappingScaling,
 }
 
@@ -94,6 +96,7 @@ impl TetraSimpleHost {
             application,
             resources,
             interactions,
+            layout_engine: Default::default(),
             scaling: Default::default(),
         })
     }
@@ -116,7 +119,7 @@ impl TetraSimpleHost {
             .process_with_context(ProcessContext::new().insert_mut(process_context))
         {
             let mapping = self.make_coords_mapping(context);
-            let _ = self.application.layout(&mapping, &mut DefaultLayoutEngine);
+            let _ = self.application.layout(&mapping, &mut self.layout_engine);
         }
         self.application.interact(&mut self.interactions).unwrap();
         self.application.consume_signals()
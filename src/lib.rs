@@ -287,7 +287,7 @@
 //! # let tree = widget!(());
 //! # let mapping = CoordsMapping::new(Rect::default());
 //! let mut application = Application::new();
-//! let mut layout_engine = DefaultLayoutEngine;
+//! let mut layout_engine = DefaultLayoutEngine::default();
 //! application.apply(tree);
 //! application.forced_process();
 //! println!(
@@ -366,7 +366,7 @@
 //!     bottom: 576.0,
 //! });
 //! application
-//!     .layout(&mapping, &mut DefaultLayoutEngine)
+//!     .layout(&mapping, &mut DefaultLayoutEngine::default())
 //!     .unwrap();
 //! // Since interactions engines require constructed layout to process interactions we have to
 //! // process interactions after we layout the UI.
@@ -20,6 +20,7 @@ fn test_threadsafe() {
     foo::<WidgetRef>();
     foo::<WidgetIdOrRef>();
     foo::<DataBinding<()>>();
+    foo::<DefaultLayoutEngine>();
 }
 
 #[test]
@@ -555,530 +556,3283 @@ fn test_layout_wrapping() {
     }
 }
 
+/// Measures text as if every glyph was half of font size wide, wrapping lines to fit width.
+struct MonospaceTextMeasurement;
+
+impl TextMeasurementEngine for MonospaceTextMeasurement {
+    fn measure_text(&self, max_width: Scalar, unit: &TextBox) -> Option<Vec2> {
+        let glyph_width = unit.font.size * 0.5;
+        let columns = ((max_width / glyph_width) as usize).max(1);
+        let mut width: usize = 0;
+        let mut lines: usize = 0;
+        for line in unit.text.lines() {
+            let count = line.chars().count();
+            width = width.max(count.min(columns));
+            lines += ((count + columns - 1) / columns).max(1);
+        }
+        Some(Vec2 {
+            x: width as Scalar * glyph_width,
+            y: lines as Scalar * unit.font.size,
+        })
+    }
+
+    fn font_metrics(&self, font: &TextBoxFont) -> Option<FontMetrics> {
+        Some(FontMetrics {
+            ascent: font.size * 0.75,
+            descent: font.size * 0.25,
+        })
+    }
+}
+
 #[test]
-fn test_components() {
-    let mut layout_engine = DefaultLayoutEngine::default();
+fn test_layout_text_content() {
+    let mut layout_engine = DefaultLayoutEngine::new(MonospaceTextMeasurement);
     let mapping = CoordsMapping::new(Rect {
         left: 0.0,
-        right: 1024.0,
+        right: 100.0,
         top: 0.0,
-        bottom: 576.0,
+        bottom: 100.0,
     });
 
-    let tree = widget! {
-        (#{"app"} vertical_box: {
-            VerticalBoxProps {
-                separation: 10.0,
-                ..Default::default()
-            }
-        } [
-            (size_box: {
-                Props::new(SizeBoxProps {
-                    height: SizeBoxSizeValue::Exact(100.0),
-                    ..Default::default()
-                }).with(FlexBoxItemLayout {
-                    basis: Some(400.0),
-                    fill: 1.0,
-                    grow: 1.0,
-                    ..Default::default()
-                })
-            })
-            (size_box: {
-                Props::new(SizeBoxProps {
-                    height: SizeBoxSizeValue::Exact(200.0),
-                    ..Default::default()
-                }).with(FlexBoxItemLayout {
-                    basis: Some(400.0),
-                    fill: 1.0,
-                    grow: 1.0,
-                    ..Default::default()
-                })
-            })
-            (size_box: {
-                Props::new(SizeBoxProps {
-                    height: SizeBoxSizeValue::Exact(50.0),
-                    ..Default::default()
-                }).with(FlexBoxItemLayout {
-                    basis: Some(400.0),
-                    fill: 1.0,
-                    grow: 2.0,
-                    ..Default::default()
-                })
-            })
-        ])
+    let text = |id: &str, text: &str| FlexBoxItemNode {
+        slot: TextBoxNode {
+            id: WidgetId::from_str(id).unwrap(),
+            text: text.to_owned(),
+            width: TextBoxSizeValue::Content,
+            height: TextBoxSizeValue::Content,
+            font: TextBoxFont {
+                name: "font".to_owned(),
+                size: 10.0,
+            },
+            ..Default::default()
+        }
+        .into(),
+        layout: FlexBoxItemLayout {
+            grow: 0.0,
+            shrink: 0.0,
+            ..Default::default()
+        },
     };
 
+    let tree = widget! {{{
+        FlexBoxNode {
+            id: WidgetId::from_str("type:/list").unwrap(),
+            direction: FlexBoxDirection::HorizontalLeftToRight,
+            separation: 10.0,
+            items: vec![
+                text("type:/list/label", "hello"),
+                text("type:/list/wrapped", "lorem ipsum dolor sit amet"),
+            ],
+            ..Default::default()
+        }
+    }}};
+
     let mut application = Application::new();
     application.apply(tree);
     application.forced_process();
-    println!(
-        "* TREE INSPECTION:\n{:#?}",
-        application.rendered_tree().inspect()
-    );
-    if application.layout(&mapping, &mut layout_engine).is_ok() {
-        println!("* LAYOUT:\n{:#?}", application.layout_data());
-    }
-}
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let layout = application.layout_data();
 
-#[test]
-fn test_refs() {
-    use serde::{Deserialize, Serialize};
+    let rect = |id: &str| layout.items[&WidgetId::from_str(id).unwrap()].ui_space;
 
-    #[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
-    struct AppState {
-        test_ref: WidgetRef,
-    }
+    let label = rect("type:/list/label");
+    assert_eq!(label.left, 0.0);
+    assert_eq!(label.width(), 25.0);
+    assert_eq!(label.height(), 10.0);
 
-    fn use_test(context: &mut WidgetContext) {
-        context.life_cycle.change(|context| {
-            for msg in context.messenger.messages {
-                if msg.as_any().downcast_ref::<()>().is_some() {
-                    println!("Test got message");
-                    let _ = context.signals.write(());
-                }
-            }
-        });
-    }
+    // 26 glyphs wrapped to 20 columns.
+    let wrapped = rect("type:/list/wrapped");
+    assert_eq!(wrapped.left, 35.0);
+    assert_eq!(wrapped.width(), 100.0);
+    assert_eq!(wrapped.height(), 20.0);
+}
 
-    #[pre_hooks(use_test)]
-    fn test(mut context: WidgetContext) -> WidgetNode {
-        println!("Render test: {:?}", context.key);
-        widget! {()}
-    }
+#[test]
+fn test_layout_baseline_alignment() {
+    let mut layout_engine = DefaultLayoutEngine::new(MonospaceTextMeasurement);
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
 
-    fn use_app(context: &mut WidgetContext) {
-        context.life_cycle.mount(|context| {
-            println!("Register app");
-            let _ = context.state.write(AppState::default());
-            let _ = context.signals.write(true);
-        });
+    let layout = FlexBoxItemLayout {
+        align_baseline: true,
+        ..FlexBoxItemLayout::cleared()
+    };
+    let text = |id: &str, text: &str, size: Scalar| FlexBoxItemNode {
+        slot: TextBoxNode {
+            id: WidgetId::from_str(id).unwrap(),
+            text: text.to_owned(),
+            width: TextBoxSizeValue::Content,
+            height: TextBoxSizeValue::Content,
+            font: TextBoxFont {
+                name: "font".to_owned(),
+                size,
+            },
+            ..Default::default()
+        }
+        .into(),
+        layout: layout.clone(),
+    };
 
-        context.life_cycle.change(|context| {
-            for msg in context.messenger.messages {
-                if msg.as_any().downcast_ref::<()>().is_some() {
-                    println!("App got message");
-                    let state = context.state.read_cloned_or_default::<AppState>();
-                    if let Some(id) = state.test_ref.read() {
-                        println!("App send message to: {:?}", id);
-                        context.messenger.write(id, ());
+    let tree = widget! {{{
+        FlexBoxNode {
+            id: WidgetId::from_str("type:/price").unwrap(),
+            direction: FlexBoxDirection::HorizontalLeftToRight,
+            items: vec![
+                text("type:/price/currency", "$", 10.0),
+                text("type:/price/amount", "99", 30.0),
+                FlexBoxItemNode {
+                    slot: ImageBoxNode {
+                        id: WidgetId::from_str("type:/price/icon").unwrap(),
+                        width: ImageBoxSizeValue::Exact(20.0),
+                        height: ImageBoxSizeValue::Exact(20.0),
+                        baseline_offset: 4.0,
+                        ..Default::default()
                     }
-                }
-            }
-        });
-    }
-
-    #[pre_hooks(use_app)]
-    fn app(mut context: WidgetContext) -> WidgetNode {
-        let WidgetContext { key, state, .. } = context;
-        println!("Render app: {:?}", key);
-        let state = state.read_cloned_or_default::<AppState>();
-
-        widget! {
-            (#{key} | {state.test_ref} test)
+                    .into(),
+                    layout: layout.clone(),
+                },
+                text("type:/price/cents", "50", 10.0),
+            ],
+            ..Default::default()
         }
-    }
+    }}};
 
-    let mut appid = WidgetId::default();
     let mut application = Application::new();
-    application.apply(widget! { (#{"app"} app) });
-    println!("* Process");
-    application.forced_process();
-    for (id, msg) in application.signals() {
-        if let Some(msg) = msg.as_any().downcast_ref() {
-            if *msg {
-                println!("Registered app: {:?}", id);
-                appid = id.to_owned();
-            }
-        }
-    }
-    println!("* Process");
-    application.forced_process();
-    println!("* Send message to app");
-    application.send_message(&appid, ());
-    println!("* Process");
-    application.forced_process();
-    println!("* Process");
+    application.apply(tree);
     application.forced_process();
-    assert!(application
-        .signals()
-        .iter()
-        .any(|(_, msg)| msg.as_any().downcast_ref::<()>().is_some()));
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let layout = application.layout_data();
+
+    let rect = |id: &str| layout.items[&WidgetId::from_str(id).unwrap()].ui_space;
+
+    assert_eq!(rect("type:/price/amount").top, 0.0);
+    assert_eq!(rect("type:/price/currency").top, 15.0);
+    assert_eq!(rect("type:/price/icon").top, 6.5);
+    assert_eq!(rect("type:/price/cents").top, 15.0);
+    assert_eq!(rect("type:/price/cents").left, 55.0);
+    assert_eq!(rect("type:/price").height(), 30.0);
 }
 
 #[test]
-fn test_process_context() {
-    struct ReadableData(u8);
-    struct WriteableData(u8);
+fn test_layout_image_content() {
+    let mut image_sizes = std::collections::HashMap::new();
+    image_sizes.insert("icons".to_owned(), Vec2 { x: 64.0, y: 32.0 });
+    let mut layout_engine = DefaultLayoutEngine::with_measurements((), &image_sizes);
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 1024.0,
+        top: 0.0,
+        bottom: 576.0,
+    });
 
-    let mut application = Application::new();
+    let icon = |id: &str, width, height, source_rect, keep_aspect_ratio: bool| FlexBoxItemNode {
+        slot: ImageBoxNode {
+            id: WidgetId::from_str(id).unwrap(),
+            width,
+            height,
+            content_keep_aspect_ratio: if keep_aspect_ratio {
+                Some(Default::default())
+            } else {
+                None
+            },
+            material: ImageBoxMaterial::Image(ImageBoxImage {
+                id: "icons".to_owned(),
+                source_rect,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .into(),
+        layout: FlexBoxItemLayout {
+            grow: 0.0,
+            shrink: 0.0,
+            ..Default::default()
+        },
+    };
+    let half = Some(Rect {
+        left: 0.0,
+        right: 0.5,
+        top: 0.0,
+        bottom: 1.0,
+    });
 
-    fn my_component(ctx: WidgetContext) -> WidgetNode {
-        {
-            // Get an immutable reference to ReadableData
-            let readable = ctx.process_context.get::<ReadableData>().unwrap();
-            assert_eq!(readable.0, 5);
+    let tree = widget! {{{
+        FlexBoxNode {
+            id: WidgetId::from_str("type:/list").unwrap(),
+            direction: FlexBoxDirection::HorizontalLeftToRight,
+            items: vec![
+                icon(
+                    "type:/list/native",
+                    ImageBoxSizeValue::Content,
+                    ImageBoxSizeValue::Content,
+                    None,
+                    false,
+                ),
+                icon(
+                    "type:/list/scaled",
+                    ImageBoxSizeValue::ContentScaled(2.0),
+                    ImageBoxSizeValue::ContentScaled(2.0),
+                    half,
+                    false,
+                ),
+                icon(
+                    "type:/list/aspect",
+                    ImageBoxSizeValue::Content,
+                    ImageBoxSizeValue::Exact(16.0),
+                    None,
+                    true,
+                ),
+            ],
+            ..Default::default()
         }
+    }}};
 
-        {
-            // Get a mutable reference to WritableData;
-            let writable = ctx.process_context.get_mut::<WriteableData>().unwrap();
-            assert_eq!(writable.0, 6);
+    let mut application = Application::new();
+    application.apply(tree);
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let layout = application.layout_data();
+    let rect = |id: &str| layout.items[&WidgetId::from_str(id).unwrap()].ui_space;
+
+    let native = rect("type:/list/native");
+    assert_eq!((native.width(), native.height()), (64.0, 32.0));
+    let scaled = rect("type:/list/scaled");
+    assert_eq!(scaled.left, 64.0);
+    assert_eq!((scaled.width(), scaled.height()), (64.0, 64.0));
+    let aspect = rect("type:/list/aspect");
+    assert_eq!(aspect.left, 128.0);
+    assert_eq!((aspect.width(), aspect.height()), (32.0, 16.0));
+}
 
-            // Write to the data
-            writable.0 = 7;
+#[test]
+fn test_layout_cache() {
+    #[derive(Default)]
+    struct CountingTextMeasurement {
+        calls: std::cell::Cell<usize>,
+    }
+
+    impl TextMeasurementEngine for CountingTextMeasurement {
+        fn measure_text(&self, _: Scalar, unit: &TextBox) -> Option<Vec2> {
+            self.calls.set(self.calls.get() + 1);
+            Some(Vec2 {
+                x: unit.text.len() as Scalar * 10.0,
+                y: 10.0,
+            })
         }
+    }
 
-        widget!(())
+    fn make_tree(first: &str, second: &str) -> WidgetNode {
+        let text = |id: &str, text: &str| FlexBoxItemNode {
+            slot: TextBoxNode {
+                id: WidgetId::from_str(id).unwrap(),
+                text: text.to_owned(),
+                width: TextBoxSizeValue::Content,
+                height: TextBoxSizeValue::Content,
+                ..Default::default()
+            }
+            .into(),
+            layout: FlexBoxItemLayout {
+                grow: 0.0,
+                shrink: 0.0,
+                ..Default::default()
+            },
+        };
+        let row = |id: &str, text: FlexBoxItemNode| FlexBoxItemNode {
+            slot: FlexBoxNode {
+                id: WidgetId::from_str(id).unwrap(),
+                direction: FlexBoxDirection::HorizontalLeftToRight,
+                items: vec![text],
+                ..Default::default()
+            }
+            .into(),
+            layout: FlexBoxItemLayout {
+                grow: 0.0,
+                shrink: 0.0,
+                ..Default::default()
+            },
+        };
+
+        widget! {{{
+            FlexBoxNode {
+                id: WidgetId::from_str("type:/list").unwrap(),
+                direction: FlexBoxDirection::VerticalTopToBottom,
+                items: vec![
+                    row("type:/list/a", text("type:/list/a/text", first)),
+                    row("type:/list/b", text("type:/list/b/text", second)),
+                ],
+                ..Default::default()
+            }
+        }}}
     }
 
-    let tree = widget! {
-        (my_component)
+    let mut layout_engine = DefaultLayoutEngine::new(CountingTextMeasurement::default());
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 1024.0,
+        top: 0.0,
+        bottom: 576.0,
+    });
+    let mut application = Application::new();
+    let mut update = |application: &mut Application, tree| {
+        application.apply(tree);
+        application.forced_process();
+        application.layout(&mapping, &mut layout_engine).unwrap();
+        let layout = application.layout_data().clone();
+        let calls = layout_engine.text_measurement_engine().calls.replace(0);
+        (layout, calls)
     };
+    let rect = |layout: &Layout, id: &str| layout.items[&WidgetId::from_str(id).unwrap()].ui_space;
 
-    application.apply(tree);
+    let (first, calls) = update(&mut application, make_tree("hello", "world"));
+    assert!(calls > 0);
 
-    let readable = ReadableData(5);
-    let mut writable = WriteableData(6);
+    let (second, calls) = update(&mut application, make_tree("hello", "world"));
+    assert_eq!(calls, 0);
+    assert_eq!(format!("{:?}", first), format!("{:?}", second));
 
-    application.process_with_context(
-        ProcessContext::new()
-            .insert(&readable)
-            .insert_mut(&mut writable),
+    let (third, calls) = update(&mut application, make_tree("hello", "everyone"));
+    assert!(calls > 0);
+    assert_eq!(
+        rect(&first, "type:/list/a/text"),
+        rect(&third, "type:/list/a/text")
     );
-
-    // Make sure data was actually mutated by UI tree
-    assert_eq!(writable.0, 7);
+    let text = rect(&third, "type:/list/b/text");
+    assert_eq!((text.top, text.width()), (10.0, 80.0));
 }
 
 #[test]
-fn test_interactivity() {
-    // [md-bakery: begin @ interactivity]
-    let mut application = Application::new();
-    // default interactions engine covers typical pointer + keyboard + gamepad navigation/interactions.
-    let mut interactions = DefaultInteractionsEngine::new();
-    // we interact with UI by sending interaction messages to the engine.
-    interactions.interact(Interaction::PointerMove(Vec2 { x: 200.0, y: 100.0 }));
-    interactions.interact(Interaction::PointerDown(
-        PointerButton::Trigger,
-        Vec2 { x: 200.0, y: 100.0 },
-    ));
-    // navigation/interactions works only if we have navigable items (such as `button`) registered
-    // in some navigable container (usually containers with `nav_` prefix).
-    let tree = widget! {
-        (#{"app"} nav_content_box [
-            // by default navigable items are inactive which means we have to tell RAUI we activate
-            // them to interact with them.
-            (#{"button"} button: {NavItemActive} {
-                content = (#{"icon"} image_box)
+fn test_layout_grid_tracks() {
+    let props = serde_yaml::from_str::<GridBoxProps>(
+        r#"
+        column_tracks:
+          - Exact: 50
+          - Content
+          - Fraction: 1
+          - MinMax: [0, 60]
+        row_tracks:
+          - Exact: 40
+        column_gap: 10
+        row_gap: 5
+        "#,
+    )
+    .unwrap();
+    let cell = |key: &str, width, height, column_span| {
+        make_widget!(size_box)
+            .key(key)
+            .with_props(SizeBoxProps {
+                width,
+                height,
+                ..Default::default()
+            })
+            .with_props(GridBoxItemLayout {
+                column_span,
+                ..Default::default()
             })
-        ])
     };
-    application.apply(tree);
-    application.process();
+
+    let mut layout_engine = DefaultLayoutEngine::default();
     let mapping = CoordsMapping::new(Rect {
         left: 0.0,
-        right: 1024.0,
+        right: 400.0,
         top: 0.0,
-        bottom: 576.0,
+        bottom: 200.0,
     });
+    let mut application = Application::new();
+    application.apply(
+        make_widget!(grid_box)
+            .with_props(props)
+            .listed_slot(cell(
+                "a",
+                SizeBoxSizeValue::Exact(30.0),
+                SizeBoxSizeValue::Fill,
+                0,
+            ))
+            .listed_slot(cell(
+                "b",
+                SizeBoxSizeValue::Exact(80.0),
+                SizeBoxSizeValue::Fill,
+                0,
+            ))
+            .listed_slot(cell("c", SizeBoxSizeValue::Fill, SizeBoxSizeValue::Fill, 0))
+            .listed_slot(cell("d", SizeBoxSizeValue::Fill, SizeBoxSizeValue::Fill, 0))
+            .listed_slot(cell(
+                "e",
+                SizeBoxSizeValue::Fill,
+                SizeBoxSizeValue::Exact(30.0),
+                2,
+            ))
+            .into(),
+    );
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let layout = application.layout_data();
+    let rect = |key: &str| {
+        layout
+            .items
+            .iter()
+            .find(|(id, _)| id.key() == key)
+            .unwrap()
+            .1
+            .ui_space
+    };
+
+    assert_eq!(rect("a").left, 0.0);
+    assert_eq!((rect("b").left, rect("b").width()), (60.0, 80.0));
+    assert_eq!((rect("c").left, rect("c").width()), (150.0, 180.0));
+    assert_eq!((rect("d").left, rect("d").width()), (340.0, 60.0));
+    assert_eq!(rect("d").height(), 40.0);
+    let e = rect("e");
+    assert_eq!((e.left, e.top), (0.0, 45.0));
+    assert_eq!((e.width(), e.height()), (140.0, 30.0));
+}
+
+fn layout_flex_list(
+    unit: FlexBoxNode,
+    width: Scalar,
+    item_layout: impl Fn(usize) -> FlexBoxItemLayout,
+    layout_engine: &mut DefaultLayoutEngine,
+) -> Layout {
+    let items = (0..3)
+        .map(|index| FlexBoxItemNode {
+            slot: SizeBoxNode {
+                id: WidgetId::from_str(&format!("type:/list/{}", index)).unwrap(),
+                width: SizeBoxSizeValue::Exact(40.0),
+                height: SizeBoxSizeValue::Exact(10.0),
+                ..Default::default()
+            }
+            .into(),
+            layout: item_layout(index),
+        })
+        .collect();
+    let mut application = Application::new();
+    application.apply(
+        FlexBoxNode {
+            id: WidgetId::from_str("type:/list").unwrap(),
+            items,
+            ..unit
+        }
+        .into(),
+    );
+    application.forced_process();
     application
-        .layout(&mapping, &mut DefaultLayoutEngine)
+        .layout(
+            &CoordsMapping::new(Rect {
+                left: 0.0,
+                right: width,
+                top: 0.0,
+                bottom: 100.0,
+            }),
+            layout_engine,
+        )
         .unwrap();
-    // Since interactions engines require constructed layout to process interactions we have to
-    // process interactions after we layout the UI.
-    application.interact(&mut interactions).unwrap();
-    // [md-bakery: end]
+    application.layout_data().to_owned()
 }
 
 #[test]
-fn test_scroll_box() {
-    fn run<F>(
-        application: &mut Application,
-        mapping: &CoordsMapping,
-        layout_engine: &mut DefaultLayoutEngine,
-        interactions: &mut DefaultInteractionsEngine,
-        actions: &[Option<Interaction>],
-        mut f: F,
-    ) where
-        F: FnMut(WidgetId, Message),
-    {
-        for action in actions.iter() {
-            println!("* Process");
-            application.forced_process();
-            application
-                .layout(mapping, layout_engine)
-                .expect("Failed layouting");
-            if let Some(action) = action {
-                println!("* Interact: {:?}", action);
-                interactions.interact(action.to_owned());
-            }
-            application
-                .interact(interactions)
-                .expect("Failed interation");
-            println!("* Read signals");
-            for (id, msg) in application.consume_signals() {
-                println!("* Signal: {:?} -> {:?}", id, msg);
-                f(id, msg);
-            }
-        }
+fn test_layout_flex_content_alignment() {
+    fn make_layout(unit: FlexBoxNode, width: Scalar) -> Layout {
+        layout_flex_list(
+            unit,
+            width,
+            |_| FlexBoxItemLayout::cleared(),
+            &mut DefaultLayoutEngine::default(),
+        )
     }
 
-    let mut layout_engine = DefaultLayoutEngine::default();
-    let mapping = CoordsMapping::new(Rect {
-        left: 0.0,
-        right: 100.0,
-        top: 0.0,
-        bottom: 100.0,
-    });
-
-    fn use_app(context: &mut WidgetContext) {
-        context.life_cycle.change(|context| {
-            for msg in context.messenger.messages {
-                println!("* App message: {:#?}", msg);
-            }
-        });
+    fn position(layout: &Layout, index: usize) -> (Scalar, Scalar) {
+        let id = WidgetId::from_str(&format!("type:/list/{}", index)).unwrap();
+        let rect = layout.items[&id].ui_space;
+        (rect.left, rect.top)
     }
 
-    #[pre_hooks(use_nav_container, use_app)]
-    fn app(mut context: WidgetContext) -> WidgetNode {
-        let WidgetContext { id, key, .. } = context;
-
-        let scroll_props = Props::new(NavContainerActive)
-            .with(NavItemActive)
-            .with(ScrollViewNotifyProps(id.to_owned().into()))
-            .with(ScrollViewRange::default());
-        let size_props = SizeBoxProps {
-            width: SizeBoxSizeValue::Exact(200.0),
-            height: SizeBoxSizeValue::Exact(200.0),
+    let layout = make_layout(
+        FlexBoxNode {
+            justify_content: FlexBoxJustifyContent::SpaceBetween,
             ..Default::default()
-        };
-
-        widget! {
-            (#{key} nav_scroll_box: {scroll_props} {
-                content = (#{"button"} button: {NavItemActive} {
-                    content = (#{"size"} size_box: {size_props})
-                })
-                scrollbars = (#{"scrollbars"} nav_scroll_box_side_scrollbars)
-            })
-        }
-    }
-
-    let mut button = WidgetId::default();
-    let mut application = Application::new();
-    let mut interactions = DefaultInteractionsEngine::default();
-    interactions.deselect_when_no_button_found = true;
-    application.apply(widget! { (#{"app"} app: {NavContainerActive}) });
+        },
+        200.0,
+    );
+    assert_eq!(position(&layout, 0), (0.0, 0.0));
+    assert_eq!(position(&layout, 1), (80.0, 0.0));
+    assert_eq!(position(&layout, 2), (160.0, 0.0));
 
-    run(
-        &mut application,
-        &mapping,
-        &mut layout_engine,
-        &mut interactions,
-        &[None],
-        |id, msg| {
-            if let Some(NavSignal::Register(NavType::Button(_))) = msg.as_any().downcast_ref() {
-                println!("* Button registered: {:?}", id);
-                button = id.to_owned();
-            }
+    let layout = make_layout(
+        FlexBoxNode {
+            separation: 10.0,
+            justify_content: FlexBoxJustifyContent::Center,
+            ..Default::default()
         },
+        200.0,
     );
+    assert_eq!(position(&layout, 0), (30.0, 0.0));
+    assert_eq!(position(&layout, 2), (130.0, 0.0));
+
+    // 100 units wide box fits two items per line.
+    let wrapping = FlexBoxNode {
+        wrap: true,
+        separation: 20.0,
+        cross_separation: Some(5.0),
+        justify_content: FlexBoxJustifyContent::SpaceEvenly,
+        align_content: FlexBoxAlignContent::End,
+        ..Default::default()
+    };
+    let layout = make_layout(wrapping.clone(), 100.0);
+    assert_eq!(position(&layout, 0), (0.0, 75.0));
+    assert_eq!(position(&layout, 1), (60.0, 75.0));
+    assert_eq!(position(&layout, 2), (30.0, 90.0));
 
-    run(
-        &mut application,
-        &mapping,
-        &mut layout_engine,
-        &mut interactions,
-        &[
-            None,
-            None,
-            None,
-            Some(Interaction::Navigate(NavSignal::Select(
-                button.to_owned().into(),
-            ))),
-            Some(Interaction::Navigate(NavSignal::Jump(NavJump::Scroll(
-                NavScroll::Factor(Vec2 { x: 2.0, y: 2.0 }, false),
-            )))),
-            Some(Interaction::Navigate(NavSignal::Jump(NavJump::Scroll(
-                NavScroll::Units(Vec2 { x: -50.0, y: -50.0 }, true),
-            )))),
-            Some(Interaction::Navigate(NavSignal::Jump(NavJump::Scroll(
-                NavScroll::Widget(button.into(), Vec2 { x: 0.5, y: 0.5 }),
-            )))),
-            Some(Interaction::PointerDown(
-                PointerButton::Trigger,
-                Vec2 { x: 95.0, y: 0.0 },
-            )),
-            Some(Interaction::PointerMove(Vec2 { x: 95.0, y: 45.0 })),
-            Some(Interaction::PointerMove(Vec2 { x: 95.0, y: 90.0 })),
-            Some(Interaction::PointerUp(
-                PointerButton::Trigger,
-                Vec2 { x: 95.0, y: 90.0 },
-            )),
-            None,
-            None,
-            None,
-        ],
-        |_, _| {},
+    let layout = make_layout(
+        FlexBoxNode {
+            wrap_reverse: true,
+            ..wrapping
+        },
+        100.0,
     );
+    assert_eq!(position(&layout, 0), (0.0, 15.0));
+    assert_eq!(position(&layout, 2), (30.0, 0.0));
 }
 
 #[test]
-fn test_immediate_mode() {
-    fn use_app(context: &mut WidgetContext) {
-        context.use_hook(use_nav_container);
+fn test_layout_size_constraints() {
+    fn make_layout(node: WidgetNode) -> Layout {
+        let mut application = Application::new();
+        application.apply(node);
+        application.forced_process();
+        application
+            .layout(
+                &CoordsMapping::new(Rect {
+                    left: 0.0,
+                    right: 300.0,
+                    top: 0.0,
+                    bottom: 100.0,
+                }),
+                &mut DefaultLayoutEngine::default(),
+            )
+            .unwrap();
+        application.layout_data().to_owned()
     }
 
-    fn app(mut context: WidgetContext) -> WidgetNode {
-        context.use_hook(use_app);
-
-        let title = context.named_slots.remove("title").unwrap_or_default();
-        let content = context.named_slots.remove("content").unwrap_or_default();
-
-        make_widget!(content_box)
-            .key(&context.key)
-            .listed_slot(title)
-            .listed_slot(content)
-            .into()
+    fn item(id: &str) -> SizeBoxNode {
+        SizeBoxNode {
+            id: WidgetId::from_str(id).unwrap(),
+            width: SizeBoxSizeValue::Fill,
+            height: SizeBoxSizeValue::Fill,
+            ..Default::default()
+        }
     }
 
-    fn make_app(key: &str) -> WidgetComponent {
-        make_widget!(app).key(key)
+    fn space(layout: &Layout, id: &str) -> Rect {
+        layout.items[&WidgetId::from_str(id).unwrap()].ui_space
     }
 
-    fn make_text_box(key: &str, text: &str) -> WidgetComponent {
-        make_widget!(text_box).key(key).with_props(TextBoxProps {
-            text: text.to_owned(),
+    // Items frozen at their limits leave the rest of the space to the others.
+    let constraints = [
+        SizeConstraints {
+            max_width: Some(30.0),
+            ..Default::default()
+        },
+        SizeConstraints {
+            min_width: 150.0,
             ..Default::default()
+        },
+        SizeConstraints {
+            max_height: Some(20.0),
+            ..Default::default()
+        },
+    ];
+    let items = constraints
+        .iter()
+        .enumerate()
+        .map(|(index, constraints)| FlexBoxItemNode {
+            slot: item(&format!("type:/list/{}", index)).into(),
+            layout: FlexBoxItemLayout {
+                constraints: *constraints,
+                ..Default::default()
+            },
         })
-    }
+        .collect();
+    let layout = make_layout(
+        FlexBoxNode {
+            id: WidgetId::from_str("type:/list").unwrap(),
+            items,
+            ..Default::default()
+        }
+        .into(),
+    );
+    let rect = space(&layout, "type:/list/0");
+    assert_eq!((rect.left, rect.width(), rect.height()), (0.0, 30.0, 100.0));
+    let rect = space(&layout, "type:/list/1");
+    assert_eq!((rect.left, rect.width()), (30.0, 210.0));
+    let rect = space(&layout, "type:/list/2");
+    assert_eq!(
+        (rect.left, rect.width(), rect.height()),
+        (240.0, 60.0, 20.0)
+    );
 
-    fn make_button(key: &str, text: &str) -> WidgetComponent {
-        make_widget!(button)
-            .key(key)
-            .with_props(NavItemActive)
-            .named_slot("content", make_text_box("text", text))
-    }
+    let layout = make_layout(
+        ContentBoxNode {
+            id: WidgetId::from_str("type:/content").unwrap(),
+            items: vec![ContentBoxItemNode {
+                slot: item("type:/content/item").into(),
+                layout: ContentBoxItemLayout {
+                    align: Vec2 { x: 0.5, y: 1.0 },
+                    constraints: SizeConstraints {
+                        max_width: Some(100.0),
+                        max_height: Some(40.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        }
+        .into(),
+    );
+    let rect = space(&layout, "type:/content/item");
+    assert_eq!(
+        (rect.left, rect.top, rect.width(), rect.height()),
+        (100.0, 60.0, 100.0, 40.0)
+    );
 
-    let mut application = Application::new();
-    application.apply(
-        make_app("app")
-            .with_props(NavContainerActive)
-            .named_slot("title", make_text_box("text", "Hello, World!"))
-            .named_slot("content", make_button("button", "Click me!"))
-            .into(),
+    let layout = make_layout(
+        SizeBoxNode {
+            constraints: SizeConstraints {
+                min_width: 30.0,
+                max_height: Some(50.0),
+                ..Default::default()
+            },
+            width: SizeBoxSizeValue::Exact(10.0),
+            ..item("type:/size")
+        }
+        .into(),
     );
+    let rect = space(&layout, "type:/size");
+    assert_eq!((rect.width(), rect.height()), (30.0, 50.0));
 }
 
 #[test]
-#[cfg(feature = "tesselate")]
-fn test_tesselation() {
-    let mut application = Application::new();
-    let mut layout_engine = DefaultLayoutEngine::default();
-    let atlas_mapping = Default::default();
+fn test_layout_constraints() {
+    use LayoutConstraintVariable::*;
+
+    let item = |key: &str| ContentBoxItemNode {
+        slot: ContentBoxNode {
+            id: WidgetId::from_str(&format!("type:/panel/{}", key)).unwrap(),
+            ..Default::default()
+        }
+        .into(),
+        layout: Default::default(),
+    };
+    let mut application = Application::new();
+    application.apply(
+        ContentBoxNode {
+            id: WidgetId::from_str("type:/panel").unwrap(),
+            items: vec![item("a"), item("b"), item("c")],
+            constraints: vec![
+                // a.left == left + 10
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("a", Left)],
+                    LayoutConstraintRelation::Equal,
+                    vec![LayoutConstraintTerm::container(Left)],
+                )
+                .with_constant(10.0),
+                // a.width == 50
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("a", Width)],
+                    LayoutConstraintRelation::Equal,
+                    vec![],
+                )
+                .with_constant(50.0),
+                // a.right == b.left - 8
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("a", Right)],
+                    LayoutConstraintRelation::Equal,
+                    vec![LayoutConstraintTerm::new("b", Left)],
+                )
+                .with_constant(-8.0),
+                // b.right == right - 10
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("b", Right)],
+                    LayoutConstraintRelation::Equal,
+                    vec![LayoutConstraintTerm::container(Right)],
+                )
+                .with_constant(-10.0),
+                // b.width >= 150 strong
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("b", Width)],
+                    LayoutConstraintRelation::GreaterOrEqual,
+                    vec![],
+                )
+                .with_constant(150.0)
+                .with_strength(LayoutConstraintStrength::Strong),
+                // b.center_y == center_y, b.height == half of height
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("b", CenterY)],
+                    LayoutConstraintRelation::Equal,
+                    vec![LayoutConstraintTerm::container(CenterY)],
+                ),
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("b", Height)],
+                    LayoutConstraintRelation::Equal,
+                    vec![LayoutConstraintTerm::container(Height).scaled(0.5)],
+                ),
+                // constraints referring to unknown items are ignored
+                LayoutConstraint::new(
+                    vec![LayoutConstraintTerm::new("c", Width)],
+                    LayoutConstraintRelation::Equal,
+                    vec![LayoutConstraintTerm::new("missing", Width)],
+                ),
+            ],
+            ..Default::default()
+        }
+        .into(),
+    );
+    application.forced_process();
+    application
+        .layout(
+            &CoordsMapping::new(Rect {
+                left: 0.0,
+                right: 200.0,
+                top: 0.0,
+                bottom: 100.0,
+            }),
+            &mut DefaultLayoutEngine::default(),
+        )
+        .unwrap();
+    let layout = application.layout_data();
+    let space = |id: &str| layout.items[&WidgetId::from_str(id).unwrap()].ui_space;
+
+    let rect = space("type:/panel/a");
+    assert_eq!(
+        (rect.left, rect.right, rect.top, rect.bottom),
+        (10.0, 60.0, 0.0, 100.0)
+    );
+    let rect = space("type:/panel/b");
+    assert_eq!(
+        (rect.left, rect.right, rect.top, rect.bottom),
+        (68.0, 190.0, 25.0, 75.0)
+    );
+    let rect = space("type:/panel/c");
+    assert_eq!(
+        (rect.left, rect.right, rect.top, rect.bottom),
+        (0.0, 200.0, 0.0, 100.0)
+    );
+}
+
+#[test]
+fn test_layout_conflicting_constraints() {
+    use LayoutConstraintVariable::*;
+
+    let width = |value: Scalar| {
+        LayoutConstraint::new(
+            vec![LayoutConstraintTerm::new("a", Width)],
+            LayoutConstraintRelation::Equal,
+            vec![],
+        )
+        .with_constant(value)
+    };
+    let rejected = vec![
+        width(20.0),
+        width(30.0).with_strength(LayoutConstraintStrength::Custom(1000.0, 1000.0, 1000.0)),
+        // ui.width == 5
+        LayoutConstraint::new(
+            vec![LayoutConstraintTerm::container(Width)],
+            LayoutConstraintRelation::Equal,
+            vec![],
+        )
+        .with_constant(5.0),
+    ];
+    let mut constraints = vec![width(10.0)];
+    constraints.extend(rejected.iter().cloned());
+    let mut application = Application::new();
+    application.apply(
+        ContentBoxNode {
+            id: WidgetId::from_str("type:/panel").unwrap(),
+            items: vec![ContentBoxItemNode {
+                slot: ContentBoxNode {
+                    id: WidgetId::from_str("type:/panel/a").unwrap(),
+                    ..Default::default()
+                }
+                .into(),
+                layout: Default::default(),
+            }],
+            constraints,
+            ..Default::default()
+        }
+        .into(),
+    );
+    application.forced_process();
+    let mut layout_engine = DefaultLayoutEngine::default();
+    application
+        .layout(
+            &CoordsMapping::new(Rect {
+                left: 0.0,
+                right: 200.0,
+                top: 0.0,
+                bottom: 100.0,
+            }),
+            &mut layout_engine,
+        )
+        .unwrap();
+    let rect =
+        application.layout_data().items[&WidgetId::from_str("type:/panel/a").unwrap()].ui_space;
+    assert_eq!(rect.width(), 10.0);
+    let mut expected = std::collections::HashMap::new();
+    expected.insert(WidgetId::from_str("type:/panel").unwrap(), rejected);
+    assert_eq!(layout_engine.rejected_constraints(), expected);
+}
+
+#[test]
+fn test_layout_hit_test() {
+    let mut application = Application::new();
+    application.apply(
+        ContentBoxNode {
+            id: WidgetId::from_str("type:/root").unwrap(),
+            items: vec![
+                ContentBoxItemNode {
+                    slot: SizeBoxNode {
+                        id: WidgetId::from_str("type:/root/panel").unwrap(),
+                        width: SizeBoxSizeValue::Exact(40.0),
+                        height: SizeBoxSizeValue::Exact(40.0),
+                        transform: Transform {
+                            scale: Vec2 { x: 2.0, y: 2.0 },
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }
+                    .into(),
+                    layout: Default::default(),
+                },
+                ContentBoxItemNode {
+                    slot: ContentBoxNode {
+                        id: WidgetId::from_str("type:/root/clip").unwrap(),
+                        items: vec![ContentBoxItemNode {
+                            slot: ImageBoxNode {
+                                id: WidgetId::from_str("type:/root/clip/image").unwrap(),
+                                ..Default::default()
+                            }
+                            .into(),
+                            layout: ContentBoxItemLayout {
+                                offset: Vec2 { x: 30.0, y: 0.0 },
+                                ..Default::default()
+                            },
+                        }],
+                        clipping: true,
+                        ..Default::default()
+                    }
+                    .into(),
+                    layout: ContentBoxItemLayout {
+                        anchors: Rect {
+                            left: 0.5,
+                            right: 1.0,
+                            top: 0.0,
+                            bottom: 0.5,
+                        },
+                        ..Default::default()
+                    },
+                },
+            ],
+            ..Default::default()
+        }
+        .into(),
+    );
+    application.forced_process();
+    application
+        .layout(
+            &CoordsMapping::new(Rect {
+                left: 0.0,
+                right: 100.0,
+                top: 0.0,
+                bottom: 100.0,
+            }),
+            &mut DefaultLayoutEngine::default(),
+        )
+        .unwrap();
+    let hit_test = |x, y| {
+        application
+            .layout_data()
+            .hit_test(application.rendered_tree(), Vec2 { x, y })
+    };
+    let ids = |x, y| {
+        hit_test(x, y)
+            .into_iter()
+            .map(|hit| hit.id.path().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    // Scaled panel gets hit outside of its untransformed rectangle.
+    assert_eq!(ids(60.0, 60.0), vec!["root/panel", "root"]);
+    assert_eq!(
+        hit_test(60.0, 60.0)[0].local_point,
+        Vec2 { x: 30.0, y: 30.0 }
+    );
+    assert_eq!(
+        ids(90.0, 10.0),
+        vec!["root/clip/image", "root/clip", "root"]
+    );
+    // Image overflowing its clipping parent cannot be hit outside of it.
+    assert_eq!(ids(90.0, 60.0), vec!["root"]);
+}
+
+#[test]
+fn test_layout_right_to_left() {
+    fn make_layout(layout_direction: Option<LayoutDirection>) -> Layout {
+        let mut layout_engine = DefaultLayoutEngine::default();
+        layout_engine.set_layout_direction(LayoutDirection::RightToLeft);
+        layout_flex_list(
+            FlexBoxNode {
+                layout_direction,
+                ..Default::default()
+            },
+            200.0,
+            |index| FlexBoxItemLayout {
+                margin: Rect {
+                    left: if index == 0 { 10.0 } else { 0.0 },
+                    ..Default::default()
+                },
+                ..FlexBoxItemLayout::cleared()
+            },
+            &mut layout_engine,
+        )
+    }
+
+    fn horizontal(layout: &Layout, id: &str) -> (Scalar, Scalar) {
+        let rect = layout.items[&WidgetId::from_str(id).unwrap()].ui_space;
+        (rect.left, rect.right)
+    }
+
+    let layout = make_layout(None);
+    assert_eq!(horizontal(&layout, "type:/list"), (70.0, 200.0));
+    assert_eq!(horizontal(&layout, "type:/list/0"), (150.0, 190.0));
+    assert_eq!(horizontal(&layout, "type:/list/1"), (110.0, 150.0));
+    assert_eq!(horizontal(&layout, "type:/list/2"), (70.0, 110.0));
+    assert_eq!(
+        layout.items[&WidgetId::from_str("type:/list/0").unwrap()].direction,
+        LayoutDirection::RightToLeft
+    );
+
+    // Subtree overriding direction keeps its content left-to-right.
+    let layout = make_layout(Some(LayoutDirection::LeftToRight));
+    assert_eq!(horizontal(&layout, "type:/list"), (70.0, 200.0));
+    assert_eq!(horizontal(&layout, "type:/list/0"), (80.0, 120.0));
+    assert_eq!(horizontal(&layout, "type:/list/2"), (160.0, 200.0));
+}
+
+#[test]
+fn test_coords_mapping_pixel_snapping() {
+    let mapping = CoordsMapping::new_scaling(
+        Rect {
+            left: 0.0,
+            right: 300.0,
+            top: 0.0,
+            bottom: 200.0,
+        },
+        CoordsMappingScaling::FitHorizontal(200.0),
+    )
+    .with_scale_factor(2.0);
+    assert_eq!(mapping.scale(), Vec2 { x: 3.0, y: 3.0 });
+    assert_eq!(mapping.virtual_area().height(), 400.0 / 3.0);
+
+    assert_eq!(
+        mapping.real_area(),
+        Rect {
+            left: 0.0,
+            right: 600.0,
+            top: 0.0,
+            bottom: 400.0,
+        }
+    );
+
+    let parent = WidgetId::from_str("type:/parent").unwrap();
+    let id = WidgetId::from_str("type:/parent/item").unwrap();
+    let child = WidgetId::from_str("type:/parent/item/child").unwrap();
+    let mut layout = Layout {
+        ui_space: mapping.virtual_area(),
+        ..Default::default()
+    };
+    let parent_space = Rect {
+        left: 10.0,
+        right: 30.0,
+        top: 5.0,
+        bottom: 20.0,
+    };
+    layout.items.insert(
+        parent.to_owned(),
+        LayoutItem {
+            local_space: parent_space,
+            ui_space: parent_space,
+            ..Default::default()
+        },
+    );
+    layout.items.insert(
+        id.to_owned(),
+        LayoutItem {
+            local_space: Rect {
+                left: 0.125,
+                right: 10.25,
+                top: 0.0,
+                bottom: 5.0625,
+            },
+            ui_space: Rect {
+                left: 10.125,
+                right: 20.25,
+                top: 5.0,
+                bottom: 10.0625,
+            },
+            parent: Some(parent.to_owned()),
+            ..Default::default()
+        },
+    );
+    layout.items.insert(
+        child.to_owned(),
+        LayoutItem {
+            local_space: Rect {
+                left: 0.25,
+                right: 5.25,
+                top: 0.5,
+                bottom: 2.5,
+            },
+            ui_space: Rect {
+                left: 10.375,
+                right: 15.375,
+                top: 5.5,
+                bottom: 7.5,
+            },
+            parent: Some(id.to_owned()),
+            ..Default::default()
+        },
+    );
+
+    let real = layout.virtual_to_real(&mapping);
+    assert_eq!(
+        real.items[&id].ui_space,
+        Rect {
+            left: 30.375,
+            right: 60.75,
+            top: 15.0,
+            bottom: 30.1875,
+        }
+    );
+
+    let snapped = layout.virtual_to_real(&mapping.clone().with_pixel_snapping(true));
+    assert_eq!(
+        snapped.items[&id].ui_space,
+        Rect {
+            left: 30.0,
+            right: 61.0,
+            top: 15.0,
+            bottom: 30.0,
+        }
+    );
+    assert_eq!(
+        snapped.items[&id].local_space,
+        Rect {
+            left: 0.0,
+            right: 31.0,
+            top: 0.0,
+            bottom: 15.0,
+        }
+    );
+    // Nested item is snapped relative to snapped ui space of its parent.
+    assert_eq!(
+        snapped.items[&child].ui_space,
+        Rect {
+            left: 31.0,
+            right: 46.0,
+            top: 17.0,
+            bottom: 23.0,
+        }
+    );
+    assert_eq!(
+        snapped.items[&child].local_space,
+        Rect {
+            left: 1.0,
+            right: 16.0,
+            top: 2.0,
+            bottom: 8.0,
+        }
+    );
+
+    let pointer = mapping.real_to_virtual_vec2(Vec2 { x: 30.375, y: 15.0 }, false);
+    assert_eq!(pointer, Vec2 { x: 10.125, y: 5.0 });
+    assert_eq!(
+        real.real_to_virtual(&mapping).items[&id].ui_space,
+        layout.items[&id].ui_space
+    );
+
+    #[cfg(feature = "tesselate")]
+    {
+        use crate::renderer::tesselate::prelude::*;
+        use std::convert::TryFrom;
+
+        let tree = WidgetUnit::try_from(WidgetNode::from(SizeBoxNode {
+            id: parent,
+            slot: Box::new(
+                SizeBoxNode {
+                    id,
+                    slot: Box::new(
+                        ImageBoxNode {
+                            id: child,
+                            ..Default::default()
+                        }
+                        .into(),
+                    ),
+                    ..Default::default()
+                }
+                .into(),
+            ),
+            ..Default::default()
+        }))
+        .unwrap();
+        let atlas_mapping = Default::default();
+        let image_sizes = Default::default();
+        let mut renderer = TesselateRenderer::new(
+            TesselationVerticesFormat::Separated,
+            (),
+            &atlas_mapping,
+            &image_sizes,
+        );
+        let tesselation = renderer
+            .render(&tree, &mapping.with_pixel_snapping(true), &layout)
+            .unwrap();
+        let positions = match tesselation.vertices {
+            TesselationVertices::Separated(vertices) => vertices.position,
+            _ => unreachable!(),
+        };
+        assert_eq!(positions[0], Vec2 { x: 31.0, y: 17.0 });
+        assert_eq!(positions[2], Vec2 { x: 46.0, y: 23.0 });
+    }
+}
+
+#[test]
+fn test_components() {
+    let mut layout_engine = DefaultLayoutEngine::default();
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 1024.0,
+        top: 0.0,
+        bottom: 576.0,
+    });
+
+    let tree = widget! {
+        (#{"app"} vertical_box: {
+            VerticalBoxProps {
+                separation: 10.0,
+                ..Default::default()
+            }
+        } [
+            (size_box: {
+                Props::new(SizeBoxProps {
+                    height: SizeBoxSizeValue::Exact(100.0),
+                    ..Default::default()
+                }).with(FlexBoxItemLayout {
+                    basis: Some(400.0),
+                    fill: 1.0,
+                    grow: 1.0,
+                    ..Default::default()
+                })
+            })
+            (size_box: {
+                Props::new(SizeBoxProps {
+                    height: SizeBoxSizeValue::Exact(200.0),
+                    ..Default::default()
+                }).with(FlexBoxItemLayout {
+                    basis: Some(400.0),
+                    fill: 1.0,
+                    grow: 1.0,
+                    ..Default::default()
+                })
+            })
+            (size_box: {
+                Props::new(SizeBoxProps {
+                    height: SizeBoxSizeValue::Exact(50.0),
+                    ..Default::default()
+                }).with(FlexBoxItemLayout {
+                    basis: Some(400.0),
+                    fill: 1.0,
+                    grow: 2.0,
+                    ..Default::default()
+                })
+            })
+        ])
+    };
+
+    let mut application = Application::new();
+    application.apply(tree);
+    application.forced_process();
+    println!(
+        "* TREE INSPECTION:\n{:#?}",
+        application.rendered_tree().inspect()
+    );
+    if application.layout(&mapping, &mut layout_engine).is_ok() {
+        println!("* LAYOUT:\n{:#?}", application.layout_data());
+    }
+}
+
+#[test]
+fn test_refs() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+    struct AppState {
+        test_ref: WidgetRef,
+    }
+
+    fn use_test(context: &mut WidgetContext) {
+        context.life_cycle.change(|context| {
+            for msg in context.messenger.messages {
+                if msg.as_any().downcast_ref::<()>().is_some() {
+                    println!("Test got message");
+                    let _ = context.signals.write(());
+                }
+            }
+        });
+    }
+
+    #[pre_hooks(use_test)]
+    fn test(mut context: WidgetContext) -> WidgetNode {
+        println!("Render test: {:?}", context.key);
+        widget! {()}
+    }
+
+    fn use_app(context: &mut WidgetContext) {
+        context.life_cycle.mount(|context| {
+            println!("Register app");
+            let _ = context.state.write(AppState::default());
+            let _ = context.signals.write(true);
+        });
+
+        context.life_cycle.change(|context| {
+            for msg in context.messenger.messages {
+                if msg.as_any().downcast_ref::<()>().is_some() {
+                    println!("App got message");
+                    let state = context.state.read_cloned_or_default::<AppState>();
+                    if let Some(id) = state.test_ref.read() {
+                        println!("App send message to: {:?}", id);
+                        context.messenger.write(id, ());
+                    }
+                }
+            }
+        });
+    }
+
+    #[pre_hooks(use_app)]
+    fn app(mut context: WidgetContext) -> WidgetNode {
+        let WidgetContext { key, state, .. } = context;
+        println!("Render app: {:?}", key);
+        let state = state.read_cloned_or_default::<AppState>();
+
+        widget! {
+            (#{key} | {state.test_ref} test)
+        }
+    }
+
+    let mut appid = WidgetId::default();
+    let mut application = Application::new();
+    application.apply(widget! { (#{"app"} app) });
+    println!("* Process");
+    application.forced_process();
+    for (id, msg) in application.signals() {
+        if let Some(msg) = msg.as_any().downcast_ref() {
+            if *msg {
+                println!("Registered app: {:?}", id);
+                appid = id.to_owned();
+            }
+        }
+    }
+    println!("* Process");
+    application.forced_process();
+    println!("* Send message to app");
+    application.send_message(&appid, ());
+    println!("* Process");
+    application.forced_process();
+    println!("* Process");
+    application.forced_process();
+    assert!(application
+        .signals()
+        .iter()
+        .any(|(_, msg)| msg.as_any().downcast_ref::<()>().is_some()));
+}
+
+#[test]
+fn test_process_context() {
+    struct ReadableData(u8);
+    struct WriteableData(u8);
+
+    let mut application = Application::new();
+
+    fn my_component(ctx: WidgetContext) -> WidgetNode {
+        {
+            // Get an immutable reference to ReadableData
+            let readable = ctx.process_context.get::<ReadableData>().unwrap();
+            assert_eq!(readable.0, 5);
+        }
+
+        {
+            // Get a mutable reference to WritableData;
+            let writable = ctx.process_context.get_mut::<WriteableData>().unwrap();
+            assert_eq!(writable.0, 6);
+
+            // Write to the data
+            writable.0 = 7;
+        }
+
+        widget!(())
+    }
+
+    let tree = widget! {
+        (my_component)
+    };
+
+    application.apply(tree);
+
+    let readable = ReadableData(5);
+    let mut writable = WriteableData(6);
+
+    application.process_with_context(
+        ProcessContext::new()
+            .insert(&readable)
+            .insert_mut(&mut writable),
+    );
+
+    // Make sure data was actually mutated by UI tree
+    assert_eq!(writable.0, 7);
+}
+
+#[test]
+fn test_interactivity() {
+    // [md-bakery: begin @ interactivity]
+    let mut application = Application::new();
+    // default interactions engine covers typical pointer + keyboard + gamepad navigation/interactions.
+    let mut interactions = DefaultInteractionsEngine::new();
+    // we interact with UI by sending interaction messages to the engine.
+    interactions.interact(Interaction::PointerMove(Vec2 { x: 200.0, y: 100.0 }));
+    interactions.interact(Interaction::PointerDown(
+        PointerButton::Trigger,
+        Vec2 { x: 200.0, y: 100.0 },
+    ));
+    // navigation/interactions works only if we have navigable items (such as `button`) registered
+    // in some navigable container (usually containers with `nav_` prefix).
+    let tree = widget! {
+        (#{"app"} nav_content_box [
+            // by default navigable items are inactive which means we have to tell RAUI we activate
+            // them to interact with them.
+            (#{"button"} button: {NavItemActive} {
+                content = (#{"icon"} image_box)
+            })
+        ])
+    };
+    application.apply(tree);
+    application.process();
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 1024.0,
+        top: 0.0,
+        bottom: 576.0,
+    });
+    application
+        .layout(&mapping, &mut DefaultLayoutEngine::default())
+        .unwrap();
+    // Since interactions engines require constructed layout to process interactions we have to
+    // process interactions after we layout the UI.
+    application.interact(&mut interactions).unwrap();
+    // [md-bakery: end]
+}
+
+#[test]
+fn test_safe_area() {
+    let mut application = Application::new();
+    application.setup(setup);
+    let mut interactions = DefaultInteractionsEngine::new();
+    let tree = widget! {
+        (#{"root"} content_box [
+            (#{"safe"} safe_area_box: {RelativeLayoutProps {
+                relative_to: WidgetId::from_str("content_box:/root").unwrap().into(),
+            }} {
+                content = (#{"hud"} content_box)
+            })
+        ])
+    };
+    application.apply(tree);
+    let mapping = CoordsMapping::new_scaling(
+        Rect {
+            left: 0.0,
+            right: 800.0,
+            top: 0.0,
+            bottom: 400.0,
+        },
+        CoordsMappingScaling::FitHorizontal(400.0),
+    )
+    .with_safe_area_insets(Rect {
+        left: 40.0,
+        right: 0.0,
+        top: 20.0,
+        bottom: 10.0,
+    });
+    assert_eq!(
+        mapping.virtual_safe_area(),
+        Rect {
+            left: 20.0,
+            right: 400.0,
+            top: 10.0,
+            bottom: 195.0,
+        }
+    );
+    let mut layout_engine = DefaultLayoutEngine::default();
+    for _ in 0..3 {
+        application.forced_process();
+        application.layout(&mapping, &mut layout_engine).unwrap();
+        application.interact(&mut interactions).unwrap();
+    }
+    let layout = application.layout_data();
+    assert_eq!(layout.safe_area, mapping.virtual_safe_area());
+    assert_eq!(
+        layout
+            .find("root/safe/hud")
+            .map(|item| item.ui_space)
+            .unwrap(),
+        layout.safe_area
+    );
+}
+
+#[test]
+fn test_masonry_box() {
+    fn card(key: &str, height: Scalar) -> WidgetComponent {
+        make_widget!(size_box).key(key).with_props(SizeBoxProps {
+            width: SizeBoxSizeValue::Fill,
+            height: SizeBoxSizeValue::Exact(height),
+            ..Default::default()
+        })
+    }
+
+    let mut application = Application::new();
+    application.setup(setup);
+    application.apply(
+        make_widget!(masonry_box)
+            .key("root")
+            .with_props(MasonryBoxProps {
+                columns: 2,
+                column_gap: 10.0,
+                row_gap: 10.0,
+                ..Default::default()
+            })
+            .listed_slot(card("a", 50.0))
+            .listed_slot(card("b", 30.0))
+            .listed_slot(card("c", 40.0))
+            .listed_slot(card("d", 20.0))
+            .into(),
+    );
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 50.0,
+    });
+    let mut layout_engine = DefaultLayoutEngine::default();
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let layout = application.layout_data();
+    let rect = |path: &str| layout.find(path).map(|item| item.ui_space).unwrap();
+    assert_eq!(
+        rect("root/a"),
+        Rect {
+            left: 0.0,
+            right: 45.0,
+            top: 0.0,
+            bottom: 50.0,
+        }
+    );
+    assert_eq!(
+        rect("root/b"),
+        Rect {
+            left: 55.0,
+            right: 100.0,
+            top: 0.0,
+            bottom: 30.0,
+        }
+    );
+    assert_eq!(
+        rect("root/c"),
+        Rect {
+            left: 55.0,
+            right: 100.0,
+            top: 40.0,
+            bottom: 80.0,
+        }
+    );
+    assert_eq!(
+        rect("root/d"),
+        Rect {
+            left: 0.0,
+            right: 45.0,
+            top: 60.0,
+            bottom: 80.0,
+        }
+    );
+    assert_eq!(rect("root").height(), 80.0);
+
+    let unit = MasonryBox {
+        min_column_width: 30.0,
+        column_gap: 10.0,
+        ..Default::default()
+    };
+    assert_eq!(unit.columns_count(100.0), 2);
+    assert_eq!(unit.columns_count(20.0), 1);
+}
+
+#[test]
+fn test_virtual_list_box() {
+    let mut application = Application::new();
+    application.setup(setup);
+    let mut interactions = DefaultInteractionsEngine::new();
+    let list_props = VirtualListBoxProps {
+        count: 10_000,
+        item_size: VirtualListBoxItemSize::Fixed(20.0),
+        ..Default::default()
+    };
+    application.apply(widget! {
+        (#{"list"} nav_virtual_list_box: {list_props} {
+            item = (#{"row"} content_box)
+        })
+    });
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+    let mut layout_engine = DefaultLayoutEngine::default();
+    let mut run = |application: &mut Application| {
+        for _ in 0..4 {
+            application.forced_process();
+            application.layout(&mapping, &mut layout_engine).unwrap();
+            application.interact(&mut interactions).unwrap();
+        }
+    };
+    let items = |application: &Application| {
+        let mut result = application
+            .layout_data()
+            .items
+            .iter()
+            .filter(|(id, _)| id.path().contains("/items/") && !id.path().ends_with("/row"))
+            .map(|(id, item)| (id.key().parse::<usize>().unwrap(), item.ui_space.top))
+            .collect::<Vec<_>>();
+        result.sort_by_key(|(index, _)| *index);
+        result
+    };
+
+    run(&mut application);
+    let visible = items(&application);
+    assert_eq!(
+        visible.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        (0..8).collect::<Vec<_>>()
+    );
+    assert_eq!(visible[1].1, 20.0);
+
+    application.send_message(
+        &WidgetId::from_str("nav_virtual_list_box:/list").unwrap(),
+        VirtualListBoxJumpToIndex(5000),
+    );
+    run(&mut application);
+    // scroll position goes through scroll factor, so it may land a fraction of unit off.
+    let visible = items(&application);
+    assert!(visible.len() <= 10);
+    assert!((5000..5006).all(|index| visible.iter().any(|(i, _)| *i == index)));
+    assert!(visible
+        .iter()
+        .any(|(index, top)| *index == 5000 && top.abs() < 0.1));
+
+    let mut measured = std::collections::HashMap::new();
+    measured.insert(1, 50.0);
+    measured.insert(2, 30.0);
+    let metrics = VirtualListBoxMetrics::new(
+        &VirtualListBoxProps {
+            count: 10,
+            item_size: VirtualListBoxItemSize::Estimated(20.0),
+            columns: 2,
+            separation: 5.0,
+            ..Default::default()
+        },
+        &measured,
+    );
+    assert_eq!(metrics.rows_count(), 5);
+    assert_eq!(metrics.row_size(0), 50.0);
+    assert_eq!(metrics.row_offset(1), 55.0);
+    assert_eq!(metrics.row_offset(2), 90.0);
+    assert_eq!(metrics.content_size(), 160.0);
+    assert_eq!(metrics.row_at(60.0), 1);
+}
+
+#[test]
+fn test_virtual_list_box_estimated_size() {
+    let mut application = Application::new();
+    application.setup(setup);
+    let mut interactions = DefaultInteractionsEngine::new();
+    let list_props = VirtualListBoxProps {
+        count: 100,
+        item_size: VirtualListBoxItemSize::Estimated(20.0),
+        ..Default::default()
+    };
+    let row_props = SizeBoxProps {
+        width: SizeBoxSizeValue::Fill,
+        height: SizeBoxSizeValue::Exact(40.0),
+        ..Default::default()
+    };
+    application.apply(widget! {
+        (#{"list"} nav_virtual_list_box: {list_props} {
+            item = (#{"row"} size_box: {row_props})
+        })
+    });
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+    let mut layout_engine = DefaultLayoutEngine::default();
+    let mut run = |application: &mut Application| {
+        for _ in 0..12 {
+            application.forced_process();
+            application.layout(&mapping, &mut layout_engine).unwrap();
+            application.interact(&mut interactions).unwrap();
+        }
+    };
+    let item_top = |application: &Application, index: usize| {
+        application
+            .layout_data()
+            .items
+            .iter()
+            .find(|(id, _)| id.path().ends_with(&format!("/items/{}", index)))
+            .map(|(_, item)| item.ui_space.top)
+    };
+    let list = WidgetId::from_str("nav_virtual_list_box:/list").unwrap();
+    let measured = |application: &Application| {
+        application
+            .state_read(&list)
+            .unwrap()
+            .read_cloned_or_default::<VirtualListBoxState>()
+            .measured
+    };
+
+    run(&mut application);
+    assert_eq!(item_top(&application, 1), Some(40.0));
+    assert!(!measured(&application).contains_key(&48));
+
+    // rows above the one jumped to get measured after the jump, bigger than estimated, and list
+    // scrolls along so the row stays at the top of the viewport.
+    application.send_message(&list, VirtualListBoxJumpToIndex(50));
+    run(&mut application);
+    let measured = measured(&application);
+    assert!(measured.contains_key(&48) && measured.contains_key(&49));
+    assert!(measured.values().all(|size| *size == 40.0));
+    assert!(item_top(&application, 50).unwrap().abs() < 0.1);
+    assert!((item_top(&application, 51).unwrap() - 40.0).abs() < 0.1);
+}
+
+#[test]
+fn test_scroll_box() {
+    fn run<F>(
+        application: &mut Application,
+        mapping: &CoordsMapping,
+        layout_engine: &mut DefaultLayoutEngine,
+        interactions: &mut DefaultInteractionsEngine,
+        actions: &[Option<Interaction>],
+        mut f: F,
+    ) where
+        F: FnMut(WidgetId, Message),
+    {
+        for action in actions.iter() {
+            println!("* Process");
+            application.forced_process();
+            application
+                .layout(mapping, layout_engine)
+                .expect("Failed layouting");
+            if let Some(action) = action {
+                println!("* Interact: {:?}", action);
+                interactions.interact(action.to_owned());
+            }
+            application
+                .interact(interactions)
+                .expect("Failed interation");
+            println!("* Read signals");
+            for (id, msg) in application.consume_signals() {
+                println!("* Signal: {:?} -> {:?}", id, msg);
+                f(id, msg);
+            }
+        }
+    }
+
+    let mut layout_engine = DefaultLayoutEngine::default();
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+
+    fn use_app(context: &mut WidgetContext) {
+        context.life_cycle.change(|context| {
+            for msg in context.messenger.messages {
+                println!("* App message: {:#?}", msg);
+            }
+        });
+    }
+
+    #[pre_hooks(use_nav_container, use_app)]
+    fn app(mut context: WidgetContext) -> WidgetNode {
+        let WidgetContext { id, key, .. } = context;
+
+        let scroll_props = Props::new(NavContainerActive)
+            .with(NavItemActive)
+            .with(ScrollViewNotifyProps(id.to_owned().into()))
+            .with(ScrollViewRange::default());
+        let size_props = SizeBoxProps {
+            width: SizeBoxSizeValue::Exact(200.0),
+            height: SizeBoxSizeValue::Exact(200.0),
+            ..Default::default()
+        };
+
+        widget! {
+            (#{key} nav_scroll_box: {scroll_props} {
+                content = (#{"button"} button: {NavItemActive} {
+                    content = (#{"size"} size_box: {size_props})
+                })
+                scrollbars = (#{"scrollbars"} nav_scroll_box_side_scrollbars)
+            })
+        }
+    }
+
+    let mut button = WidgetId::default();
+    let mut application = Application::new();
+    let mut interactions = DefaultInteractionsEngine::default();
+    interactions.deselect_when_no_button_found = true;
+    application.apply(widget! { (#{"app"} app: {NavContainerActive}) });
+
+    run(
+        &mut application,
+        &mapping,
+        &mut layout_engine,
+        &mut interactions,
+        &[None],
+        |id, msg| {
+            if let Some(NavSignal::Register(NavType::Button(_))) = msg.as_any().downcast_ref() {
+                println!("* Button registered: {:?}", id);
+                button = id.to_owned();
+            }
+        },
+    );
+
+    run(
+        &mut application,
+        &mapping,
+        &mut layout_engine,
+        &mut interactions,
+        &[
+            None,
+            None,
+            None,
+            Some(Interaction::Navigate(NavSignal::Select(
+                button.to_owned().into(),
+            ))),
+            Some(Interaction::Navigate(NavSignal::Jump(NavJump::Scroll(
+                NavScroll::Factor(Vec2 { x: 2.0, y: 2.0 }, false),
+            )))),
+            Some(Interaction::Navigate(NavSignal::Jump(NavJump::Scroll(
+                NavScroll::Units(Vec2 { x: -50.0, y: -50.0 }, true),
+            )))),
+            Some(Interaction::Navigate(NavSignal::Jump(NavJump::Scroll(
+                NavScroll::Widget(button.into(), Vec2 { x: 0.5, y: 0.5 }),
+            )))),
+            Some(Interaction::PointerDown(
+                PointerButton::Trigger,
+                Vec2 { x: 95.0, y: 0.0 },
+            )),
+            Some(Interaction::PointerMove(Vec2 { x: 95.0, y: 45.0 })),
+            Some(Interaction::PointerMove(Vec2 { x: 95.0, y: 90.0 })),
+            Some(Interaction::PointerUp(
+                PointerButton::Trigger,
+                Vec2 { x: 95.0, y: 90.0 },
+            )),
+            None,
+            None,
+            None,
+        ],
+        |_, _| {},
+    );
+}
+
+#[test]
+fn test_immediate_mode() {
+    fn use_app(context: &mut WidgetContext) {
+        context.use_hook(use_nav_container);
+    }
+
+    fn app(mut context: WidgetContext) -> WidgetNode {
+        context.use_hook(use_app);
+
+        let title = context.named_slots.remove("title").unwrap_or_default();
+        let content = context.named_slots.remove("content").unwrap_or_default();
+
+        make_widget!(content_box)
+            .key(&context.key)
+            .listed_slot(title)
+            .listed_slot(content)
+            .into()
+    }
+
+    fn make_app(key: &str) -> WidgetComponent {
+        make_widget!(app).key(key)
+    }
+
+    fn make_text_box(key: &str, text: &str) -> WidgetComponent {
+        make_widget!(text_box).key(key).with_props(TextBoxProps {
+            text: text.to_owned(),
+            ..Default::default()
+        })
+    }
+
+    fn make_button(key: &str, text: &str) -> WidgetComponent {
+        make_widget!(button)
+            .key(key)
+            .with_props(NavItemActive)
+            .named_slot("content", make_text_box("text", text))
+    }
+
+    let mut application = Application::new();
+    application.apply(
+        make_app("app")
+            .with_props(NavContainerActive)
+            .named_slot("title", make_text_box("text", "Hello, World!"))
+            .named_slot("content", make_button("button", "Click me!"))
+            .into(),
+    );
+}
+
+#[test]
+fn test_animation_time_scale() {
+    fn use_item(context: &mut WidgetContext) {
+        context.life_cycle.mount(|context| {
+            let _ = context.animator.change(
+                "spin",
+                Some(Animation::Looped(Box::new(Animation::Value(
+                    AnimatedValue {
+                        name: "angle".to_owned(),
+                        duration: 8.0,
+                    },
+                )))),
+            );
+        });
+    }
+
+    #[pre_hooks(use_item)]
+    fn item(mut context: WidgetContext) -> WidgetNode {
+        widget! {
+            (#{context.key} image_box)
+        }
+    }
+
+    fn make_app(scale: Option<Scalar>) -> WidgetNode {
+        let app = make_widget!(content_box)
+            .key("app")
+            .listed_slot(make_widget!(item).key("item"));
+        match scale {
+            Some(scale) => app.with_shared_props(AnimationTimeScale(scale)).into(),
+            None => app.into(),
+        }
+    }
+
+    fn progress(application: &Application) -> Scalar {
+        let id = WidgetId::from_str("item:/app/item").unwrap();
+        application
+            .animator_states(&id)
+            .unwrap()
+            .value_progress_factor_or_zero("spin", "angle")
+    }
+
+    let mut application = Application::new();
+    application.apply(make_app(None));
+    application.forced_process();
+    application.animations_delta_time = 1.0;
+    assert!(application.process());
+    assert_eq!(progress(&application), 0.125);
+
+    // scale set in the same pass freezes animation right away and frozen animation does not
+    // keep application processing.
+    application.apply(make_app(Some(0.0)));
+    assert!(application.process());
+    assert_eq!(progress(&application), 0.125);
+    assert!(!application.process());
+    assert_eq!(progress(&application), 0.125);
+
+    // scale persists over passes that do not reprocess the scaling widget props.
+    application.apply(make_app(Some(2.0)));
+    assert!(application.process());
+    assert_eq!(progress(&application), 0.375);
+    assert!(application.process());
+    assert_eq!(progress(&application), 0.625);
+}
+
+#[test]
+fn test_exit_transitions() {
+    fn use_item(context: &mut WidgetContext) {
+        context.life_cycle.exit(
+            "exit",
+            Animation::Value(AnimatedValue {
+                name: "fade".to_owned(),
+                duration: 0.5,
+            }),
+        );
+
+        context.life_cycle.unmount(|context| {
+            let _ = context.signals.write(());
+        });
+    }
+
+    #[pre_hooks(use_item)]
+    fn item(mut context: WidgetContext) -> WidgetNode {
+        widget! {
+            (#{context.key} image_box)
+        }
+    }
+
+    fn make_app(keys: &[&str]) -> WidgetNode {
+        let items = keys
+            .iter()
+            .map(|key| make_widget!(item).key(key).into())
+            .collect::<Vec<WidgetNode>>();
+        widget! {
+            (#{"app"} vertical_box |[ items ]|)
+        }
+    }
+
+    fn items_count(application: &Application) -> usize {
+        match application.rendered_tree() {
+            WidgetUnit::FlexBox(unit) => unit.items.len(),
+            _ => 0,
+        }
+    }
+
+    let departing = WidgetId::from_str("item:/app/app/b").unwrap();
+    let mut application = Application::new();
+    application.apply(make_app(&["a", "b", "c"]));
+    application.forced_process();
+    assert_eq!(items_count(&application), 3);
+
+    application.apply(make_app(&["a", "c"]));
+    application.forced_process();
+    assert_eq!(items_count(&application), 3);
+    assert!(application.is_widget_departing(&departing));
+    assert!(application.is_widget_departing(&WidgetId::from_str("image_box:/app/app/b/b").unwrap()));
+    assert!(!application.is_widget_departing(&WidgetId::from_str("item:/app/app/a").unwrap()));
+    assert!(application.signals().is_empty());
+    assert!(application
+        .animator_states(&departing)
+        .unwrap()
+        .in_progress());
+
+    application.animations_delta_time = 1.0;
+    application.process();
+    assert_eq!(items_count(&application), 3);
+    assert!(application.signals().is_empty());
+
+    application.process();
+    assert_eq!(items_count(&application), 2);
+    assert!(!application.is_widget_departing(&departing));
+    assert_eq!(application.signals().len(), 1);
+    assert_eq!(application.signals()[0].0, departing);
+}
+
+#[test]
+fn test_layout_transition() {
+    fn make_app(offset: Scalar) -> WidgetNode {
+        widget! {
+            (#{"app"} vertical_box [
+                (#{"offset"} size_box: {
+                    Props::new(SizeBoxProps {
+                        height: SizeBoxSizeValue::Exact(offset),
+                        ..Default::default()
+                    }).with(FlexBoxItemLayout {
+                        grow: 0.0,
+                        shrink: 0.0,
+                        ..Default::default()
+                    })
+                })
+                (#{"transition"} layout_transition_box: {
+                    Props::new(LayoutTransitionProps {
+                        easing: Easing::Linear,
+                        ..Default::default()
+                    }).with(FlexBoxItemLayout {
+                        grow: 0.0,
+                        shrink: 0.0,
+                        ..Default::default()
+                    })
+                } {
+                    content = (#{"content"} size_box: {SizeBoxProps {
+                        height: SizeBoxSizeValue::Exact(50.0),
+                        ..Default::default()
+                    }})
+                })
+            ])
+        }
+    }
+
+    fn transform(application: &Application) -> Transform {
+        match application.rendered_tree() {
+            WidgetUnit::FlexBox(unit) => match &unit.items[1].slot {
+                WidgetUnit::ContentBox(unit) => unit.transform,
+                _ => panic!("transition box does not render content box"),
+            },
+            _ => panic!("app does not render flex box"),
+        }
+    }
+
+    let mut layout_engine = DefaultLayoutEngine::default();
+    let mut interactions = DefaultInteractionsEngine::new();
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 1024.0,
+        top: 0.0,
+        bottom: 576.0,
+    });
+    let mut application = Application::new();
+    let mut update = |application: &mut Application| {
+        application.process();
+        application.layout(&mapping, &mut layout_engine).unwrap();
+        application.interact(&mut interactions).unwrap();
+    };
+
+    application.apply(make_app(100.0));
+    update(&mut application);
+    assert_eq!(transform(&application), Transform::default());
+
+    // first frame rendered with new layout already shows content at its old place.
+    application.apply(make_app(200.0));
+    update(&mut application);
+    let current = transform(&application);
+    assert!((current.translation.y + 100.0).abs() < 1.0e-6);
+    assert!((current.scale.y - 1.0).abs() < 1.0e-6);
+    for _ in 0..3 {
+        update(&mut application);
+    }
+    let current = transform(&application);
+    assert!((current.translation.y + 100.0).abs() < 1.0e-6);
+    assert!((current.scale.y - 1.0).abs() < 1.0e-6);
+
+    application.animations_delta_time = 0.125;
+    update(&mut application);
+    assert!((transform(&application).translation.y + 50.0).abs() < 1.0e-6);
+
+    application.animations_delta_time = 1.0;
+    update(&mut application);
+    update(&mut application);
+    assert_eq!(transform(&application), Transform::default());
+}
+
+#[test]
+#[cfg(feature = "tesselate")]
+fn test_tesselation() {
+    let mut application = Application::new();
+    let mut layout_engine = DefaultLayoutEngine::default();
+    let atlas_mapping = Default::default();
+    let image_sizes = Default::default();
+    let mut renderer = TesselateRenderer::new(Default::default(), (), &atlas_mapping, &image_sizes);
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+    application.apply(
+        make_widget!(grid_box)
+            .with_props(GridBoxProps {
+                cols: 2,
+                rows: 2,
+                ..Default::default()
+            })
+            .listed_slot(
+                make_widget!(image_box)
+                    .with_props(ImageBoxProps {
+                        material: ImageBoxMaterial::Color(ImageBoxColor {
+                            color: Color {
+                                r: 0.25,
+                                g: 0.5,
+                                b: 0.75,
+                                a: 1.0,
+                            },
+                            scaling: ImageBoxImageScaling::Frame((10.0, true).into()),
+                        }),
+                        ..Default::default()
+                    })
+                    .with_props(GridBoxItemLayout {
+                        space_occupancy: IntRect {
+                            left: 1,
+                            right: 2,
+                            top: 0,
+                            bottom: 1,
+                        },
+                        ..Default::default()
+                    }),
+            )
+            .listed_slot(
+                make_widget!(image_box)
+                    .with_props(ImageBoxProps {
+                        material: ImageBoxMaterial::Image(ImageBoxImage {
+                            id: "ass".to_owned(),
+                            ..Default::default()
+                        }),
+                        content_keep_aspect_ratio: Some(ImageBoxAspectRatio {
+                            horizontal_alignment: 0.0,
+                            vertical_alignment: 0.5,
+                            outside: false,
+                        }),
+                        ..Default::default()
+                    })
+                    .with_props(GridBoxItemLayout {
+                        space_occupancy: IntRect {
+                            left: 0,
+                            right: 1,
+                            top: 0,
+                            bottom: 1,
+                        },
+                        ..Default::default()
+                    }),
+            )
+            .listed_slot(
+                make_widget!(image_box)
+                    .with_props(ImageBoxProps {
+                        material: ImageBoxMaterial::Image(ImageBoxImage {
+                            id: "ass".to_owned(),
+                            ..Default::default()
+                        }),
+                        content_keep_aspect_ratio: Some(ImageBoxAspectRatio {
+                            horizontal_alignment: 1.0,
+                            vertical_alignment: 0.5,
+                            outside: false,
+                        }),
+                        ..Default::default()
+                    })
+                    .with_props(GridBoxItemLayout {
+                        space_occupancy: IntRect {
+                            left: 0,
+                            right: 2,
+                            top: 1,
+                            bottom: 2,
+                        },
+                        ..Default::default()
+                    }),
+            )
+            .listed_slot(make_widget!(text_box).with_props(TextBoxProps {
+                text: "hello".to_owned(),
+                font: TextBoxFont {
+                    name: "font".to_owned(),
+                    size: 16.0,
+                },
+                ..Default::default()
+            }))
+            .into(),
+    );
+    application.forced_process();
+    application
+        .layout(&mapping, &mut layout_engine)
+        .expect("Failed layouting");
+    let tesselation = application
+        .render(&mapping, &mut renderer)
+        .expect("Cannot tesselate UI tree!")
+        .optimized_batches();
+    println!("* Tesselation: {:#?}", tesselation);
+}
+
+#[test]
+#[cfg(feature = "tesselate")]
+fn test_z_index() {
+    use crate::renderer::tesselate::prelude::*;
+
+    fn image(id: &str, r: Scalar) -> ImageBoxNode {
+        ImageBoxNode {
+            id: WidgetId::from_str(id).unwrap(),
+            material: ImageBoxMaterial::Color(ImageBoxColor {
+                color: Color {
+                    r,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 1.0,
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    // Popup nested in the first item overlaps later sibling.
+    let tree = |z_index, clipping| {
+        ContentBoxNode {
+            id: WidgetId::from_str("type:/root").unwrap(),
+            items: vec![
+                ContentBoxItemNode {
+                    slot: FlexBoxNode {
+                        id: WidgetId::from_str("type:/root/menu").unwrap(),
+                        items: vec![FlexBoxItemNode {
+                            slot: image("type:/root/menu/popup", 1.0).into(),
+                            layout: FlexBoxItemLayout {
+                                z_index,
+                                ..Default::default()
+                            },
+                        }],
+                        ..Default::default()
+                    }
+                    .into(),
+                    layout: Default::default(),
+                },
+                ContentBoxItemNode {
+                    slot: image("type:/root/cover", 0.5).into(),
+                    layout: Default::default(),
+                },
+            ],
+            clipping,
+            ..Default::default()
+        }
+        .into()
+    };
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+    let atlas_mapping = Default::default();
     let image_sizes = Default::default();
-    let mut renderer = TesselateRenderer::new(Default::default(), (), &atlas_mapping, &image_sizes);
+    let mut renderer = TesselateRenderer::new(
+        TesselationVerticesFormat::Separated,
+        (),
+        &atlas_mapping,
+        &image_sizes,
+    );
+    let mut render = |z_index, clipping| {
+        let mut application = Application::new();
+        application.apply(tree(z_index, clipping));
+        application.forced_process();
+        application
+            .layout(&mapping, &mut DefaultLayoutEngine::default())
+            .unwrap();
+        let tesselation = application.render(&mapping, &mut renderer).unwrap();
+        // color of last drawn triangles.
+        let last = tesselation
+            .batches
+            .iter()
+            .rev()
+            .find_map(|batch| match batch {
+                Batch::ColoredTriangles(range) => {
+                    let index = tesselation.indices[range.start] as usize;
+                    Some(tesselation.vertices.as_separated().unwrap().color[index].r)
+                }
+                _ => None,
+            })
+            .unwrap();
+        let batches = tesselation
+            .batches
+            .iter()
+            .map(|batch| match batch {
+                Batch::ColoredTriangles(_) => "triangles",
+                Batch::ClipPush(_) => "push",
+                Batch::ClipPop => "pop",
+                _ => "other",
+            })
+            .collect::<Vec<_>>();
+        (application.layout_data().to_owned(), last, batches)
+    };
+
+    let (layout, last, _) = render(0, false);
+    assert_eq!(layout.layers(), vec![0]);
+    assert_eq!(last, 0.5);
+
+    let (layout, last, _) = render(1, false);
+    assert_eq!(layout.layers(), vec![0, 1]);
+    assert_eq!(
+        layout.items[&WidgetId::from_str("type:/root/menu/popup").unwrap()].layer,
+        1
+    );
+    assert_eq!(
+        layout.items[&WidgetId::from_str("type:/root/cover").unwrap()].layer,
+        0
+    );
+    assert_eq!(last, 1.0);
+
+    // clip rectangle is opened once per layer with clipped content.
+    let (_, last, batches) = render(0, true);
+    assert_eq!(last, 0.5);
+    assert_eq!(batches, vec!["push", "triangles", "triangles", "pop"]);
+    let (_, last, batches) = render(1, true);
+    assert_eq!(last, 1.0);
+    assert_eq!(
+        batches,
+        vec!["push", "triangles", "pop", "push", "triangles", "pop"]
+    );
+}
+
+#[test]
+#[cfg(feature = "tesselate")]
+fn test_sprite_animation() {
+    let mut application = Application::new();
+    let mut layout_engine = DefaultLayoutEngine::default();
+    let mut atlas_mapping = std::collections::HashMap::new();
+    atlas_mapping.insert(
+        "sheet".to_owned(),
+        (
+            "atlas".to_owned(),
+            Rect {
+                left: 0.5,
+                right: 1.0,
+                top: 0.0,
+                bottom: 0.5,
+            },
+        ),
+    );
+    let image_sizes = Default::default();
+    let mut renderer = TesselateRenderer::new(
+        crate::renderer::tesselate::prelude::TesselationVerticesFormat::Separated,
+        (),
+        &atlas_mapping,
+        &image_sizes,
+    );
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+    let make_app = |sprite: SpriteAnimationProps| -> WidgetNode {
+        make_widget!(sprite_image_box)
+            .with_props(ImageBoxProps {
+                material: ImageBoxMaterial::Image(ImageBoxImage {
+                    id: "sheet".to_owned(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .with_props(sprite)
+            .into()
+    };
+    application.apply(make_app(SpriteAnimationProps {
+        layout: SpriteSheetLayout::Grid {
+            columns: 2,
+            rows: 2,
+            count: Some(3),
+        },
+        frame_duration: 0.5,
+        frame_durations: vec![0.25],
+        looping: SpriteAnimationLooping::Once,
+    }));
+    let mut tex_coords = |application: &mut Application| {
+        application.process();
+        application
+            .layout(&mapping, &mut layout_engine)
+            .expect("Failed layouting");
+        let tesselation = application
+            .render(&mapping, &mut renderer)
+            .expect("Cannot tesselate UI tree!");
+        let vertices = tesselation.vertices.as_separated().unwrap();
+        (vertices.tex_coord[0], vertices.tex_coord[2])
+    };
+
+    let (tl, br) = tex_coords(&mut application);
+    assert_eq!((tl.x, tl.y, br.x, br.y), (0.5, 0.0, 0.75, 0.25));
+
+    application.animations_delta_time = 0.375;
+    let (tl, br) = tex_coords(&mut application);
+    assert_eq!((tl.x, tl.y, br.x, br.y), (0.75, 0.0, 1.0, 0.25));
+
+    application.animations_delta_time = 10.0;
+    tex_coords(&mut application);
+    tex_coords(&mut application);
+    let (tl, br) = tex_coords(&mut application);
+    assert_eq!((tl.x, tl.y, br.x, br.y), (0.5, 0.25, 0.75, 0.5));
+
+    // changed props restart animation from the first frame with new timing.
+    application.apply(make_app(SpriteAnimationProps {
+        layout: SpriteSheetLayout::Grid {
+            columns: 2,
+            rows: 2,
+            count: Some(3),
+        },
+        frame_duration: 1.0,
+        frame_durations: vec![],
+        looping: SpriteAnimationLooping::Loop,
+    }));
+    application.animations_delta_time = 0.0;
+    let (tl, br) = tex_coords(&mut application);
+    assert_eq!((tl.x, tl.y, br.x, br.y), (0.5, 0.0, 0.75, 0.25));
+    tex_coords(&mut application);
+    application.animations_delta_time = 1.5;
+    let (tl, br) = tex_coords(&mut application);
+    assert_eq!((tl.x, tl.y, br.x, br.y), (0.75, 0.0, 1.0, 0.25));
+    application.animations_delta_time = 1.0;
+    let (tl, br) = tex_coords(&mut application);
+    assert_eq!((tl.x, tl.y, br.x, br.y), (0.5, 0.25, 0.75, 0.5));
+}
+
+#[test]
+fn test_custom_unit() {
+    use serde::{Deserialize, Serialize};
+    use std::{
+        convert::TryFrom,
+        hash::{Hash, Hasher},
+    };
+
+    #[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+    struct ChartProps {
+        values: Vec<Scalar>,
+    }
+
+    struct ChartMeasurement;
+
+    impl CustomUnitMeasurementEngine for ChartMeasurement {
+        fn measure_custom_unit(&self, _: Vec2, unit: &CustomBox) -> Option<Vec2> {
+            let props = unit.props.read::<ChartProps>().ok()?;
+            Some(Vec2 {
+                x: props.values.len() as Scalar * 10.0,
+                y: props.values.iter().copied().fold(0.0, Scalar::max),
+            })
+        }
+
+        fn hash_custom_unit(&self, unit: &CustomBox, mut state: &mut dyn Hasher) {
+            if let Ok(props) = unit.props.read::<ChartProps>() {
+                for value in &props.values {
+                    value.to_bits().hash(&mut state);
+                }
+            }
+        }
+    }
+
+    let mut application = Application::new();
+    application.setup(setup);
+    application.register_props::<ChartProps>("ChartProps");
+    let tree = |values: Vec<Scalar>| -> WidgetNode {
+        make_widget!(flex_box)
+            .key("root")
+            .listed_slot(
+                make_widget!(custom_box)
+                    .key("chart")
+                    .with_props(CustomBoxProps {
+                        type_name: "chart".to_owned(),
+                        ..Default::default()
+                    })
+                    .with_props(ChartProps { values })
+                    .with_props(FlexBoxItemLayout::cleared()),
+            )
+            .listed_slot(
+                make_widget!(custom_box)
+                    .key("unknown")
+                    .with_props(CustomBoxProps {
+                        type_name: "unknown".to_owned(),
+                        ..Default::default()
+                    }),
+            )
+            .into()
+    };
+    application.apply(tree(vec![20.0, 35.0, 10.0]));
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 50.0,
+    });
+    let mut layout_engine =
+        DefaultLayoutEngine::default().with_custom_unit("chart", ChartMeasurement);
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let layout = application.layout_data();
+    let rect = |path: &str| layout.find(path).map(|item| item.ui_space).unwrap();
+    assert_eq!(
+        rect("root/chart"),
+        Rect {
+            left: 0.0,
+            right: 30.0,
+            top: 0.0,
+            bottom: 35.0,
+        }
+    );
+    assert_eq!(
+        rect("root/unknown"),
+        Rect {
+            left: 30.0,
+            right: 100.0,
+            top: 0.0,
+            bottom: 50.0,
+        }
+    );
+
+    // Changed props of custom unit invalidate its cached layout.
+    application.apply(tree(vec![40.0]));
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    assert_eq!(
+        application
+            .layout_data()
+            .find("root/chart")
+            .unwrap()
+            .ui_space
+            .size(),
+        Vec2 { x: 10.0, y: 40.0 }
+    );
+
+    let node: WidgetNode = CustomBoxNode {
+        id: WidgetId::from_str("type:/chart").unwrap(),
+        props: Props::new(ChartProps {
+            values: vec![1.0, 2.0],
+        }),
+        type_name: "chart".to_owned(),
+        ..Default::default()
+    }
+    .into();
+    let prefab = application.serialize_node(&node).unwrap();
+    let node = application.deserialize_node(prefab).unwrap();
+    let unit = WidgetUnit::try_from(node).unwrap();
+    if let WidgetUnit::CustomBox(unit) = unit {
+        assert_eq!(unit.type_name, "chart");
+        assert_eq!(
+            unit.props.read::<ChartProps>().unwrap().values,
+            vec![1.0, 2.0]
+        );
+    } else {
+        panic!("Expected custom unit");
+    }
+
+    // Serialized units keep everything but custom unit props.
+    let unit = WidgetUnit::CustomBox(CustomBox {
+        id: WidgetId::from_str("type:/chart").unwrap(),
+        type_name: "chart".to_owned(),
+        props: Props::new(ChartProps {
+            values: vec![1.0, 2.0],
+        }),
+        transform: Transform {
+            translation: Vec2 { x: 5.0, y: 10.0 },
+            ..Default::default()
+        },
+    });
+    let yaml = serde_yaml::to_string(&unit).unwrap();
+    let unit = serde_yaml::from_str::<WidgetUnit>(&yaml).unwrap();
+    if let WidgetUnit::CustomBox(unit) = unit {
+        assert_eq!(unit.id, WidgetId::from_str("type:/chart").unwrap());
+        assert_eq!(unit.type_name, "chart");
+        assert_eq!(unit.transform.translation, Vec2 { x: 5.0, y: 10.0 });
+        assert!(!unit.props.has::<ChartProps>());
+    } else {
+        panic!("Expected custom unit");
+    }
+}
+
+#[test]
+#[cfg(feature = "tesselate")]
+fn test_custom_unit_tesselation() {
+    use crate::renderer::tesselate::{prelude::*, Error, Index};
+
+    struct QuadTesselation;
+
+    impl CustomTesselationEngine for QuadTesselation {
+        fn count(&self, _: &CustomBox, _: &LayoutItem) -> (usize, usize, usize) {
+            (4, 6, 1)
+        }
+
+        fn tesselate(
+            &mut self,
+            _: &CustomBox,
+            _: &LayoutItem,
+            _: [Scalar; 16],
+            _: TesselationVerticesSliceMut,
+            output_indices: &mut [Index],
+            output_batches: &mut [Batch],
+        ) -> Result<(), Error> {
+            output_indices.copy_from_slice(&[0, 1, 2, 2, 3, 0]);
+            output_batches[0] = Batch::ColoredTriangles(0..6);
+            Ok(())
+        }
+    }
+
+    let mut application = Application::new();
+    application.setup(setup);
+    application.apply(
+        make_widget!(vertical_box)
+            .listed_slot(make_widget!(image_box))
+            .listed_slot(make_widget!(custom_box).with_props(CustomBoxProps {
+                type_name: "quad".to_owned(),
+                ..Default::default()
+            }))
+            .listed_slot(make_widget!(custom_box).with_props(CustomBoxProps {
+                type_name: "external".to_owned(),
+                ..Default::default()
+            }))
+            .into(),
+    );
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 90.0,
+    });
+    let mut layout_engine = DefaultLayoutEngine::default();
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let atlas_mapping = Default::default();
+    let image_sizes = Default::default();
+    let mut renderer = TesselateRenderer::new(
+        TesselationVerticesFormat::Separated,
+        (),
+        &atlas_mapping,
+        &image_sizes,
+    )
+    .with_custom_unit("quad", QuadTesselation);
+    let tesselation = application.render(&mapping, &mut renderer).unwrap();
+    let vertices = tesselation.vertices.as_separated().unwrap().position.len() as Index;
+    let range = match &tesselation.batches[tesselation.batches.len() - 2] {
+        Batch::ColoredTriangles(range) => range.clone(),
+        batch => panic!("Unexpected batch: {:?}", batch),
+    };
+    assert_eq!(range.end, tesselation.indices.len());
+    assert_eq!(range.len(), 6);
+    assert!(tesselation.indices[range]
+        .iter()
+        .all(|index| *index >= vertices - 4 && *index < vertices));
+    match tesselation.batches.last() {
+        Some(Batch::ExternalCustom(_, batch)) => {
+            assert_eq!(batch.type_name, "external");
+            assert_eq!(batch.box_size, Vec2 { x: 100.0, y: 30.0 });
+        }
+        batch => panic!("Unexpected batch: {:?}", batch),
+    }
+}
+
+#[test]
+fn test_rich_text() {
+    let style = RichTextMarkupStyle {
+        font: TextBoxFont {
+            name: "regular".to_owned(),
+            size: 10.0,
+        },
+        bold_font: "bold".to_owned(),
+        link_color: Some(Color {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        }),
+        ..Default::default()
+    };
+    let markup = "Hello <b>big</b> <link=home>world</link><img=smile>\\<3";
+    let spans = parse_rich_text_markup(markup, &style).unwrap();
+    assert_eq!(
+        spans.iter().map(|span| span.text()).collect::<Vec<_>>(),
+        vec![
+            Some("Hello "),
+            Some("big"),
+            Some(" "),
+            Some("world"),
+            None,
+            Some("<3")
+        ]
+    );
+    assert_eq!(spans[0].font.name, "regular");
+    assert_eq!(spans[1].font.name, "bold");
+    assert_eq!(spans[3].link.as_deref(), Some("home"));
+    assert_eq!(spans[3].color.r, 1.0);
+    assert_eq!(spans[3].color.g, 0.0);
+    match &spans[4].content {
+        RichTextBoxSpanContent::Image(image) => {
+            assert_eq!(image.id, "smile");
+            assert_eq!(image.width, 10.0);
+            assert_eq!(image.height, 10.0);
+        }
+        content => panic!("Unexpected span content: {:?}", content),
+    }
+    assert_eq!(
+        parse_rich_text_markup("<b>bold", &style).unwrap_err(),
+        RichTextMarkupError::UnclosedTag("b".to_owned())
+    );
+    assert_eq!(
+        parse_rich_text_markup("<b>bold</i>", &style).unwrap_err(),
+        RichTextMarkupError::UnexpectedClosingTag("i".to_owned())
+    );
+    assert_eq!(
+        parse_rich_text_markup("<wave>text</wave>", &style).unwrap_err(),
+        RichTextMarkupError::UnknownTag("wave".to_owned())
+    );
+    assert_eq!(
+        parse_rich_text_markup("<color=red>text</color>", &style).unwrap_err(),
+        RichTextMarkupError::InvalidTagValue("color".to_owned(), "red".to_owned())
+    );
+    assert_eq!(
+        parse_rich_text_markup("text <b", &style).unwrap_err(),
+        RichTextMarkupError::UnterminatedTag(5)
+    );
+
+    let mut application = Application::new();
+    application.setup(setup);
+    application.apply(
+        make_widget!(rich_text_box)
+            .key("text")
+            .with_props(RichTextBoxProps {
+                text: "Hello <b>big</b> <link=home>world</link>".to_owned(),
+                width: TextBoxSizeValue::Exact(50.0),
+                height: TextBoxSizeValue::Content,
+                style,
+                ..Default::default()
+            })
+            .into(),
+    );
     let mapping = CoordsMapping::new(Rect {
         left: 0.0,
         right: 100.0,
         top: 0.0,
         bottom: 100.0,
     });
-    application.apply(
-        make_widget!(grid_box)
-            .with_props(GridBoxProps {
-                cols: 2,
-                rows: 2,
+    let mut layout_engine = DefaultLayoutEngine::default();
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let layout = application.layout_data();
+    let item = layout.find("text").unwrap();
+    assert_eq!(
+        item.ui_space,
+        Rect {
+            left: 0.0,
+            right: 50.0,
+            top: 0.0,
+            bottom: 20.0,
+        }
+    );
+    let fragment = |span, range, text: &str, left, right, top, bottom| LayoutTextFragment {
+        span,
+        range,
+        text: text.to_owned(),
+        font_size: 10.0,
+        rect: Rect {
+            left,
+            right,
+            top,
+            bottom,
+        },
+        baseline: 8.0,
+    };
+    assert_eq!(
+        item.fragments,
+        vec![
+            fragment(0, 0..6, "Hello ", 0.0, 30.0, 0.0, 10.0),
+            fragment(1, 0..3, "big", 30.0, 45.0, 0.0, 10.0),
+            fragment(2, 0..1, " ", 45.0, 50.0, 0.0, 10.0),
+            fragment(3, 0..5, "world", 0.0, 25.0, 10.0, 20.0),
+        ]
+    );
+    let link_at = |x, y| {
+        layout
+            .rich_text_link_at(application.rendered_tree(), Vec2 { x, y })
+            .map(|(id, link)| (id.path().to_owned(), link))
+    };
+    assert_eq!(
+        link_at(10.0, 15.0),
+        Some(("text".to_owned(), "home".to_owned()))
+    );
+    assert_eq!(link_at(10.0, 5.0), None);
+    assert_eq!(link_at(40.0, 15.0), None);
+
+    // Links get clicked on release over the link pressed before.
+    let mut interactions = DefaultInteractionsEngine::new();
+    let mut click = |application: &mut Application, interaction| {
+        interactions.interact(interaction);
+        application.interact(&mut interactions).unwrap();
+        application.forced_process();
+        application.layout(&mapping, &mut layout_engine).unwrap();
+        application.signals().iter().any(|(id, msg)| {
+            id.path() == "text"
+                && msg
+                    .as_any()
+                    .downcast_ref::<RichTextLinkMessage>()
+                    .map(|msg| msg.link == "home")
+                    .unwrap_or_default()
+        })
+    };
+    let position = Vec2 { x: 10.0, y: 15.0 };
+    assert!(!click(
+        &mut application,
+        Interaction::PointerDown(PointerButton::Trigger, position)
+    ));
+    assert!(click(
+        &mut application,
+        Interaction::PointerUp(PointerButton::Trigger, position)
+    ));
+    assert!(!click(
+        &mut application,
+        Interaction::PointerDown(PointerButton::Trigger, position)
+    ));
+    let away = Vec2 { x: 40.0, y: 15.0 };
+    assert!(!click(&mut application, Interaction::PointerMove(away)));
+    assert!(!click(
+        &mut application,
+        Interaction::PointerUp(PointerButton::Trigger, away)
+    ));
+}
+
+#[test]
+fn test_text_overflow() {
+    fn layout_text(props: TextBoxProps) -> LayoutItem {
+        let mut application = Application::new();
+        application.setup(setup);
+        application.apply(make_widget!(text_box).key("text").with_props(props).into());
+        let mapping = CoordsMapping::new(Rect {
+            left: 0.0,
+            right: 100.0,
+            top: 0.0,
+            bottom: 100.0,
+        });
+        application.forced_process();
+        application
+            .layout(&mapping, &mut DefaultLayoutEngine::default())
+            .unwrap();
+        application.layout_data().find("text").unwrap().to_owned()
+    }
+
+    fn texts(item: &LayoutItem) -> Vec<&str> {
+        item.fragments
+            .iter()
+            .map(|fragment| fragment.text.as_str())
+            .collect()
+    }
+
+    // without text measurement engine glyphs are half of font size wide.
+    let props = |text: &str, width: Scalar| TextBoxProps {
+        text: text.to_owned(),
+        width: TextBoxSizeValue::Exact(width),
+        height: TextBoxSizeValue::Content,
+        font: TextBoxFont {
+            name: "font".to_owned(),
+            size: 10.0,
+        },
+        ..Default::default()
+    };
+
+    assert!(layout_text(props("hello big world", 50.0))
+        .fragments
+        .is_empty());
+
+    let item = layout_text(TextBoxProps {
+        line_height: Some(12.0),
+        ..props("hello big world", 50.0)
+    });
+    assert_eq!(texts(&item), vec!["hello big", "world"]);
+    assert_eq!(item.ui_space.height(), 24.0);
+    assert_eq!(item.fragments[1].range, 10..15);
+    assert_eq!(
+        item.fragments[1].rect,
+        Rect {
+            left: 0.0,
+            right: 25.0,
+            top: 13.0,
+            bottom: 23.0,
+        }
+    );
+
+    let item = layout_text(TextBoxProps {
+        wrap: TextBoxWrap::Character,
+        ..props("hello world", 40.0)
+    });
+    assert_eq!(texts(&item), vec!["hello wo", "rld"]);
+
+    let item = layout_text(TextBoxProps {
+        max_lines: Some(2),
+        ..props("hello world", 40.0)
+    });
+    assert_eq!(texts(&item), vec!["hello", "world"]);
+
+    let item = layout_text(TextBoxProps {
+        max_lines: Some(1),
+        overflow: TextBoxOverflow::EllipsisEnd,
+        ..props("hello big world", 50.0)
+    });
+    assert_eq!(texts(&item), vec!["hello big\u{2026}"]);
+    assert_eq!(item.fragments[0].range, 0..15);
+
+    let item = layout_text(TextBoxProps {
+        wrap: TextBoxWrap::None,
+        overflow: TextBoxOverflow::EllipsisMiddle,
+        ..props("abcdefghijklmnop", 50.0)
+    });
+    assert_eq!(texts(&item), vec!["abcde\u{2026}mnop"]);
+
+    let item = layout_text(TextBoxProps {
+        wrap: TextBoxWrap::None,
+        overflow: TextBoxOverflow::Clip,
+        ..props("abcdefghijklmnop", 50.0)
+    });
+    assert_eq!(texts(&item), vec!["abcdefghij"]);
+
+    let item = layout_text(TextBoxProps {
+        height: TextBoxSizeValue::Exact(25.0),
+        overflow: TextBoxOverflow::Clip,
+        ..props("aa bb cc dd", 10.0)
+    });
+    assert_eq!(texts(&item), vec!["aa", "bb"]);
+
+    let item = layout_text(TextBoxProps {
+        wrap: TextBoxWrap::None,
+        overflow: TextBoxOverflow::ShrinkToFit,
+        ..props("abcdefghijklmnopqrst", 50.0)
+    });
+    assert_eq!(texts(&item), vec!["abcdefghijklmnopqrst"]);
+    assert_eq!(item.fragments[0].font_size, 5.0);
+    assert_eq!(item.ui_space.height(), 5.0);
+
+    // Empty lines do not break wrapping at negative available width.
+    let node = DefaultLayoutEngine::default()
+        .layout_text_box(
+            Vec2 { x: -4.0, y: 10.0 },
+            &TextBox {
+                id: WidgetId::from_str("type:/text").unwrap(),
+                text: "a\n\nb".to_owned(),
+                width: TextBoxSizeValue::Fill,
+                max_lines: Some(2),
                 ..Default::default()
-            })
-            .listed_slot(
-                make_widget!(image_box)
-                    .with_props(ImageBoxProps {
-                        material: ImageBoxMaterial::Color(ImageBoxColor {
-                            color: Color {
-                                r: 0.25,
-                                g: 0.5,
-                                b: 0.75,
-                                a: 1.0,
-                            },
-                            scaling: ImageBoxImageScaling::Frame((10.0, true).into()),
-                        }),
-                        ..Default::default()
-                    })
-                    .with_props(GridBoxItemLayout {
-                        space_occupancy: IntRect {
-                            left: 1,
-                            right: 2,
-                            top: 0,
-                            bottom: 1,
-                        },
-                        ..Default::default()
-                    }),
-            )
-            .listed_slot(
-                make_widget!(image_box)
-                    .with_props(ImageBoxProps {
-                        material: ImageBoxMaterial::Image(ImageBoxImage {
-                            id: "ass".to_owned(),
-                            ..Default::default()
-                        }),
-                        content_keep_aspect_ratio: Some(ImageBoxAspectRatio {
-                            horizontal_alignment: 0.0,
-                            vertical_alignment: 0.5,
-                            outside: false,
-                        }),
-                        ..Default::default()
-                    })
-                    .with_props(GridBoxItemLayout {
-                        space_occupancy: IntRect {
-                            left: 0,
-                            right: 1,
-                            top: 0,
-                            bottom: 1,
-                        },
-                        ..Default::default()
-                    }),
-            )
-            .listed_slot(
-                make_widget!(image_box)
-                    .with_props(ImageBoxProps {
-                        material: ImageBoxMaterial::Image(ImageBoxImage {
-                            id: "ass".to_owned(),
-                            ..Default::default()
-                        }),
-                        content_keep_aspect_ratio: Some(ImageBoxAspectRatio {
-                            horizontal_alignment: 1.0,
-                            vertical_alignment: 0.5,
-                            outside: false,
-                        }),
-                        ..Default::default()
-                    })
-                    .with_props(GridBoxItemLayout {
-                        space_occupancy: IntRect {
-                            left: 0,
-                            right: 2,
-                            top: 1,
-                            bottom: 2,
-                        },
-                        ..Default::default()
-                    }),
-            )
-            .listed_slot(make_widget!(text_box).with_props(TextBoxProps {
-                text: "hello".to_owned(),
+            },
+        )
+        .unwrap();
+    let texts = node
+        .fragments
+        .iter()
+        .map(|fragment| fragment.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(texts, vec!["a", ""]);
+}
+
+#[test]
+#[cfg(feature = "tesselate")]
+fn test_text_overflow_tesselation() {
+    use crate::renderer::tesselate::prelude::*;
+
+    let mut application = Application::new();
+    application.setup(setup);
+    application.apply(
+        make_widget!(text_box)
+            .with_props(TextBoxProps {
+                text: "hello big world".to_owned(),
+                width: TextBoxSizeValue::Exact(50.0),
+                max_lines: Some(2),
                 font: TextBoxFont {
                     name: "font".to_owned(),
-                    size: 16.0,
+                    size: 10.0,
                 },
                 ..Default::default()
-            }))
+            })
             .into(),
     );
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+    let mut layout_engine = DefaultLayoutEngine::default();
     application.forced_process();
-    application
-        .layout(&mapping, &mut layout_engine)
-        .expect("Failed layouting");
-    let tesselation = application
-        .render(&mapping, &mut renderer)
-        .expect("Cannot tesselate UI tree!")
-        .optimized_batches();
-    println!("* Tesselation: {:#?}", tesselation);
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let atlas_mapping = Default::default();
+    let image_sizes = Default::default();
+    let mut renderer = TesselateRenderer::new(
+        TesselationVerticesFormat::Separated,
+        (),
+        &atlas_mapping,
+        &image_sizes,
+    );
+    let tesselation = application.render(&mapping, &mut renderer).unwrap();
+    let lines = tesselation
+        .batches
+        .iter()
+        .filter_map(|batch| match batch {
+            Batch::ExternalText(_, text) => Some((text.text.as_str(), text.wrap, text.matrix[13])),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            ("hello big", TextBoxWrap::None, 0.0),
+            ("world", TextBoxWrap::None, 10.0),
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "tesselate")]
+fn test_shape_tesselation() {
+    use crate::renderer::tesselate::prelude::*;
+    #[cfg(not(feature = "scalar64"))]
+    use std::f32::consts::PI;
+    #[cfg(feature = "scalar64")]
+    use std::f64::consts::PI;
+
+    fn area(mesh: &ShapeMesh, color: Color) -> Scalar {
+        mesh.indices
+            .chunks(3)
+            .filter(|t| mesh.colors[t[0] as usize] == color)
+            .map(|t| {
+                let a = mesh.positions[t[0] as usize];
+                let b = mesh.positions[t[1] as usize];
+                let c = mesh.positions[t[2] as usize];
+                ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() * 0.5
+            })
+            .sum()
+    }
+
+    let fill = Color {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+    let border = ImageBoxShapeBorder {
+        width: 5.0,
+        color: Color {
+            r: 0.0,
+            g: 0.0,
+            b: 1.0,
+            a: 1.0,
+        },
+    };
+    let size = Vec2 { x: 100.0, y: 50.0 };
+    let shape_mesh = |shape: ImageBoxShape| {
+        let mesh = ShapeMesh::new(&shape, size, 1.0);
+        // capacity used to preallocate tesselation is upper bound of mesh size.
+        let (vertices, indices) = ShapeMesh::capacity(&shape, size, 1.0);
+        assert!(mesh.positions.len() <= vertices);
+        assert!(mesh.indices.len() <= indices);
+        mesh
+    };
+
+    let shape = serde_yaml::from_str::<ImageBoxShape>(
+        r#"
+        kind:
+          Rectangle:
+            corner_radius:
+              top_left: 10
+        "#,
+    )
+    .unwrap();
+    assert!(matches!(
+        shape.kind,
+        ImageBoxShapeKind::Rectangle { corner_radius } if corner_radius.top_left == 10.0
+            && corner_radius.bottom_right == 0.0
+    ));
+
+    let mesh = shape_mesh(ImageBoxShape {
+        color: fill,
+        border,
+        ..Default::default()
+    });
+    assert!((area(&mesh, fill) - 90.0 * 40.0).abs() < 1.0e-3);
+    assert!((area(&mesh, border.color) - (5000.0 - 90.0 * 40.0)).abs() < 1.0e-3);
+
+    let mesh = shape_mesh(ImageBoxShape {
+        kind: ImageBoxShapeKind::Rectangle {
+            corner_radius: 10.0.into(),
+        },
+        color: fill,
+        ..Default::default()
+    });
+    let expected = 5000.0 - 4.0 * (100.0 - PI * 25.0);
+    assert!((area(&mesh, fill) - expected).abs() < expected * 0.01);
+
+    let mesh = shape_mesh(ImageBoxShape {
+        kind: ImageBoxShapeKind::Ellipse,
+        color: fill,
+        ..Default::default()
+    });
+    let expected = PI * 50.0 * 25.0;
+    assert!((area(&mesh, fill) - expected).abs() < expected * 0.01);
+
+    // concave L shaped polygon.
+    let points = vec![
+        Vec2 { x: 0.0, y: 0.0 },
+        Vec2 { x: 1.0, y: 0.0 },
+        Vec2 { x: 1.0, y: 0.5 },
+        Vec2 { x: 0.5, y: 0.5 },
+        Vec2 { x: 0.5, y: 1.0 },
+        Vec2 { x: 0.0, y: 1.0 },
+    ];
+    let mesh = shape_mesh(ImageBoxShape {
+        kind: ImageBoxShapeKind::Polygon(points.to_owned()),
+        color: fill,
+        ..Default::default()
+    });
+    assert_eq!(mesh.indices.len(), 4 * 3);
+    assert!((area(&mesh, fill) - 5000.0 * 0.75).abs() < 1.0e-3);
+    let mesh = shape_mesh(ImageBoxShape {
+        kind: ImageBoxShapeKind::Polygon(points.into_iter().rev().collect()),
+        color: fill,
+        ..Default::default()
+    });
+    assert!((area(&mesh, fill) - 5000.0 * 0.75).abs() < 1.0e-3);
+
+    let mesh = shape_mesh(ImageBoxShape {
+        kind: ImageBoxShapeKind::Polyline(vec![Vec2 { x: 0.0, y: 0.5 }, Vec2 { x: 1.0, y: 0.5 }]),
+        border,
+        ..Default::default()
+    });
+    assert!((area(&mesh, border.color) - 100.0 * 5.0).abs() < 1.0e-3);
+
+    let mut application = Application::new();
+    application.setup(setup);
+    application.apply(
+        make_widget!(image_box)
+            .with_props(ImageBoxProps {
+                material: ImageBoxMaterial::Shape(ImageBoxShape {
+                    kind: ImageBoxShapeKind::Arc {
+                        start_angle: 0.0,
+                        end_angle: PI,
+                    },
+                    border,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .into(),
+    );
+    let mapping = CoordsMapping::new(Rect {
+        left: 0.0,
+        right: 100.0,
+        top: 0.0,
+        bottom: 100.0,
+    });
+    let mut layout_engine = DefaultLayoutEngine::default();
+    application.forced_process();
+    application.layout(&mapping, &mut layout_engine).unwrap();
+    let atlas_mapping = Default::default();
+    let image_sizes = Default::default();
+    let mut renderer = TesselateRenderer::new(
+        TesselationVerticesFormat::Separated,
+        (),
+        &atlas_mapping,
+        &image_sizes,
+    );
+    let tesselation = application.render(&mapping, &mut renderer).unwrap();
+    assert_eq!(tesselation.batches.len(), 1);
+    assert!(matches!(tesselation.batches[0], Batch::ColoredTriangles(_)));
+    let positions = tesselation.vertices.as_separated().unwrap().position;
+    assert!(positions
+        .iter()
+        .all(|p| p.x >= -1.0e-3 && p.x <= 100.001 && p.y >= 49.999 && p.y <= 100.001));
+}
+
+#[test]
+#[cfg(feature = "tesselate")]
+fn test_gradient_tesselation() {
+    use crate::renderer::tesselate::prelude::*;
+
+    let black = Color {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+    let white = Color {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+        a: 1.0,
+    };
+    let mut gradient = serde_yaml::from_str::<ImageBoxGradient>(
+        r#"
+        kind:
+          Radial: {}
+        stops:
+          - position: 0
+            color: { r: 0, g: 0, b: 0, a: 1 }
+          - position: 1
+            color: { r: 1, g: 1, b: 1, a: 1 }
+        "#,
+    )
+    .unwrap();
+    assert!(matches!(
+        gradient.kind,
+        ImageBoxGradientKind::Radial { center, radius } if center == Vec2 { x: 0.5, y: 0.5 }
+            && radius == 0.5
+    ));
+    let size = Vec2 { x: 100.0, y: 50.0 };
+    assert_eq!(gradient.color_at(Vec2 { x: 50.0, y: 25.0 }, size), black);
+    assert_eq!(gradient.color_at(Vec2 { x: 75.0, y: 25.0 }, size).r, 0.5);
+    assert_eq!(gradient.color_at(Vec2 { x: 100.0, y: 25.0 }, size), white);
+    assert_eq!(gradient.color_at(Vec2 { x: 100.0, y: 50.0 }, size), white);
+    assert_eq!(gradient.sample(-1.0), black);
+    assert_eq!(gradient.sample(2.0), white);
+
+    gradient.kind = ImageBoxGradientKind::Linear { angle: 0.0 };
+    assert_eq!(gradient.color_at(Vec2 { x: 0.0, y: 50.0 }, size), black);
+    assert_eq!(gradient.color_at(Vec2 { x: 25.0, y: 0.0 }, size).r, 0.25);
+    assert_eq!(gradient.color_at(Vec2 { x: 100.0, y: 0.0 }, size), white);
+
+    let render = |gradient: ImageBoxGradient| {
+        let mut application = Application::new();
+        application.setup(setup);
+        application.apply(
+            make_widget!(image_box)
+                .with_props(ImageBoxProps {
+                    material: ImageBoxMaterial::Gradient(gradient),
+                    ..Default::default()
+                })
+                .into(),
+        );
+        let mapping = CoordsMapping::new(Rect {
+            left: 0.0,
+            right: 100.0,
+            top: 0.0,
+            bottom: 50.0,
+        });
+        let mut layout_engine = DefaultLayoutEngine::default();
+        application.forced_process();
+        application.layout(&mapping, &mut layout_engine).unwrap();
+        let atlas_mapping = Default::default();
+        let image_sizes = Default::default();
+        let mut renderer = TesselateRenderer::new(
+            TesselationVerticesFormat::Separated,
+            (),
+            &atlas_mapping,
+            &image_sizes,
+        );
+        let tesselation = application.render(&mapping, &mut renderer).unwrap();
+        assert_eq!(tesselation.batches.len(), 1);
+        assert!(matches!(tesselation.batches[0], Batch::ColoredTriangles(_)));
+        tesselation
+    };
+
+    let tesselation = render(ImageBoxGradient {
+        scaling: ImageBoxImageScaling::Frame((10.0, true).into()),
+        ..gradient.clone()
+    });
+    let vertices = tesselation.vertices.as_separated().unwrap();
+    // frame lines split box into 3 columns and 3 rows of cells, axis aligned linear gradient
+    // needs no other lines.
+    assert_eq!(vertices.position.len(), 4 * 4);
+    assert_eq!(tesselation.indices.len(), (3 * 3 - 1) * 6);
+    for (position, color) in vertices.position.iter().zip(vertices.color.iter()) {
+        assert!((color.r - position.x / 100.0).abs() < 1.0e-4);
+    }
+
+    // grid lines at stops keep colors exact.
+    gradient.stops.insert(
+        1,
+        ImageBoxGradientStop {
+            position: 0.3,
+            color: white,
+        },
+    );
+    let tesselation = render(gradient.clone());
+    let vertices = tesselation.vertices.as_separated().unwrap();
+    assert_eq!(vertices.position.len(), 3 * 2);
+    assert!((vertices.position[1].x - 30.0).abs() < 1.0e-4);
+    assert_eq!(vertices.color[1], white);
+
+    // radial gradient cells are at most 16 pixels wide.
+    gradient.kind = ImageBoxGradientKind::Radial {
+        center: Vec2 { x: 0.5, y: 0.5 },
+        radius: 0.5,
+    };
+    let tesselation = render(gradient);
+    let vertices = tesselation.vertices.as_separated().unwrap();
+    assert_eq!(vertices.position.len(), 8 * 5);
 }